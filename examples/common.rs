@@ -0,0 +1,1005 @@
+//! Helpers shared by the examples.
+
+// Each example uses only some of these helpers
+#![allow(dead_code)]
+
+use clap::{Args, ValueEnum};
+use ethers::{
+    abi::{Abi, Detokenize, Token},
+    contract::{ContractCall, ContractError, LogMeta},
+    providers::{JsonRpcClient, Middleware, PendingTransaction, Provider, StreamExt},
+    signers::Signer,
+    types::{
+        transaction::eip2718::TypedTransaction, Address, BlockNumber, Eip1559TransactionRequest,
+        TransactionReceipt, H256, I256, U256,
+    },
+    utils::to_checksum,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use stylus_hello_world::bindings::{
+    erc_20::TransferFilter, faucet::FAUCET_ABI, nft::ERC721_ABI, vault::ERC4626_ABI,
+    vesting::VESTING_ABI, wrapper::WRAPPER_ABI, Erc20, CAPPEDERC20_ABI, FEEERC20_ABI,
+    VOTESERC20_ABI, WETH_ABI,
+};
+use stylus_hello_world::client::{
+    chain_name, check_gas_price, parse_checksummed, read_wallet_from_file, Client,
+};
+use stylus_hello_world::signer::{read_keystore, read_mnemonic, ClientSigner};
+use tracing::{debug, info, Instrument};
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, EnvFilter};
+
+/// Logging settings. `RUST_LOG` picks what is logged, e.g. `RUST_LOG=debug`.
+#[derive(Debug, Args)]
+pub struct LogArgs {
+    /// Logs warnings and errors only, whatever `RUST_LOG` says.
+    #[arg(long, short)]
+    pub quiet: bool,
+
+    /// Logs one JSON object per line, for log collectors.
+    #[arg(long)]
+    pub log_json: bool,
+}
+
+impl LogArgs {
+    /// Installs the global tracing subscriber, logging to stdout.
+    pub fn init(&self) {
+        self.init_to(OutputFormat::Text);
+    }
+
+    /// Like [`init`](Self::init), but logs to stderr under `--output json`,
+    /// leaving stdout to the JSON result.
+    pub fn init_to(&self, output: OutputFormat) {
+        let filter = match self.quiet {
+            true => EnvFilter::new("warn"),
+            false => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        };
+        let writer = match output {
+            OutputFormat::Text => BoxMakeWriter::new(std::io::stdout),
+            OutputFormat::Json => BoxMakeWriter::new(std::io::stderr),
+        };
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(writer);
+        match self.log_json {
+            true => subscriber.json().init(),
+            false => subscriber.init(),
+        }
+    }
+}
+
+/// How an example reports its results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Log lines for humans
+    #[default]
+    Text,
+    /// A single JSON object on stdout, for scripts; logs go to stderr
+    Json,
+}
+
+/// What the erc20 example found, printed under `--output json`. Amounts
+/// are decimal strings of base units, so scripts needn't handle big numbers.
+#[derive(Debug, Default, Serialize)]
+pub struct Erc20Report {
+    pub decimals: Option<u8>,
+    /// `sum`'s total and the tag it came with, unless it reverted
+    pub sum: Option<String>,
+    pub sum_tag: Option<String>,
+    /// The last transaction the run sent, if any
+    pub tx_hash: Option<H256>,
+    pub gas_used: Option<String>,
+}
+
+impl Erc20Report {
+    /// Records the transaction of `receipt` as the run's latest.
+    pub fn record_tx(&mut self, receipt: &TransactionReceipt) {
+        self.tx_hash = Some(receipt.transaction_hash);
+        self.gas_used = receipt.gas_used.map(|gas| gas.to_string());
+    }
+}
+
+/// The JSON object `--output json` prints for a run's `result`: the report
+/// itself, or `{"error": "..."}` with the whole error chain.
+pub fn json_output<T: Serialize>(result: &eyre::Result<T>) -> serde_json::Value {
+    match result {
+        Ok(report) => serde_json::to_value(report).expect("reports serialize"),
+        Err(err) => serde_json::json!({ "error": format!("{err:#}") }),
+    }
+}
+
+/// Connection settings for a deployed program. Every flag falls back to its env var.
+#[derive(Debug, Args)]
+pub struct ExampleArgs {
+    /// Where the signing key comes from.
+    #[arg(long, env = "SIGNER_TYPE", value_enum, default_value_t = SignerType::File)]
+    pub signer_type: SignerType,
+
+    /// Your private key file path, for `--signer-type file`.
+    #[arg(long, env = "PRIV_KEY_PATH")]
+    pub priv_key_path: Option<String>,
+
+    /// Keystore JSON file path, for `--signer-type keystore`. Its password is
+    /// read from KEYSTORE_PASSWORD.
+    #[arg(long, env = "KEYSTORE_PATH")]
+    pub keystore_path: Option<String>,
+
+    /// BIP-39 mnemonic file path, for `--signer-type mnemonic`.
+    #[arg(long, env = "MNEMONIC_PATH")]
+    pub mnemonic_path: Option<String>,
+
+    /// Which account of the mnemonic to use, i.e. the last component of the
+    /// HD path `m/44'/60'/0'/0/<index>`.
+    #[arg(long, env = "HD_INDEX", default_value_t = 0)]
+    pub hd_index: u32,
+
+    /// AWS KMS key id, for `--signer-type kms`.
+    #[arg(long, env = "KMS_KEY_ID")]
+    pub kms_key_id: Option<String>,
+
+    /// TOML file with defaults for the connection settings, see
+    /// `examples/config.example.toml`. Flags and env vars override it.
+    #[arg(long, env = "STYLUS_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// The `[networks.<profile>]` table of `--config` to use.
+    #[arg(long, env = "STYLUS_PROFILE", requires = "config")]
+    pub profile: Option<String>,
+
+    /// Stylus RPC endpoint url.
+    #[arg(long, env = "RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// The chain the RPC endpoint must be on; defaults to whichever it reports.
+    #[arg(long, env = "CHAIN_ID")]
+    pub chain_id: Option<u64>,
+
+    /// Deployed program address, or its name in `--address-book`.
+    #[arg(long, env = "STYLUS_PROGRAM_ADDRESS")]
+    pub program_address: Option<String>,
+
+    /// TOML file mapping short names such as `weth` to addresses, which
+    /// address flags then accept in place of the hex.
+    #[arg(long, env = "ADDRESS_BOOK")]
+    pub address_book: Option<PathBuf>,
+
+    /// Retries for RPC requests that fail transiently.
+    #[arg(long, env = "RPC_RETRIES", default_value_t = 3)]
+    pub retries: u32,
+
+    /// Delay before the first retry in milliseconds, doubled on each retry.
+    #[arg(long, env = "RPC_RETRY_DELAY_MS", default_value_t = 500)]
+    pub retry_delay_ms: u64,
+
+    /// Simulates state-changing calls with eth_call instead of broadcasting them.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Blocks to wait for after a transaction is mined, itself included.
+    #[arg(long, env = "CONFIRMATIONS", default_value_t = 1)]
+    pub confirmations: usize,
+
+    /// Seconds to wait for a sent transaction's confirmations before giving up on it.
+    #[arg(long, env = "TX_TIMEOUT_SECS", default_value_t = 300)]
+    pub tx_timeout_secs: u64,
+}
+
+/// How long [`confirm`] waits on a sent transaction: for `confirmations`
+/// blocks, itself included, but no longer than `timeout`.
+#[derive(Clone, Copy, Debug)]
+pub struct TxWait {
+    pub confirmations: usize,
+    pub timeout: Duration,
+}
+
+/// Connection settings read from a `--config` file. Every field is optional,
+/// and flags and env vars take precedence over it.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Profile {
+    pub rpc_url: Option<String>,
+    #[serde(default, deserialize_with = "checksummed")]
+    pub program_address: Option<Address>,
+    #[serde(default, deserialize_with = "checksummed")]
+    pub helper_address: Option<Address>,
+    pub priv_key_path: Option<String>,
+}
+
+/// A `--config` file: top-level defaults, plus named profiles such as
+/// `[networks.stylus-testnet]` that override them.
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    #[serde(flatten)]
+    pub defaults: Profile,
+    #[serde(default)]
+    pub networks: BTreeMap<String, Profile>,
+}
+
+fn checksummed<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Address>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|input| parse_checksummed(&input).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+impl Config {
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| eyre::eyre!("could not read {}: {err}", path.display()))?;
+        Self::from_toml(&contents)
+    }
+
+    pub fn from_toml(contents: &str) -> eyre::Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    /// The defaults, overridden by the `name` profile's values if given.
+    pub fn profile(&self, name: Option<&str>) -> eyre::Result<Profile> {
+        let Some(name) = name else {
+            return Ok(self.defaults.clone());
+        };
+        let Some(profile) = self.networks.get(name) else {
+            let known: Vec<_> = self.networks.keys().map(String::as_str).collect();
+            eyre::bail!("no profile {name:?} in the config, it has {known:?}");
+        };
+        let defaults = self.defaults.clone();
+        Ok(Profile {
+            rpc_url: profile.rpc_url.clone().or(defaults.rpc_url),
+            program_address: profile.program_address.or(defaults.program_address),
+            helper_address: profile.helper_address.or(defaults.helper_address),
+            priv_key_path: profile.priv_key_path.clone().or(defaults.priv_key_path),
+        })
+    }
+}
+
+/// An `--address-book` file: a flat table of names to addresses, e.g.
+/// `weth = "0xC4CA13280b8EafD7A033670E620B1AF74950E147"`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AddressBook(BTreeMap<String, Address>);
+
+impl AddressBook {
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| eyre::eyre!("could not read {}: {err}", path.display()))?;
+        Self::from_toml(&contents)
+    }
+
+    pub fn from_toml(contents: &str) -> eyre::Result<Self> {
+        let entries: BTreeMap<String, String> = toml::from_str(contents)?;
+        let book = entries.into_iter().map(|(name, input)| {
+            let address = parse_checksummed(&input)
+                .map_err(|err| eyre::eyre!("address book entry {name:?}: {err}"))?;
+            Ok((name, address))
+        });
+        Ok(Self(book.collect::<eyre::Result<_>>()?))
+    }
+
+    /// The address `input` names in the book, or else `input` parsed as an address.
+    pub fn resolve(&self, input: &str) -> eyre::Result<Address> {
+        if let Some(address) = self.0.get(input) {
+            return Ok(*address);
+        }
+        parse_checksummed(input).map_err(|err| match self.0.is_empty() {
+            true => eyre::eyre!("{err}"),
+            false => eyre::eyre!("{err}, and not a name in the address book"),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SignerType {
+    /// A hex private key in a file
+    File,
+    /// An encrypted JSON keystore
+    Keystore,
+    /// An account derived from a BIP-39 mnemonic in a file
+    Mnemonic,
+    /// A key held in AWS KMS, needs the `kms` feature
+    Kms,
+}
+
+/// EIP-1559 fee caps for sent transactions; the provider's estimates fill in
+/// whichever is left out.
+#[derive(Debug, Args)]
+pub struct FeeArgs {
+    /// Most wei per gas, in gwei, the transaction may pay in total.
+    #[arg(long, value_parser = parse_gwei)]
+    pub max_fee_per_gas: Option<U256>,
+
+    /// Most wei per gas, in gwei, the transaction may tip the sequencer.
+    #[arg(long, value_parser = parse_gwei)]
+    pub max_priority_fee_per_gas: Option<U256>,
+
+    /// Aborts instead of sending a transaction while the node quotes a gas
+    /// price above this, in gwei.
+    #[arg(long, value_parser = parse_gwei)]
+    pub max_gas_price: Option<U256>,
+}
+
+fn parse_gwei(input: &str) -> eyre::Result<U256> {
+    parse_units(input, 9)
+}
+
+impl FeeArgs {
+    /// Rejects a priority fee above the max fee, which no node would accept.
+    pub fn validate(&self) -> eyre::Result<()> {
+        if let (Some(max_fee), Some(priority_fee)) =
+            (self.max_fee_per_gas, self.max_priority_fee_per_gas)
+        {
+            eyre::ensure!(
+                priority_fee <= max_fee,
+                "--max-priority-fee-per-gas ({}) exceeds --max-fee-per-gas ({})",
+                format_units(priority_fee, 9),
+                format_units(max_fee, 9)
+            );
+        }
+        Ok(())
+    }
+
+    /// Fails if the node quotes a gas price above `--max-gas-price`, if set.
+    pub async fn check_gas_price<P: JsonRpcClient>(
+        &self,
+        provider: &Provider<P>,
+    ) -> eyre::Result<()> {
+        if let Some(max) = self.max_gas_price {
+            let price = check_gas_price(provider, max).await?;
+            info!(gas_price_gwei = %format_units(price, 9), "gas price within --max-gas-price");
+        }
+        Ok(())
+    }
+
+    /// Makes `call` an EIP-1559 transaction with these fee caps, if any are set.
+    pub fn apply<M: Middleware, D: Detokenize>(
+        &self,
+        mut call: ContractCall<M, D>,
+    ) -> eyre::Result<ContractCall<M, D>> {
+        self.validate()?;
+        if self.max_fee_per_gas.is_none() && self.max_priority_fee_per_gas.is_none() {
+            return Ok(call);
+        }
+        let mut tx = match call.tx {
+            TypedTransaction::Eip1559(tx) => tx,
+            tx => Eip1559TransactionRequest {
+                from: tx.from().copied(),
+                to: tx.to().cloned(),
+                gas: tx.gas().copied(),
+                value: tx.value().copied(),
+                data: tx.data().cloned(),
+                nonce: tx.nonce().copied(),
+                ..Default::default()
+            },
+        };
+        tx.max_fee_per_gas = self.max_fee_per_gas.or(tx.max_fee_per_gas);
+        tx.max_priority_fee_per_gas = self
+            .max_priority_fee_per_gas
+            .or(tx.max_priority_fee_per_gas);
+        call.tx = tx.into();
+        Ok(call)
+    }
+}
+
+/// Prints the gas and fee fields a mined transaction actually used.
+pub async fn log_gas_fields<M: Middleware + 'static>(
+    client: &M,
+    receipt: &TransactionReceipt,
+) -> eyre::Result<()> {
+    let tx = client
+        .get_transaction(receipt.transaction_hash)
+        .await?
+        .ok_or_else(|| eyre::eyre!("transaction {:?} not found", receipt.transaction_hash))?;
+    let gwei = |value: Option<U256>| value.map_or("-".into(), |v| format_units(v, 9));
+    info!(
+        tx_hash = ?receipt.transaction_hash,
+        gas_used = ?receipt.gas_used,
+        gas_limit = %tx.gas,
+        effective_gas_price_gwei = %gwei(receipt.effective_gas_price),
+        max_fee_per_gas_gwei = %gwei(tx.max_fee_per_gas),
+        max_priority_fee_per_gas_gwei = %gwei(tx.max_priority_fee_per_gas),
+        "gas fields"
+    );
+    Ok(())
+}
+
+impl ExampleArgs {
+    /// Fills in the settings neither a flag nor an env var gave from the
+    /// `--config` file, if any. Returns the selected profile for the
+    /// example-specific settings it holds.
+    pub fn load_config(&mut self) -> eyre::Result<Profile> {
+        let Some(path) = &self.config else {
+            return Ok(Profile::default());
+        };
+        let profile = Config::read(path)?.profile(self.profile.as_deref())?;
+        self.rpc_url = self.rpc_url.take().or(profile.rpc_url.clone());
+        let configured = profile.program_address.map(|a| to_checksum(&a, None));
+        self.program_address = self.program_address.take().or(configured);
+        self.priv_key_path = self.priv_key_path.take().or(profile.priv_key_path.clone());
+        Ok(profile)
+    }
+
+    pub fn rpc_url(&self) -> eyre::Result<String> {
+        self.rpc_url
+            .clone()
+            .ok_or_else(|| eyre::eyre!("set --rpc-url, RPC_URL or rpc_url in --config"))
+    }
+
+    /// The `--address-book`, empty if none was given.
+    pub fn address_book(&self) -> eyre::Result<AddressBook> {
+        match &self.address_book {
+            Some(path) => AddressBook::read(path),
+            None => Ok(AddressBook::default()),
+        }
+    }
+
+    pub fn program_address(&self) -> eyre::Result<Address> {
+        let input = self.program_address.as_deref().ok_or_else(|| {
+            eyre::eyre!(
+                "set --program-address, STYLUS_PROGRAM_ADDRESS or program_address in --config"
+            )
+        })?;
+        self.address_book()?.resolve(input)
+    }
+
+    /// Loads the signer `--signer-type` selects.
+    pub async fn signer(&self) -> eyre::Result<ClientSigner> {
+        let required = |value: &Option<String>, flag: &str| {
+            value
+                .clone()
+                .ok_or_else(|| eyre::eyre!("--signer-type {:?} needs --{flag}", self.signer_type))
+        };
+        match self.signer_type {
+            SignerType::File => {
+                let path = required(&self.priv_key_path, "priv-key-path")?;
+                Ok(read_wallet_from_file(&path)?.into())
+            }
+            SignerType::Keystore => {
+                let path = required(&self.keystore_path, "keystore-path")?;
+                let password = std::env::var("KEYSTORE_PASSWORD")
+                    .map_err(|_| eyre::eyre!("KEYSTORE_PASSWORD is not set"))?;
+                Ok(read_keystore(&path, &password)?.into())
+            }
+            SignerType::Mnemonic => {
+                let path = required(&self.mnemonic_path, "mnemonic-path")?;
+                Ok(read_mnemonic(&path, self.hd_index)?.into())
+            }
+            #[cfg(feature = "kms")]
+            SignerType::Kms => {
+                let key_id = required(&self.kms_key_id, "kms-key-id")?;
+                Ok(stylus_hello_world::signer::kms_signer(&key_id)
+                    .await?
+                    .into())
+            }
+            #[cfg(not(feature = "kms"))]
+            SignerType::Kms => eyre::bail!("--signer-type kms needs the kms feature"),
+        }
+    }
+
+    /// The `--confirmations` and `--tx-timeout-secs` to wait on transactions with.
+    pub fn tx_wait(&self) -> TxWait {
+        TxWait {
+            confirmations: self.confirmations,
+            timeout: Duration::from_secs(self.tx_timeout_secs),
+        }
+    }
+
+    pub fn print_mode(&self) {
+        match self.dry_run {
+            true => info!(dry_run = true, "no transactions will be broadcast"),
+            false => info!(dry_run = false, "transactions will be broadcast"),
+        }
+    }
+}
+
+/// Logs the chain `client` signs for, by name when it is a known Arbitrum chain.
+pub fn log_chain(client: &Client) {
+    let chain_id = client.signer().chain_id();
+    let network = chain_name(chain_id).unwrap_or("unknown");
+    info!(chain_id, network, "connected");
+}
+
+/// Fails unless `provider` answers `net_version` and `eth_blockNumber` and
+/// has a block past genesis. A node still at block zero is unsynced, or is
+/// a chain nothing can have been deployed to yet.
+pub async fn check_provider<P: JsonRpcClient>(provider: &Provider<P>) -> eyre::Result<()> {
+    let network = provider
+        .get_net_version()
+        .await
+        .map_err(|err| eyre::eyre!("RPC endpoint is unreachable: {err}"))?;
+    let block = provider
+        .get_block_number()
+        .await
+        .map_err(|err| eyre::eyre!("RPC endpoint did not report its block number: {err}"))?;
+    eyre::ensure!(
+        !block.is_zero(),
+        "RPC endpoint (network {network}) is at block 0; is the node synced?"
+    );
+    debug!(network, %block, "provider is healthy");
+    Ok(())
+}
+
+/// Estimates the gas for `call` and logs it. Stylus gas costs differ from the
+/// EVM's, so this is handy for budgeting. A call that would revert surfaces its
+/// revert reason, as a string when it is an `Error(string)` and as hex otherwise.
+pub async fn estimate_and_log<M: Middleware, D: Detokenize>(
+    call: &ContractCall<M, D>,
+) -> eyre::Result<U256> {
+    match call.estimate_gas().await {
+        Ok(gas) => {
+            info!(call = %call.function.name, %gas, "estimated gas");
+            Ok(gas)
+        }
+        Err(err) => match decode_revert(&err) {
+            Some(reason) => eyre::bail!("gas estimation reverted: {reason}"),
+            None => eyre::bail!("gas estimation failed: {err}"),
+        },
+    }
+}
+
+/// Renders the revert in `err` for humans: a custom error of any program in
+/// this crate as `Name(param: value, ...)`, then an `Error(string)` as its
+/// message, then anything else as hex. `None` if `err` is not a revert.
+pub fn decode_revert<M: Middleware>(err: &ContractError<M>) -> Option<String> {
+    let data = err.as_revert()?;
+    let abis: [&Abi; 9] = [
+        &WETH_ABI,
+        &CAPPEDERC20_ABI,
+        &FEEERC20_ABI,
+        &ERC4626_ABI,
+        &ERC721_ABI,
+        &VOTESERC20_ABI,
+        &VESTING_ABI,
+        &FAUCET_ABI,
+        &WRAPPER_ABI,
+    ];
+    if let Some((selector, params)) = data.split_first_chunk::<4>() {
+        let known = abis.iter().flat_map(|abi| abi.errors());
+        for error in known.filter(|error| error.signature()[..4] == selector[..]) {
+            let Ok(tokens) = error.decode(params) else {
+                continue;
+            };
+            let fields: Vec<_> = error
+                .inputs
+                .iter()
+                .zip(&tokens)
+                .map(|(input, token)| format!("{}: {}", input.name, render_token(token)))
+                .collect();
+            return Some(format!("{}({})", error.name, fields.join(", ")));
+        }
+    }
+    if let Some(reason) = err.decode_revert::<String>() {
+        return Some(reason);
+    }
+    Some(format!("unknown error {data}"))
+}
+
+// Numbers in decimal and bytes as 0x-prefixed hex, unlike Token's Display
+fn render_token(token: &Token) -> String {
+    let list = |tokens: &[Token]| -> Vec<String> { tokens.iter().map(render_token).collect() };
+    match token {
+        Token::Address(address) => format!("{address:?}"),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => format!("{value:?}"),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Array(tokens) | Token::FixedArray(tokens) => {
+            format!("[{}]", list(tokens).join(", "))
+        }
+        Token::Tuple(tokens) => format!("({})", list(tokens).join(", ")),
+    }
+}
+
+/// Turns a failed call into a report naming its revert, if it was one.
+pub fn revert_report<M: Middleware + 'static>(err: ContractError<M>) -> eyre::Report {
+    match decode_revert(&err) {
+        Some(reason) => eyre::eyre!("call reverted: {reason}"),
+        None => err.into(),
+    }
+}
+
+/// Parses an `--at-block` tag: a block number, `latest` or `pending`.
+pub fn parse_block_tag(input: &str) -> eyre::Result<BlockNumber> {
+    match input {
+        "latest" => Ok(BlockNumber::Latest),
+        "pending" => Ok(BlockNumber::Pending),
+        number => number
+            .parse::<u64>()
+            .map(BlockNumber::from)
+            .map_err(|_| eyre::eyre!("{input:?} is not a block number, latest or pending")),
+    }
+}
+
+/// Runs the read-only `call` against the state at `block`, if given, instead
+/// of the latest.
+pub fn pin_to_block<M: Middleware, D: Detokenize>(
+    call: ContractCall<M, D>,
+    block: Option<BlockNumber>,
+) -> ContractCall<M, D> {
+    match block {
+        Some(block) => call.block(block),
+        None => call,
+    }
+}
+
+/// Waits for `pending` to be mined and confirmed, failing if it was dropped
+/// or reverted, and logs where it landed. Past `wait.timeout` it gives up,
+/// naming the transaction so it can be followed up on by hand.
+pub async fn confirm<P: JsonRpcClient>(
+    pending: PendingTransaction<'_, P>,
+    wait: TxWait,
+) -> eyre::Result<TransactionReceipt> {
+    let hash = pending.tx_hash();
+    let receipt = tokio::time::timeout(wait.timeout, pending.confirmations(wait.confirmations))
+        .await
+        .map_err(|_| {
+            eyre::eyre!(
+                "transaction {hash:?} not mined within {} seconds",
+                wait.timeout.as_secs()
+            )
+        })??
+        .ok_or_else(|| eyre::eyre!("transaction {hash:?} was dropped"))?;
+    info!(
+        tx_hash = ?hash,
+        block = %receipt.block_number.unwrap_or_default(),
+        gas_used = %receipt.gas_used.unwrap_or_default(),
+        "transaction mined"
+    );
+    eyre::ensure!(
+        receipt.status == Some(1.into()),
+        "transaction {hash:?} reverted"
+    );
+    Ok(receipt)
+}
+
+/// Sends `call` and waits for its confirmed receipt. In a dry run it is only
+/// estimated and simulated with eth_call instead, and `None` is returned.
+pub async fn send_or_simulate<M: Middleware + 'static, D: Detokenize + Debug>(
+    call: ContractCall<M, D>,
+    dry_run: bool,
+    wait: TxWait,
+) -> eyre::Result<Option<TransactionReceipt>> {
+    let span = tracing::info_span!("send", call = %call.function.name);
+    async move {
+        if dry_run {
+            estimate_and_log(&call).await?;
+            let result = call.call().await.map_err(revert_report)?;
+            info!(?result, "dry run: simulated, not broadcast");
+            return Ok(None);
+        }
+        Ok(Some(safe_send(call, wait).await?))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Simulates `call` with eth_call and, only if that succeeds, sends it and
+/// waits for its confirmed receipt. A predicted revert fails with its
+/// decoded reason before anything is broadcast, so it costs no gas.
+pub async fn safe_send<M: Middleware + 'static, D: Detokenize>(
+    call: ContractCall<M, D>,
+    wait: TxWait,
+) -> eyre::Result<TransactionReceipt> {
+    call.call().await.map_err(revert_report)?;
+    let pending = call.send().await.map_err(revert_report)?;
+    confirm(pending, wait).await
+}
+
+/// Fails early, without spending gas, if `token.transfer(to, amount)` sent by
+/// `from` would revert for lack of balance.
+pub async fn preflight_transfer<M: Middleware + 'static>(
+    client: Arc<M>,
+    token: Address,
+    from: Address,
+    to: Address,
+    amount: Amount,
+) -> eyre::Result<()> {
+    eyre::ensure!(!to.is_zero(), "cannot transfer to the zero address");
+    let balance = Erc20::new(token, client).balance_of(from).call().await?;
+    let balance = Amount::from_base_units(balance, amount.decimals());
+    eyre::ensure!(
+        balance.base_units() >= amount.base_units(),
+        "{from:?} holds {balance} but the transfer needs {amount}"
+    );
+    Ok(())
+}
+
+/// Like [`preflight_transfer`] for `token.transferFrom(from, to, amount)`
+/// sent by `spender`, also checking that `from` approved enough.
+pub async fn preflight_transfer_from<M: Middleware + 'static>(
+    client: Arc<M>,
+    token: Address,
+    spender: Address,
+    from: Address,
+    to: Address,
+    amount: Amount,
+) -> eyre::Result<()> {
+    let allowance = Erc20::new(token, client.clone())
+        .allowance(from, spender)
+        .call()
+        .await?;
+    let allowance = Amount::from_base_units(allowance, amount.decimals());
+    eyre::ensure!(
+        allowance.base_units() >= amount.base_units(),
+        "{from:?} allows {spender:?} {allowance} but the transfer needs {amount}"
+    );
+    preflight_transfer(client, token, from, to, amount).await
+}
+
+/// Makes sure `spender` may spend `needed` of `token` held by the token
+/// client's sender. Short of that, fails like [`preflight_transfer_from`]
+/// unless `approve` is set, in which case it first approves `spender` for
+/// that much, at least `needed`, and waits for the approval's receipt, which
+/// it returns. The approval goes through [`send_or_simulate`], so a predicted
+/// revert broadcasts nothing and a dry run only simulates it.
+pub async fn ensure_allowance<M: Middleware + 'static>(
+    token: &Erc20<M>,
+    spender: Address,
+    needed: U256,
+    approve: Option<U256>,
+    fees: &FeeArgs,
+    dry_run: bool,
+    wait: TxWait,
+) -> eyre::Result<Option<TransactionReceipt>> {
+    let owner = token
+        .client()
+        .default_sender()
+        .ok_or_else(|| eyre::eyre!("the token client has no sender to approve from"))?;
+    let allowance = token.allowance(owner, spender).call().await?;
+    if allowance >= needed {
+        return Ok(None);
+    }
+    let Some(amount) = approve else {
+        eyre::bail!("{owner:?} allows {spender:?} {allowance} but the transfer needs {needed}");
+    };
+    eyre::ensure!(
+        amount >= needed,
+        "approving {amount} would still leave {spender:?} short of {needed}"
+    );
+    info!(%allowance, %amount, ?spender, "allowance too low, approving first");
+    if !dry_run {
+        fees.check_gas_price(token.client().provider()).await?;
+    }
+    let call = fees.apply(token.approve(spender, amount))?;
+    send_or_simulate(call, dry_run, wait).await
+}
+
+/// The `Transfer` events `token` emitted from block `from` through `to`, in
+/// order. A range without any is an empty list, not an error.
+pub async fn transfers_in_range<M: Middleware + 'static>(
+    token: &Erc20<M>,
+    from: u64,
+    to: u64,
+) -> eyre::Result<Vec<(TransferFilter, LogMeta)>> {
+    let transfers = token
+        .transfer_filter()
+        .from_block(from)
+        .to_block(to)
+        .query_with_meta()
+        .await?;
+    Ok(transfers)
+}
+
+/// Caches a token's `balanceOf`, `allowance` and `decimals` reads for `ttl`,
+/// for clients that poll the same accounts. [`CachedReader::watch`] drops a
+/// cached balance or allowance as soon as a `Transfer` may have changed it.
+pub struct CachedReader<M> {
+    token: Erc20<M>,
+    ttl: Duration,
+    decimals: Mutex<Option<(u8, Instant)>>,
+    balances: Mutex<HashMap<Address, (U256, Instant)>>,
+    allowances: Mutex<HashMap<(Address, Address), (U256, Instant)>>,
+}
+
+// The cached value, if it is younger than `ttl`
+fn fresh<T: Copy>(entry: Option<&(T, Instant)>, ttl: Duration) -> Option<T> {
+    entry
+        .filter(|(_, fetched)| fetched.elapsed() < ttl)
+        .map(|(value, _)| *value)
+}
+
+impl<M: Middleware + 'static> CachedReader<M> {
+    pub fn new(token: Erc20<M>, ttl: Duration) -> Self {
+        Self {
+            token,
+            ttl,
+            decimals: Mutex::new(None),
+            balances: Mutex::new(HashMap::new()),
+            allowances: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_balance(&self, account: Address) -> eyre::Result<U256> {
+        if let Some(balance) = fresh(self.balances.lock().unwrap().get(&account), self.ttl) {
+            return Ok(balance);
+        }
+        let balance = self.token.balance_of(account).call().await?;
+        let entry = (balance, Instant::now());
+        self.balances.lock().unwrap().insert(account, entry);
+        Ok(balance)
+    }
+
+    pub async fn get_allowance(&self, owner: Address, spender: Address) -> eyre::Result<U256> {
+        let key = (owner, spender);
+        if let Some(allowance) = fresh(self.allowances.lock().unwrap().get(&key), self.ttl) {
+            return Ok(allowance);
+        }
+        let allowance = self.token.allowance(owner, spender).call().await?;
+        let entry = (allowance, Instant::now());
+        self.allowances.lock().unwrap().insert(key, entry);
+        Ok(allowance)
+    }
+
+    pub async fn get_decimals(&self) -> eyre::Result<u8> {
+        if let Some(decimals) = fresh(self.decimals.lock().unwrap().as_ref(), self.ttl) {
+            return Ok(decimals);
+        }
+        let decimals = self.token.decimals().call().await?;
+        *self.decimals.lock().unwrap() = Some((decimals, Instant::now()));
+        Ok(decimals)
+    }
+
+    /// Drops what `transfer` may have changed: both parties' balances, and
+    /// every allowance of the sender, since a transferFrom logs no spender.
+    pub fn invalidate(&self, transfer: &TransferFilter) {
+        let mut balances = self.balances.lock().unwrap();
+        balances.remove(&transfer.from);
+        balances.remove(&transfer.to);
+        self.allowances
+            .lock()
+            .unwrap()
+            .retain(|(owner, _), _| *owner != transfer.from);
+    }
+
+    /// Streams the token's `Transfer` events, invalidating entries as they
+    /// arrive, until the stream fails. Run it beside the reads, e.g. in a
+    /// task holding an `Arc` of the reader.
+    pub async fn watch(&self) -> eyre::Result<()> {
+        let events = self.token.transfer_filter();
+        let mut stream = events.stream().await?;
+        while let Some(transfer) = stream.next().await {
+            let transfer = transfer?;
+            debug!(from = ?transfer.from, to = ?transfer.to, "transfer, invalidating");
+            self.invalidate(&transfer);
+        }
+        Ok(())
+    }
+}
+
+/// Which way a watched balance went past its threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Crossing {
+    /// From below the threshold to at or above it.
+    Up,
+    /// From at or above the threshold to below it.
+    Down,
+}
+
+/// A balance reading that differs from the one before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+    /// The last reading, `None` for the first.
+    pub previous: Option<U256>,
+    pub balance: U256,
+    /// Set when this reading went past the threshold. The first reading
+    /// never crosses, as there is nothing to cross from.
+    pub crossed: Option<Crossing>,
+}
+
+/// Compares an account's balance readings, reporting only those that
+/// changed and any that crossed `threshold`.
+#[derive(Debug, Default)]
+pub struct BalanceWatch {
+    threshold: Option<U256>,
+    last: Option<U256>,
+}
+
+impl BalanceWatch {
+    pub fn new(threshold: Option<U256>) -> Self {
+        Self {
+            threshold,
+            last: None,
+        }
+    }
+
+    /// Records `balance`, returning the change unless it equals the last reading.
+    pub fn observe(&mut self, balance: U256) -> Option<BalanceChange> {
+        let previous = self.last.replace(balance);
+        if previous == Some(balance) {
+            return None;
+        }
+        let crossed = match (previous, self.threshold) {
+            (Some(previous), Some(threshold)) if previous < threshold && balance >= threshold => {
+                Some(Crossing::Up)
+            }
+            (Some(previous), Some(threshold)) if previous >= threshold && balance < threshold => {
+                Some(Crossing::Down)
+            }
+            _ => None,
+        };
+        Some(BalanceChange {
+            previous,
+            balance,
+            crossed,
+        })
+    }
+}
+
+/// A token amount, kept in base units along with the token's decimals so
+/// whole tokens and base units can't be mixed up. Displays as both, e.g.
+/// `1.5 (1500000000000000000 base units)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Amount {
+    base_units: U256,
+    decimals: u8,
+}
+
+impl Amount {
+    /// Parses whole tokens such as `"1.5"`, see [`parse_units`].
+    pub fn from_tokens(value: &str, decimals: u8) -> eyre::Result<Self> {
+        Ok(Self::from_base_units(
+            parse_units(value, decimals)?,
+            decimals,
+        ))
+    }
+
+    pub fn from_base_units(base_units: U256, decimals: u8) -> Self {
+        Self {
+            base_units,
+            decimals,
+        }
+    }
+
+    /// What the token's functions take and return.
+    pub fn base_units(&self) -> U256 {
+        self.base_units
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// The amount in whole tokens, see [`format_units`].
+    pub fn tokens(&self) -> String {
+        format_units(self.base_units, self.decimals)
+    }
+}
+
+impl std::fmt::Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({} base units)", self.tokens(), self.base_units)
+    }
+}
+
+/// Formats a base-unit `value` as a decimal with `decimals` fractional digits,
+/// dropping trailing zeros, e.g. `1500000000000000000` with 18 is `"1.5"`.
+pub fn format_units(value: U256, decimals: u8) -> String {
+    let digits = value.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+    let digits = format!("{digits:0>width$}", width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    match fraction.trim_end_matches('0') {
+        "" => whole.to_string(),
+        fraction => format!("{whole}.{fraction}"),
+    }
+}
+
+/// Parses a decimal such as `"1.5"` into base units with `decimals`
+/// fractional digits, rejecting input more precise than that.
+pub fn parse_units(input: &str, decimals: u8) -> eyre::Result<U256> {
+    let (whole, fraction) = input.split_once('.').unwrap_or((input, ""));
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    eyre::ensure!(
+        !(whole.is_empty() && fraction.is_empty()) && is_digits(whole) && is_digits(fraction),
+        "{input:?} is not a decimal number"
+    );
+    eyre::ensure!(
+        fraction.len() <= decimals as usize,
+        "{input:?} has more than {decimals} fractional digits"
+    );
+    let digits = format!("{whole}{fraction:0<width$}", width = decimals as usize);
+    U256::from_dec_str(&digits).map_err(|_| eyre::eyre!("{input:?} does not fit in a uint256"))
+}
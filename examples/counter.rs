@@ -8,7 +8,13 @@
 // PRIV_KEY_PATH=/opt/7d3f.pri \
 // RPC_URL=https://stylus-testnet.arbitrum.io/rpc \
 // STYLUS_PROGRAM_ADDRESS=0xdAb817c16C479aE8681d726dB7DBA33B0734DaC9 \
-// cargo run --example counter
+// cargo run --features client --example counter
+//
+// or, equivalently, with flags:
+// cargo run --features client --example counter -- \
+//     --priv-key-path /opt/7d3f.pri \
+//     --rpc-url https://stylus-testnet.arbitrum.io/rpc \
+//     --program-address 0xdAb817c16C479aE8681d726dB7DBA33B0734DaC9
 
 // interface ICounter {
 //     function number() external view returns (uint256);
@@ -16,35 +22,45 @@
 //     function increment() external;
 // }
 
-use ethers::{
-    middleware::SignerMiddleware,
-    prelude::abigen,
-    providers::{Http, Middleware, Provider},
-    signers::{LocalWallet, Signer},
-    types::Address,
-};
-use eyre::eyre;
-use std::io::{BufRead, BufReader};
-use std::str::FromStr;
-use std::sync::Arc;
+mod common;
 
-/// Your private key file path.
-const ENV_PRIV_KEY_PATH: &str = "PRIV_KEY_PATH";
+use clap::Parser;
+use common::{log_chain, send_or_simulate, ExampleArgs, LogArgs};
+use ethers::{prelude::abigen, providers::Middleware};
+use std::time::Duration;
+use stylus_hello_world::client::{connect_to_chain, ensure_deployed, RetryPolicy};
+use tracing::info;
 
-/// Stylus RPC endpoint url.
-const ENV_RPC_URL: &str = "RPC_URL";
+/// Reads and increments a deployed Counter program.
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    example: ExampleArgs,
 
-/// Deployed pragram address.
-const ENV_PROGRAM_ADDRESS: &str = "STYLUS_PROGRAM_ADDRESS";
+    #[command(flatten)]
+    log: LogArgs,
+}
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
-    let priv_key_path = std::env::var(ENV_PRIV_KEY_PATH)
-        .map_err(|_| eyre!("No {} env var set", ENV_PRIV_KEY_PATH))?;
-    let rpc_url =
-        std::env::var(ENV_RPC_URL).map_err(|_| eyre!("No {} env var set", ENV_RPC_URL))?;
-    let program_address = std::env::var(ENV_PROGRAM_ADDRESS)
-        .map_err(|_| eyre!("No {} env var set", ENV_PROGRAM_ADDRESS))?;
+    let mut cli = Cli::parse();
+    cli.log.init();
+    cli.example.load_config()?;
+    cli.example.print_mode();
+    let signer = cli.example.signer().await?;
+    let (rpc_url, address) = (cli.example.rpc_url()?, cli.example.program_address()?);
+    let wait = cli.example.tx_wait();
+    let Cli {
+        example:
+            ExampleArgs {
+                chain_id,
+                retries,
+                retry_delay_ms,
+                dry_run,
+                ..
+            },
+        ..
+    } = cli;
     abigen!(
         Counter,
         r#"[
@@ -54,33 +70,26 @@ async fn main() -> eyre::Result<()> {
         ]"#
     );
 
-    let provider = Provider::<Http>::try_from(rpc_url)?;
-    let address: Address = program_address.parse()?;
-
-    let privkey = read_secret_from_file(&priv_key_path)?;
-    let wallet = LocalWallet::from_str(&privkey)?;
-    let chain_id = provider.get_chainid().await?.as_u64();
-    let client = Arc::new(SignerMiddleware::new(
-        provider,
-        wallet.clone().with_chain_id(chain_id),
-    ));
+    let policy = RetryPolicy {
+        retries,
+        base_delay: Duration::from_millis(retry_delay_ms),
+    };
+    let client = connect_to_chain(&rpc_url, signer, policy, chain_id).await?;
+    log_chain(&client);
+    ensure_deployed(client.provider(), address).await?;
 
     let counter = Counter::new(address, client);
-    let num = counter.number().call().await;
-    println!("\n---Counter number value = {:?}\n", num);
+    let number = counter.number().call().await?;
+    info!(%number, "counter value");
 
-    let _ = counter.increment().send().await?.await?;
-    println!("Successfully incremented counter via a tx");
+    if send_or_simulate(counter.increment(), dry_run, wait)
+        .await?
+        .is_some()
+    {
+        info!("incremented the counter");
+    }
 
-    let num = counter.number().call().await;
-    println!("New counter number value = {:?}", num);
+    let number = counter.number().call().await?;
+    info!(%number, "new counter value");
     Ok(())
 }
-
-fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
-    let f = std::fs::File::open(fpath)?;
-    let mut buf_reader = BufReader::new(f);
-    let mut secret = String::new();
-    buf_reader.read_line(&mut secret)?;
-    Ok(secret.trim().to_string())
-}
@@ -8,100 +8,480 @@
 // PRIV_KEY_PATH=/opt/7d3f.pri \
 // RPC_URL=https://stylus-testnet.arbitrum.io/rpc \
 // STYLUS_PROGRAM_ADDRESS=0xC4CA13280b8EafD7A033670E620B1AF74950E147 \
-// cargo run --example erc20
+// HELPER_ADDRESS=<optional, a Helper deployed with `--features helper`> \
+// cargo run --features client --example erc20
+//
+// To deploy a fresh Helper instead, pass `--deploy-helper` together with
+// HELPER_TX_DATA=<file from `cargo stylus deploy --features helper --dry-run --output-tx-data`>.
+//
+// Each env var can also be passed as a flag, see `cargo run --features client --example erc20 -- --help`.
+// Or keep them per network in a TOML file, see examples/config.example.toml:
+// cargo run --features client --example erc20 -- --config examples/config.example.toml --profile stylus-testnet
+//
+// Addresses can be given by name from a TOML address book, see examples/address_book.example.toml:
+// cargo run --features client --example erc20 -- --address-book examples/address_book.example.toml \
+//     --program-address weth --helper-address helper
+//
+// For scripts, `--output json` prints the results as one JSON object on stdout
+// and logs to stderr, e.g. `... --example erc20 -- --output json | jq -r .sum`.
+// A failed run prints `{"error": "..."}` instead and exits non-zero.
 
 // Contracts:
 // interface IErc20 {
-//     function name() external pure returns (string memory);
-//     function symbol() external pure returns (string memory);
-//     function decimals() external pure returns (uint8);
+//     event Transfer(address indexed from, address indexed to, uint256 value);
+//     event Approval(address indexed owner, address indexed spender, uint256 value);
+//
+//     function init(string memory name, string memory symbol, uint8 decimals) external;
+//     function name() external view returns (string memory);
+//     function symbol() external view returns (string memory);
+//     function decimals() external view returns (uint8);
+//     function setName(string memory name) external;
+//     function setSymbol(string memory symbol) external;
+//     function tokenMetadata() external view returns (string memory, string memory, uint8, uint256);
+//     function totalSupply() external view returns (uint256);
 //     function balanceOf(address _address) external view returns (uint256);
+//     function balancesOf(address[] memory accounts) external view returns (uint256[] memory);
+//     function holderCount() external view returns (uint256);
 //     function transfer(address to, uint256 value) external returns (bool);
+//     function transferBatch(address[] memory recipients, uint256[] memory amounts) external returns (bool);
+//     function transferBatch(address[] memory recipients, uint256[] memory amounts, bool strict) external returns (bool);
 //     function approve(address spender, uint256 value) external returns (bool);
+//     function increaseAllowance(address spender, uint256 added) external returns (bool);
+//     function decreaseAllowance(address spender, uint256 subtracted) external returns (bool);
 //     function transferFrom(address from, address to, uint256 value) external returns (bool);
 //     function allowance(address owner, address spender) external view returns (uint256);
+//     function mint(address to, uint256 value) external;
+//     function burn(uint256 value) external;
+//     function burnFrom(address account, uint256 value) external;
+//     function rescueTokens(address token, address to, uint256 amount) external;
+//     function maxFlashLoan(address token) external view returns (uint256);
+//     function flashFee(address token, uint256 amount) external view returns (uint256);
+//     function flashFeeRecipient() external view returns (address);
+//     function setFlashFee(uint256 fee_bps, address recipient) external;
+//     function flashLoan(address receiver, address token, uint256 amount, bytes memory data) external returns (bool);
+//     function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s) external;
+//     function nonces(address owner) external view returns (uint256);
+//     function DOMAIN_SEPARATOR() external view returns (bytes32);
+//     function eip712Domain() external view returns (bytes1, string memory, string memory, uint256, address, bytes32, uint256[] memory);
+//     function transferWithAuthorization(address from, address to, uint256 value, uint256 validAfter, uint256 validBefore, bytes32 nonce, uint8 v, bytes32 r, bytes32 s) external;
+//     function cancelAuthorization(address authorizer, bytes32 nonce, uint8 v, bytes32 r, bytes32 s) external;
+//     function authorizationState(address authorizer, bytes32 nonce) external view returns (bool);
+//     function paused() external view returns (bool);
+//     function pause() external;
+//     function unpause() external;
+//     function snapshot() external returns (uint256);
+//     function balanceOfAt(address account, uint256 snapshot_id) external view returns (uint256);
+//     function totalSupplyAt(uint256 snapshot_id) external view returns (uint256);
+//     function rateLimit() external view returns (uint256, uint256, uint256);
+//     function setRateLimit(uint256 max_per_tx, uint256 max_per_window, uint256 window_seconds) external;
+//     function windowTotal(address account) external view returns (uint256);
 // }
 
-// interface IWeth is IErc20 {
+// interface IOwnable {
+//     event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+//
+//     function owner() external view returns (address);
+//     function transferOwnership(address newOwner) external;
+//     function renounceOwnership() external;
+// }
+
+// interface IWeth is IErc20, IOwnable {
+//     event Deposit(address indexed dst, uint256 wad);
+//     event Withdrawal(address indexed src, uint256 wad);
+//
+//     // receive() external payable; wraps like deposit()
 //     function deposit() external payable;
 //     function withdraw(uint256 amount) external;
+//     function withdrawAll() external returns (uint256);
+//     function isFullyCollateralized() external view returns (bool);
+//     function reserves() external view returns (uint256, uint256, uint256);
+//     function sweepExcessEth(address to) external returns (uint256);
+//     function depositTo(address account) external payable;
+//     function withdrawTo(address account, uint256 amount) external;
 //     function sum(uint256[] memory values) external pure returns (string memory, uint256);
 //     function sumWithHelper(address helper, uint256[] memory values) external view returns (uint256);
 // }
 
+// `sumWithHelper` cross-calls the helper's `sum(uint256[])`, selector 0x0194db8e.
+// interface IHelper {
+//     function sum(uint256[] memory values) external pure returns (string memory, uint256);
+// }
+
+mod common;
+
+use clap::Parser;
+use common::{
+    check_provider, confirm, decode_revert, ensure_allowance, estimate_and_log, format_units,
+    json_output, log_chain, log_gas_fields, parse_block_tag, pin_to_block, preflight_transfer,
+    preflight_transfer_from, send_or_simulate, Amount, Erc20Report, ExampleArgs, FeeArgs, LogArgs,
+    OutputFormat,
+};
 use ethers::{
-    middleware::SignerMiddleware,
-    prelude::abigen,
-    providers::{Http, Middleware, Provider},
-    signers::{LocalWallet, Signer},
-    types::{Address, U256},
+    contract::parse_log,
+    providers::Middleware,
+    signers::Signer,
+    types::{Address, BlockNumber, TransactionRequest, U256},
+};
+use std::time::Duration;
+use stylus_hello_world::bindings::{DepositFilter, Erc20, Helper, Weth, WethErrors};
+use stylus_hello_world::client::{
+    connect_to_chain, deploy_program, ensure_deployed, parse_checksummed, read_tx_data, retry,
+    NonceManager, RetryPolicy,
 };
-use eyre::eyre;
-use std::io::{BufRead, BufReader};
-use std::str::FromStr;
-use std::sync::Arc;
-// /// Import the Stylus SDK along with alloy primitive types for use in our program.
-// use stylus_sdk::{alloy_primitives::U256, prelude::*};
+use tracing::{info, warn};
+
+/// Exercises a deployed Weth program.
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    example: ExampleArgs,
+
+    /// Optional deployed Helper address, or its name in `--address-book`.
+    #[arg(long, env = "HELPER_ADDRESS")]
+    helper_address: Option<String>,
+
+    /// Deploys a fresh Helper for the sumWithHelper check instead of using
+    /// `--helper-address`.
+    #[arg(long, requires = "helper_tx_data")]
+    deploy_helper: bool,
 
-/// Your private key file path.
-const ENV_PRIV_KEY_PATH: &str = "PRIV_KEY_PATH";
+    /// Helper deployment tx data, from
+    /// `cargo stylus deploy --features helper --dry-run --output-tx-data`.
+    #[arg(long, env = "HELPER_TX_DATA")]
+    helper_tx_data: Option<String>,
 
-/// Stylus RPC endpoint url.
-const ENV_RPC_URL: &str = "RPC_URL";
+    /// Wei to wrap with a bare ETH transfer, checking the balance it credits.
+    #[arg(long)]
+    wrap_wei: Option<u128>,
 
-/// Deployed pragram address.
-const ENV_PROGRAM_ADDRESS: &str = "STYLUS_PROGRAM_ADDRESS";
+    /// Wei to deposit, transfer to yourself and withdraw in three back-to-back
+    /// transactions, sent before any of them is mined.
+    #[arg(long)]
+    burst_wei: Option<u128>,
+
+    /// Recipient of a WETH transfer, checked for balance before it is sent.
+    #[arg(long, value_parser = parse_checksummed, requires = "transfer_amount")]
+    transfer_to: Option<Address>,
+
+    /// WETH to send to `--transfer-to`, in whole tokens such as `1.5`.
+    #[arg(long, requires = "transfer_to")]
+    transfer_amount: Option<String>,
+
+    /// Sends the transfer with transferFrom out of this owner's allowance.
+    #[arg(long, value_parser = parse_checksummed, requires = "transfer_to")]
+    transfer_from: Option<Address>,
+
+    /// Approves the transferFrom first if the allowance falls short of the
+    /// amount. The signer approves itself, so `--transfer-from` must be the signer.
+    /// A dry run only simulates the approval.
+    #[arg(long, requires = "transfer_from")]
+    auto_approve: bool,
+
+    /// Allowance `--auto-approve` sets, in whole tokens; defaults to exactly
+    /// `--transfer-amount`.
+    #[arg(long, requires = "auto_approve")]
+    approve_amount: Option<String>,
+
+    /// Block to run the read-only `decimals` and `sum` calls at: a number,
+    /// `latest` or `pending`.
+    #[arg(long, value_parser = parse_block_tag)]
+    at_block: Option<BlockNumber>,
+
+    /// How to report the results: log lines, or one JSON object on stdout.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    #[command(flatten)]
+    fees: FeeArgs,
+
+    #[command(flatten)]
+    log: LogArgs,
+}
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
-    let priv_key_path = std::env::var(ENV_PRIV_KEY_PATH)
-        .map_err(|_| eyre!("No {} env var set", ENV_PRIV_KEY_PATH))?;
-    let rpc_url =
-        std::env::var(ENV_RPC_URL).map_err(|_| eyre!("No {} env var set", ENV_RPC_URL))?;
-    let program_address = std::env::var(ENV_PROGRAM_ADDRESS)
-        .map_err(|_| eyre!("No {} env var set", ENV_PROGRAM_ADDRESS))?;
-    abigen!(
-        Weth,
-        r#"[
-            function deposit() external payable
-            function withdraw(uint256 amount) external
-            function sum(uint256[] memory values) external pure returns (string memory, uint256)
-            function sumWithHelper(address helper, uint256[] memory values) external view returns (uint256)
-            function decimals() external pure returns (uint8)
-        ]"#
-    );
-
-    let provider = Provider::<Http>::try_from(rpc_url)?;
-    let address: Address = program_address.parse()?;
+    let cli = Cli::parse();
+    let output = cli.output;
+    cli.log.init_to(output);
+    let mut report = Erc20Report::default();
+    let result = run(cli, &mut report).await;
+    if output == OutputFormat::Text {
+        return result;
+    }
+    let result = result.map(|()| report);
+    println!("{}", json_output(&result));
+    if result.is_err() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-    let privkey = read_secret_from_file(&priv_key_path)?;
-    let wallet = LocalWallet::from_str(&privkey)?;
-    let chain_id = provider.get_chainid().await?.as_u64();
-    let client = Arc::new(SignerMiddleware::new(
-        provider,
-        wallet.clone().with_chain_id(chain_id),
-    ));
+// Runs the checks, filling in `report` as results come in
+async fn run(mut cli: Cli, report: &mut Erc20Report) -> eyre::Result<()> {
+    let profile = cli.example.load_config()?;
+    let book = cli.example.address_book()?;
+    let helper_address = cli
+        .helper_address
+        .as_deref()
+        .map(|input| book.resolve(input));
+    let helper_address = helper_address.transpose()?.or(profile.helper_address);
+    cli.example.print_mode();
+    let signer = cli.example.signer().await?;
+    let (rpc_url, address) = (cli.example.rpc_url()?, cli.example.program_address()?);
+    let wait = cli.example.tx_wait();
+    let Cli {
+        example:
+            ExampleArgs {
+                chain_id,
+                retries,
+                retry_delay_ms,
+                dry_run,
+                ..
+            },
+        deploy_helper,
+        helper_tx_data,
+        wrap_wei,
+        burst_wei,
+        transfer_to,
+        transfer_amount,
+        transfer_from,
+        auto_approve,
+        approve_amount,
+        at_block,
+        fees,
+        ..
+    } = cli;
+    fees.validate()?;
+    let policy = RetryPolicy {
+        retries,
+        base_delay: Duration::from_millis(retry_delay_ms),
+    };
+    let client = connect_to_chain(&rpc_url, signer, policy, chain_id).await?;
+    check_provider(client.provider()).await?;
+    log_chain(&client);
+    ensure_deployed(client.provider(), address).await?;
 
     // ====
-    let ww = Weth::new(address, client);
+    let ww = Weth::new(address, client.clone());
+
+    // Call fn from base Erc20
+    let decimals = retry(policy, || async {
+        pin_to_block(ww.decimals(), at_block).call().await
+    })
+    .await?;
+    report.decimals = Some(decimals);
+    info!(decimals, "decimals");
 
     // call fn from Weth
     let xx: U256 = U256::from(16);
-    let num = ww.sum(vec![xx]).call().await;
-    println!("\n--- sum = {:?}\n", num); // todo
+    let sum_call = pin_to_block(ww.sum(vec![xx]), at_block);
+    if let Err(err) = estimate_and_log(&sum_call).await {
+        warn!(%err, "could not estimate sum");
+    }
+    match retry(policy, || sum_call.call()).await {
+        Ok((tag, sum)) => {
+            report.sum = Some(sum.to_string());
+            report.sum_tag = Some(tag.clone());
+            info!(tag, sum = %format_units(sum, decimals), "sum");
+        }
+        Err(err) => match decode_revert(&err) {
+            Some(reason) => warn!(%reason, "sum reverted"),
+            None => warn!(%err, "sum failed"),
+        },
+    }
 
-    // Call fn from base Erc20
-    let decimals = ww.decimals().call().await;
-    println!("\n--- decimals = {:?}\n", decimals);
+    let total_supply = ww.total_supply().call().await?;
+    info!(total_supply = %format_units(total_supply, decimals), "total supply");
+
+    // The aggregated getter must agree with the individual ones
+    let (name, symbol, meta_decimals, meta_supply) = ww.token_metadata().call().await?;
+    eyre::ensure!(
+        name == ww.name().call().await?,
+        "tokenMetadata name mismatch"
+    );
+    eyre::ensure!(
+        symbol == ww.symbol().call().await?,
+        "tokenMetadata symbol mismatch"
+    );
+    eyre::ensure!(meta_decimals == decimals, "tokenMetadata decimals mismatch");
+    eyre::ensure!(
+        meta_supply == total_supply,
+        "tokenMetadata total supply mismatch"
+    );
+    info!(
+        name,
+        symbol,
+        decimals,
+        total_supply = %format_units(meta_supply, decimals),
+        "token metadata"
+    );
+
+    // Sending ETH with no calldata should wrap it exactly like deposit()
+    if let Some(wei) = wrap_wei {
+        let wei = U256::from(wei);
+        let account = client.signer().address();
+        let before = ww.balance_of(account).call().await?;
+        let tx = TransactionRequest::new().to(address).value(wei);
+        if dry_run {
+            let tx = tx.into();
+            let gas = client.estimate_gas(&tx, None).await?;
+            client.call(&tx, None).await?;
+            info!(%wei, %gas, "dry run: bare transfer simulated, not broadcast");
+        } else {
+            fees.check_gas_price(client.provider()).await?;
+            let receipt = confirm(client.send_transaction(tx, None).await?, wait).await?;
+            report.record_tx(&receipt);
+            let deposit = receipt
+                .logs
+                .into_iter()
+                .find_map(|log| parse_log::<DepositFilter>(log).ok())
+                .ok_or_else(|| eyre::eyre!("bare transfer emitted no Deposit"))?;
+            eyre::ensure!(
+                deposit.dst == account && deposit.wad == wei,
+                "unexpected {:?}",
+                deposit
+            );
+            let after = ww.balance_of(account).call().await?;
+            eyre::ensure!(
+                after == before + wei,
+                "bare transfer moved the balance from {} to {}",
+                format_units(before, decimals),
+                format_units(after, decimals)
+            );
+            info!(%wei, "wrapped with a bare transfer");
+        }
+    }
+
+    // Gas for each is estimated against the latest block, so the calls must
+    // succeed in any order; the existing balance covers the transfer and withdraw
+    if let Some(wei) = burst_wei.filter(|_| !dry_run) {
+        let wei = U256::from(wei);
+        let account = client.signer().address();
+        let balance = ww.balance_of(account).call().await?;
+        eyre::ensure!(
+            balance >= wei,
+            "a burst of {wei} wei needs that much WETH up front, {account:?} holds {balance}"
+        );
+        fees.check_gas_price(client.provider()).await?;
+        let mut nonces = NonceManager::fetch(client.as_ref(), account).await?;
+        let deposit = nonces.assign(ww.deposit().value(wei));
+        let transfer = nonces.assign(ww.transfer(account, wei));
+        let withdraw = nonces.assign(ww.withdraw(wei));
+        let pending = [
+            deposit.send().await?,
+            transfer.send().await?,
+            withdraw.send().await?,
+        ];
+        for tx in pending {
+            report.record_tx(&confirm(tx, wait).await?);
+        }
+    }
+
+    // Check balance and allowance first so a doomed transfer costs no gas
+    if let (Some(to), Some(amount)) = (transfer_to, transfer_amount) {
+        let amount = Amount::from_tokens(&amount, decimals)?;
+        let sender = client.signer().address();
+        if !dry_run {
+            fees.check_gas_price(client.provider()).await?;
+        }
+        let receipt = match transfer_from {
+            Some(from) => {
+                if auto_approve {
+                    eyre::ensure!(
+                        from == sender,
+                        "--auto-approve approves as the signer {sender:?}, not {from:?}"
+                    );
+                    let approve = match approve_amount {
+                        Some(approve) => Amount::from_tokens(&approve, decimals)?,
+                        None => amount,
+                    };
+                    let token = Erc20::new(address, client.clone());
+                    let (needed, approve) = (amount.base_units(), approve.base_units());
+                    let approve = Some(approve);
+                    ensure_allowance(&token, sender, needed, approve, &fees, dry_run, wait).await?;
+                }
+                if dry_run && auto_approve {
+                    // A simulated approval leaves the allowance as it was, so
+                    // transferFrom can only be checked against the balance
+                    preflight_transfer(client.clone(), address, from, to, amount).await?;
+                    info!("dry run: transferFrom not simulated, it needs the approval first");
+                    None
+                } else {
+                    preflight_transfer_from(client.clone(), address, sender, from, to, amount)
+                        .await?;
+                    send_or_simulate(
+                        fees.apply(ww.transfer_from(from, to, amount.base_units()))?,
+                        dry_run,
+                        wait,
+                    )
+                    .await?
+                }
+            }
+            None => {
+                preflight_transfer(client.clone(), address, sender, to, amount).await?;
+                send_or_simulate(
+                    fees.apply(ww.transfer(to, amount.base_units()))?,
+                    dry_run,
+                    wait,
+                )
+                .await?
+            }
+        };
+        if let Some(receipt) = receipt {
+            report.record_tx(&receipt);
+            info!(
+                %amount,
+                symbol,
+                tx_hash = ?receipt.transaction_hash,
+                "transferred"
+            );
+            log_gas_fields(client.as_ref(), &receipt).await?;
+        }
+    }
+
+    let helper_address = match helper_tx_data.filter(|_| deploy_helper) {
+        Some(_) if dry_run => {
+            info!("dry run: skipping the Helper deployment");
+            helper_address
+        }
+        Some(tx_data) => {
+            fees.check_gas_price(client.provider()).await?;
+            let deployed = deploy_program(client.clone(), read_tx_data(&tx_data)?).await?;
+            info!(address = ?deployed, "deployed Helper, reuse it with HELPER_ADDRESS");
+            Some(deployed)
+        }
+        None => helper_address,
+    };
+
+    // Compare the Helper called directly with Weth's cross-call into it
+    if let Some(helper_address) = helper_address {
+        let helper = Helper::new(helper_address, client.clone());
+        let (_, direct) = helper.sum(vec![xx]).call().await?;
+        let via_weth = ww.sum_with_helper(helper_address, vec![xx]).call().await?;
+        eyre::ensure!(
+            direct == via_weth,
+            "sumWithHelper disagrees with the helper"
+        );
+        info!(%direct, %via_weth, "helper sum matches sumWithHelper");
+    }
+
+    // An account without code returns no data, which fails to decode
+    let not_a_helper = client.signer().address();
+    let err = match ww.sum_with_helper(not_a_helper, vec![xx]).call().await {
+        Ok(sum) => eyre::bail!("sumWithHelper on an EOA returned {}", sum),
+        Err(err) => err,
+    };
+    match err.decode_contract_revert::<WethErrors>() {
+        Some(WethErrors::HelperCallFailed(failed)) => {
+            eyre::ensure!(
+                failed.helper == not_a_helper && !failed.reverted,
+                "unexpected {:?}",
+                failed
+            );
+        }
+        other => eyre::bail!("expected HelperCallFailed, got {:?}", other),
+    }
     // ====
 
     Ok(())
 }
-
-fn read_secret_from_file(fpath: &str) -> eyre::Result<String> {
-    let f = std::fs::File::open(fpath)?;
-    let mut buf_reader = BufReader::new(f);
-    let mut secret = String::new();
-    buf_reader.read_line(&mut secret)?;
-    Ok(secret.trim().to_string())
-}
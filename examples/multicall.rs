@@ -0,0 +1,109 @@
+//! Example that reads a token's metadata, supply and several balances in a
+//! single eth_call, batched through Multicall3's `aggregate3`.
+
+// e.g. usage:
+// RPC_URL=https://stylus-testnet.arbitrum.io/rpc \
+// STYLUS_PROGRAM_ADDRESS=0xC4CA13280b8EafD7A033670E620B1AF74950E147 \
+// cargo run --features client --example multicall -- \
+//     --account 0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E --account <another>
+//
+// Multicall3 lives at the same address on most chains; pass --multicall-address
+// where it was deployed elsewhere.
+
+mod common;
+
+use clap::Parser;
+use common::{format_units, LogArgs};
+use ethers::{
+    abi::Token,
+    contract::Multicall,
+    types::{Address, U256},
+};
+use std::sync::Arc;
+use stylus_hello_world::bindings::Erc20;
+use stylus_hello_world::client::{ensure_deployed, parse_checksummed};
+use stylus_hello_world::transport;
+use tracing::info;
+
+// Where Multicall3 is deployed on most chains, ethers' `MULTICALL_ADDRESS`
+const MULTICALL3: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+/// Batch-reads a deployed token through Multicall3.
+#[derive(Parser)]
+struct Cli {
+    /// Stylus RPC endpoint url.
+    #[arg(long, env = "RPC_URL")]
+    rpc_url: String,
+
+    /// Deployed token address.
+    #[arg(long, env = "STYLUS_PROGRAM_ADDRESS", value_parser = parse_checksummed)]
+    program_address: Address,
+
+    /// Account whose balance to read; repeat for more.
+    #[arg(long = "account", value_parser = parse_checksummed)]
+    accounts: Vec<Address>,
+
+    /// Multicall3 address, for chains where it isn't at the canonical one.
+    #[arg(long, env = "MULTICALL_ADDRESS", default_value = MULTICALL3, value_parser = parse_checksummed)]
+    multicall_address: Address,
+
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+// An aggregate3 result that failed or didn't decode to the expected type
+fn expect<T>(value: Option<T>, what: &str) -> eyre::Result<T> {
+    value.ok_or_else(|| eyre::eyre!("{what} failed or returned unexpected data"))
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let Cli {
+        rpc_url,
+        program_address: address,
+        accounts,
+        multicall_address,
+        log,
+    } = Cli::parse();
+    log.init();
+    let provider = transport::connect(&rpc_url).await?;
+    ensure_deployed(&provider, address).await?;
+    ensure_deployed(&provider, multicall_address).await?;
+    let client = Arc::new(provider);
+    let token = Erc20::new(address, client.clone());
+
+    // Every call may fail on its own, so one bad balance doesn't sink the batch
+    let mut multicall = Multicall::new(client, Some(multicall_address)).await?;
+    multicall
+        .add_call(token.name(), true)
+        .add_call(token.symbol(), true)
+        .add_call(token.decimals(), true)
+        .add_call(token.total_supply(), true);
+    for &account in &accounts {
+        multicall.add_call(token.balance_of(account), true);
+    }
+    let mut results = multicall.call_raw().await?.into_iter().map(Result::ok);
+    let mut next = || results.next().flatten();
+    info!(calls = 4 + accounts.len(), "read in one round-trip");
+
+    let name = expect(next().and_then(Token::into_string), "name")?;
+    let symbol = expect(next().and_then(Token::into_string), "symbol")?;
+    let decimals = expect(next().and_then(Token::into_uint), "decimals")?;
+    let decimals = u8::try_from(decimals.as_u64())?;
+    let supply = expect(next().and_then(Token::into_uint), "totalSupply")?;
+    let balances: Vec<Option<U256>> = accounts
+        .iter()
+        .map(|_| next().and_then(Token::into_uint))
+        .collect();
+
+    println!("{:<44} {address:?}", "token");
+    println!("{:<44} {name}", "name");
+    println!("{:<44} {symbol}", "symbol");
+    println!("{:<44} {decimals}", "decimals");
+    println!("{:<44} {}", "total supply", format_units(supply, decimals));
+    for (account, balance) in accounts.iter().zip(balances) {
+        let balance = balance.map_or("call failed".into(), |b| format_units(b, decimals));
+        println!("{:<44} {balance}", format!("{account:?}"));
+    }
+    Ok(())
+}
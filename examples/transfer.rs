@@ -0,0 +1,144 @@
+//! Example that sends a token transfer and verifies it: the balances of both
+//! parties just before and just after the transaction's block must differ
+//! by exactly the amount. Prints PASS or FAIL and exits non-zero on FAIL, so
+//! it doubles as a smoke test of a deployment.
+
+// e.g. usage:
+// PRIV_KEY_PATH=/opt/7d3f.pri \
+// RPC_URL=https://stylus-testnet.arbitrum.io/rpc \
+// STYLUS_PROGRAM_ADDRESS=0xC4CA13280b8EafD7A033670E620B1AF74950E147 \
+// cargo run --features client --example transfer -- \
+//     --to 0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E --amount 1.5
+//
+// A transfer to yourself passes when your balance is unchanged. A fee-taking
+// token (`--features fee`) fails, since the recipient is credited less.
+
+mod common;
+
+use clap::Parser;
+use common::{
+    format_units, log_chain, preflight_transfer, send_or_simulate, Amount, ExampleArgs, FeeArgs,
+    LogArgs,
+};
+use ethers::{
+    providers::Middleware,
+    signers::Signer,
+    types::{Address, BlockId, U256},
+};
+use std::time::Duration;
+use stylus_hello_world::bindings::Erc20;
+use stylus_hello_world::client::{
+    connect_to_chain, ensure_deployed, parse_checksummed, Client, RetryPolicy,
+};
+use tracing::info;
+
+/// Sends a transfer and checks both balances moved by its amount.
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    example: ExampleArgs,
+
+    /// Recipient of the transfer.
+    #[arg(long, value_parser = parse_checksummed)]
+    to: Address,
+
+    /// Tokens to send, in whole tokens such as `1.5`.
+    #[arg(long)]
+    amount: String,
+
+    #[command(flatten)]
+    fees: FeeArgs,
+
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+// The balances of `accounts` as of `block`
+async fn balances_at(
+    token: &Erc20<Client>,
+    accounts: [Address; 2],
+    block: u64,
+) -> eyre::Result<[U256; 2]> {
+    let mut balances = [U256::zero(); 2];
+    for (balance, account) in balances.iter_mut().zip(accounts) {
+        *balance = token
+            .balance_of(account)
+            .block(BlockId::from(block))
+            .call()
+            .await?;
+    }
+    Ok(balances)
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let mut cli = Cli::parse();
+    cli.log.init();
+    cli.example.load_config()?;
+    cli.example.print_mode();
+    cli.fees.validate()?;
+    let signer = cli.example.signer().await?;
+    let (rpc_url, address) = (cli.example.rpc_url()?, cli.example.program_address()?);
+    let policy = RetryPolicy {
+        retries: cli.example.retries,
+        base_delay: Duration::from_millis(cli.example.retry_delay_ms),
+    };
+    let client = connect_to_chain(&rpc_url, signer, policy, cli.example.chain_id).await?;
+    log_chain(&client);
+    ensure_deployed(client.provider(), address).await?;
+
+    let token = Erc20::new(address, client.clone());
+    let decimals = token.decimals().call().await?;
+    let amount = Amount::from_tokens(&cli.amount, decimals)?;
+    let (from, to) = (client.signer().address(), cli.to);
+    preflight_transfer(client.clone(), address, from, to, amount).await?;
+
+    if !cli.example.dry_run {
+        cli.fees.check_gas_price(client.provider()).await?;
+    }
+    let call = cli.fees.apply(token.transfer(to, amount.base_units()))?;
+    let Some(receipt) = send_or_simulate(call, cli.example.dry_run, cli.example.tx_wait()).await?
+    else {
+        info!("dry run: nothing was sent, so there is nothing to verify");
+        return Ok(());
+    };
+
+    // Around the transaction's own block, so later transfers don't interfere
+    let block = receipt
+        .block_number
+        .ok_or_else(|| eyre::eyre!("the receipt has no block number"))?
+        .as_u64();
+    let before = balances_at(&token, [from, to], block - 1).await?;
+    let after = balances_at(&token, [from, to], block).await?;
+    let expected = match from == to {
+        true => before,
+        false => [
+            before[0].saturating_sub(amount.base_units()),
+            before[1].saturating_add(amount.base_units()),
+        ],
+    };
+
+    let show = |value: U256| format_units(value, decimals);
+    for (i, (name, account)) in [("sender", from), ("recipient", to)]
+        .into_iter()
+        .enumerate()
+    {
+        info!(
+            party = name,
+            ?account,
+            before = %show(before[i]),
+            after = %show(after[i]),
+            expected = %show(expected[i]),
+            "balance"
+        );
+    }
+    if after != expected {
+        println!("FAIL: balances moved by other than {amount}");
+        eyre::bail!(
+            "transfer in {:?} did not move the balances as expected",
+            receipt.transaction_hash
+        );
+    }
+    println!("PASS: {amount} moved from {from:?} to {to:?}");
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Example that polls one account's balance of a deployed Erc20 program and
+//! prints it whenever it changes, optionally warning as it crosses a threshold.
+
+// e.g. usage:
+// RPC_URL=https://stylus-testnet.arbitrum.io/rpc \
+// STYLUS_PROGRAM_ADDRESS=0xC4CA13280b8EafD7A033670E620B1AF74950E147 \
+// cargo run --features client --example watch_balance -- \
+//     --account 0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E --threshold 100
+//
+// --threshold is in whole tokens, such as 12.5. --once prints the current
+// balance and exits; otherwise --poll-interval-ms sets the wait between reads.
+
+mod common;
+
+use clap::Parser;
+use common::{format_units, parse_units, BalanceWatch, Crossing, LogArgs};
+use ethers::types::Address;
+use std::sync::Arc;
+use std::time::Duration;
+use stylus_hello_world::bindings::Erc20;
+use stylus_hello_world::client::parse_checksummed;
+use stylus_hello_world::transport;
+use tracing::{info, warn};
+
+#[derive(Parser)]
+struct Cli {
+    /// Stylus RPC endpoint: an `http(s)://` or `ws(s)://` url, or an IPC socket path.
+    #[arg(long, env = "RPC_URL")]
+    rpc_url: String,
+
+    /// Deployed token address.
+    #[arg(long, env = "STYLUS_PROGRAM_ADDRESS", value_parser = parse_checksummed)]
+    program_address: Address,
+
+    /// The account whose balance is watched.
+    #[arg(long, value_parser = parse_checksummed)]
+    account: Address,
+
+    /// Warns when the balance rises to or falls below this many whole tokens.
+    #[arg(long)]
+    threshold: Option<String>,
+
+    /// Prints the current balance and exits.
+    #[arg(long)]
+    once: bool,
+
+    /// Milliseconds between reads of the balance.
+    #[arg(long, default_value_t = 2000, value_parser = clap::value_parser!(u64).range(1..))]
+    poll_interval_ms: u64,
+
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+    cli.log.init();
+    let account = cli.account;
+    if account.is_zero() {
+        warn!("the zero address can't receive tokens, its balance stays 0");
+    }
+    let provider = transport::connect(&cli.rpc_url).await?;
+    let token = Erc20::new(cli.program_address, Arc::new(provider));
+    let decimals = token.decimals().call().await?;
+    let threshold = cli
+        .threshold
+        .map(|threshold| parse_units(&threshold, decimals))
+        .transpose()?;
+    let mut watch = BalanceWatch::new(threshold);
+
+    // The first read must succeed, later failures are retried next poll
+    let balance = token.balance_of(account).call().await?;
+    let change = watch
+        .observe(balance)
+        .expect("the first reading is a change");
+    let value = format_units(change.balance, decimals);
+    info!(account = ?account, balance = %value, "balance");
+    if let Some(threshold) = threshold.filter(|threshold| balance >= *threshold) {
+        let threshold = format_units(threshold, decimals);
+        info!(balance = %value, threshold = %threshold, "balance is at or above the threshold");
+    }
+    if cli.once {
+        return Ok(());
+    }
+
+    let poll_interval = Duration::from_millis(cli.poll_interval_ms);
+    loop {
+        tokio::time::sleep(poll_interval).await;
+        let balance = match token.balance_of(account).call().await {
+            Ok(balance) => balance,
+            Err(err) => {
+                warn!(%err, "could not read the balance, retrying");
+                continue;
+            }
+        };
+        let Some(change) = watch.observe(balance) else {
+            continue;
+        };
+        let value = format_units(change.balance, decimals);
+        let previous = format_units(change.previous.unwrap_or_default(), decimals);
+        info!(account = ?account, previous = %previous, balance = %value, "balance changed");
+        if let (Some(crossing), Some(threshold)) = (change.crossed, threshold) {
+            let threshold = format_units(threshold, decimals);
+            match crossing {
+                Crossing::Up => {
+                    warn!(balance = %value, %threshold, "balance rose to the threshold")
+                }
+                Crossing::Down => {
+                    warn!(balance = %value, %threshold, "balance fell below the threshold")
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Example that prints the `Transfer` events of a deployed Erc20 program,
+//! either over a block range or live as new blocks arrive.
+//!
+//! Over a `ws://` or IPC endpoint, `--follow` subscribes to new transfers;
+//! over HTTP it polls for new blocks instead.
+
+// e.g. usage:
+// RPC_URL=https://stylus-testnet.arbitrum.io/rpc \
+// STYLUS_PROGRAM_ADDRESS=0xC4CA13280b8EafD7A033670E620B1AF74950E147 \
+// cargo run --features client --example watch_transfers -- --from-block 1000 --follow
+//
+// Without --from-block and --to-block only the latest block is scanned. Over
+// HTTP, --poll-interval-ms sets how long --follow waits between polls.
+// RPC_URL=ws://localhost:8548 or RPC_URL=/path/to/nitro.ipc streams live transfers.
+
+mod common;
+
+use clap::Parser;
+use common::{format_units, transfers_in_range, LogArgs};
+use ethers::{
+    contract::LogMeta,
+    providers::{Middleware, Provider, StreamExt},
+    types::{Address, H256},
+};
+use std::sync::Arc;
+use std::time::Duration;
+use stylus_hello_world::bindings::{erc_20::TransferFilter, Erc20};
+use stylus_hello_world::client::parse_checksummed;
+use stylus_hello_world::transport::{self, Transport};
+use tracing::{info, warn};
+
+#[derive(Parser)]
+struct Cli {
+    /// Stylus RPC endpoint: an `http(s)://` or `ws(s)://` url, or an IPC socket path.
+    #[arg(long, env = "RPC_URL")]
+    rpc_url: String,
+
+    /// Deployed token address.
+    #[arg(long, env = "STYLUS_PROGRAM_ADDRESS", value_parser = parse_checksummed)]
+    program_address: Address,
+
+    /// First block to scan, defaulting to the latest one.
+    #[arg(long)]
+    from_block: Option<u64>,
+
+    /// Last block to scan, defaulting to the latest one.
+    #[arg(long, conflicts_with = "follow")]
+    to_block: Option<u64>,
+
+    /// Keeps watching for new transfers once the range is scanned.
+    #[arg(long)]
+    follow: bool,
+
+    /// Milliseconds between polls for new blocks when following over HTTP.
+    #[arg(long, default_value_t = 2000, value_parser = clap::value_parser!(u64).range(1..))]
+    poll_interval_ms: u64,
+
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+type Token = Erc20<Provider<Transport>>;
+
+// Prints the transfers in the range, returning how many there were
+async fn print_transfers(token: &Token, from: u64, to: u64, decimals: u8) -> eyre::Result<usize> {
+    let transfers = transfers_in_range(token, from, to).await?;
+    for (transfer, meta) in &transfers {
+        print_transfer(transfer, meta, decimals);
+    }
+    Ok(transfers.len())
+}
+
+fn print_transfer(transfer: &TransferFilter, meta: &LogMeta, decimals: u8) {
+    info!(
+        block = %meta.block_number,
+        tx_hash = ?meta.transaction_hash,
+        from = ?transfer.from,
+        to = ?transfer.to,
+        value = %format_units(transfer.value, decimals),
+        "transfer"
+    );
+}
+
+async fn block_hash(provider: &Provider<Transport>, number: u64) -> eyre::Result<Option<H256>> {
+    Ok(provider
+        .get_block(number)
+        .await?
+        .and_then(|block| block.hash))
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let Cli {
+        rpc_url,
+        program_address: address,
+        from_block,
+        to_block,
+        follow,
+        poll_interval_ms,
+        log,
+    } = Cli::parse();
+    log.init();
+    let provider = transport::connect(&rpc_url).await?;
+    let token = Erc20::new(address, Arc::new(provider.clone()));
+    let decimals = token.decimals().call().await?;
+
+    let latest = provider.get_block_number().await?.as_u64();
+    let end = to_block.unwrap_or(latest);
+    let start = from_block.unwrap_or(end);
+    eyre::ensure!(start <= end, "--from-block {start} is after block {end}");
+    if print_transfers(&token, start, end, decimals).await? == 0 {
+        info!(from = start, to = end, "no transfers in range");
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    // The node pushes each new transfer, and resends reorged ones
+    if provider.as_ref().supports_subscriptions() {
+        let filter = token.transfer_filter().from_block(end + 1);
+        let mut transfers = filter.subscribe_with_meta().await?;
+        while let Some(transfer) = transfers.next().await {
+            let (transfer, meta) = transfer?;
+            print_transfer(&transfer, &meta, decimals);
+        }
+        eyre::bail!("the node closed the subscription");
+    }
+
+    // The last scanned block and its hash. If that hash changes, the block
+    // was reorged out and is scanned again, which may repeat some transfers.
+    // Every poll, new block or not, waits first, so an idle chain isn't hammered.
+    let poll_interval = Duration::from_millis(poll_interval_ms);
+    let mut tip = (end, block_hash(&provider, end).await?);
+    loop {
+        tokio::time::sleep(poll_interval).await;
+        let latest = provider.get_block_number().await?.as_u64();
+        let next = if latest < tip.0 {
+            warn!(from = tip.0, to = latest, "the chain head went back");
+            latest
+        } else if block_hash(&provider, tip.0).await? != tip.1 {
+            warn!(block = tip.0, "block was reorged, scanning it again");
+            tip.0
+        } else if latest == tip.0 {
+            continue;
+        } else {
+            tip.0 + 1
+        };
+        print_transfers(&token, next, latest, decimals).await?;
+        tip = (latest, block_hash(&provider, latest).await?);
+    }
+}
@@ -0,0 +1,180 @@
+//! Selectors and calldata for calling other contracts by hand, and calls to
+//! ERC-20 tokens that tolerate the non-standard ones.
+
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::{b256, Address, B256, U256},
+    alloy_sol_types::{sol, sol_data, token::TokenSeq, SolCall, SolError, SolType},
+    call::RawCall,
+    keccak_const::Keccak256,
+    types::AddressVM,
+};
+
+/// The selector of a canonical signature such as `"sum(uint256[])"`: the first
+/// 4 bytes of its keccak hash. Being `const`, it can fill in constants too.
+///
+/// ```
+/// use stylus_hello_world::abi::selector;
+///
+/// assert_eq!(selector("transfer(address,uint256)"), [0xa9, 0x05, 0x9c, 0xbb]);
+/// assert_eq!(selector("sum(uint256[])"), [0x01, 0x94, 0xdb, 0x8e]);
+/// assert_eq!(selector("supportsInterface(bytes4)"), [0x01, 0xff, 0xc9, 0xa7]);
+/// ```
+pub const fn selector(signature: &str) -> [u8; 4] {
+    let digest: [u8; 32] = Keccak256::new().update(signature.as_bytes()).finalize();
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+/// Calldata calling `signature` with `args`, ABI-encoded as the tuple type `T`.
+///
+/// ```
+/// use alloy_primitives::{Address, U256};
+/// use alloy_sol_types::sol_data;
+/// use stylus_hello_world::abi::encode_call;
+///
+/// let to = Address::repeat_byte(0x11);
+/// let calldata = encode_call::<(sol_data::Address, sol_data::Uint<256>)>(
+///     "transfer(address,uint256)",
+///     &(to, U256::from(5)),
+/// );
+/// assert_eq!(calldata[..4], [0xa9, 0x05, 0x9c, 0xbb]);
+/// assert_eq!(calldata[16..36], to[..]);
+/// assert_eq!(calldata[67], 5);
+/// assert_eq!(calldata.len(), 4 + 2 * 32);
+/// ```
+pub fn encode_call<T>(signature: &str, args: &T::RustType) -> Vec<u8>
+where
+    T: SolType,
+    for<'a> T::TokenType<'a>: TokenSeq<'a>,
+{
+    let mut calldata = selector(signature).to_vec();
+    calldata.extend(T::encode_params(args));
+    calldata
+}
+
+sol! {
+    error SafeErc20FailedOperation(address token);
+
+    function transfer(address to, uint256 value) returns (bool);
+    function transferFrom(address from, address to, uint256 value) returns (bool);
+    function approve(address spender, uint256 value) returns (bool);
+}
+
+pub enum SafeErc20Error {
+    FailedOperation(SafeErc20FailedOperation),
+}
+
+impl From<SafeErc20Error> for Vec<u8> {
+    fn from(err: SafeErc20Error) -> Vec<u8> {
+        match err {
+            SafeErc20Error::FailedOperation(e) => e.encode(),
+        }
+    }
+}
+
+/// Calls `token.transfer(to, value)`, see [`call_token`].
+pub fn safe_transfer(token: Address, to: Address, value: U256) -> Result<(), SafeErc20Error> {
+    call_token(token, &transferCall { to, value }.encode())
+}
+
+/// Calls `token.transferFrom(from, to, value)`, see [`call_token`].
+pub fn safe_transfer_from(
+    token: Address,
+    from: Address,
+    to: Address,
+    value: U256,
+) -> Result<(), SafeErc20Error> {
+    call_token(token, &transferFromCall { from, to, value }.encode())
+}
+
+/// Calls `token.approve(spender, value)`, see [`call_token`].
+pub fn safe_approve(token: Address, spender: Address, value: U256) -> Result<(), SafeErc20Error> {
+    call_token(token, &approveCall { spender, value }.encode())
+}
+
+/// Calls `token` with `calldata` for a method returning `bool`. Tokens that
+/// return nothing, or something other than a bool, succeed unless they
+/// revert; only an explicit `false` fails, as does an account without code,
+/// where the call would succeed without doing anything.
+pub fn call_token(token: Address, calldata: &[u8]) -> Result<(), SafeErc20Error> {
+    let failed = || SafeErc20Error::FailedOperation(SafeErc20FailedOperation { token });
+    if !has_code(token) {
+        return Err(failed());
+    }
+    let output = RawCall::new().call(token, calldata).map_err(|_| failed())?;
+    let returned_false = <(sol_data::Bool,)>::decode_params(&output, true).is_ok_and(|(ok,)| !ok);
+    if returned_false {
+        return Err(failed());
+    }
+    Ok(())
+}
+
+/// Whether `account` has code, i.e. is a deployed contract.
+// AddressVM::has_code has its check inverted in this SDK version, so
+// compare the code hash against the empty-account and empty-code hashes here
+pub fn has_code(account: Address) -> bool {
+    const EMPTY_CODE_HASH: B256 =
+        b256!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+    let hash = account.codehash();
+    !hash.is_zero() && hash != EMPTY_CODE_HASH
+}
+
+/// Signs the EIP-712 message with `struct_hash` to `domain` the way a
+/// wallet's `eth_signTypedData_v4` does, returning the `(v, r, s)` that
+/// `permit` or `transferWithAuthorization` take. Hashing runs on the host
+/// with ethers, as alloy's keccak here is the Stylus hostio.
+///
+/// ```
+/// use ethers::abi::{encode, Token};
+/// use ethers::signers::{LocalWallet, Signer};
+/// use ethers::types::{transaction::eip712::EIP712Domain, Address, Signature, H256, U256};
+/// use ethers::utils::keccak256;
+/// use stylus_hello_world::abi::sign_typed;
+///
+/// let wallet: LocalWallet = "b6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9520659"
+///     .parse()
+///     .unwrap();
+/// let domain = EIP712Domain {
+///     name: Some("Wrapped Ether Example".into()),
+///     version: Some("1".into()),
+///     chain_id: Some(412346.into()),
+///     verifying_contract: Some(Address::repeat_byte(0x70)),
+///     salt: None,
+/// };
+/// let type_hash = keccak256(
+///     "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+/// );
+/// let struct_hash = keccak256(encode(&[
+///     Token::FixedBytes(type_hash.to_vec()),
+///     Token::Address(wallet.address()),
+///     Token::Address(Address::repeat_byte(0x11)),
+///     Token::Uint(100.into()),
+///     Token::Uint(U256::zero()),
+///     Token::Uint(U256::MAX),
+/// ]));
+/// let (v, r, s) = sign_typed(&wallet, &domain, struct_hash);
+///
+/// let digest = keccak256([&[0x19, 0x01][..], &domain.separator(), &struct_hash].concat());
+/// let signature = Signature { r: r.into(), s: s.into(), v: v.into() };
+/// assert_eq!(signature.recover(H256(digest)).unwrap(), wallet.address());
+/// ```
+#[cfg(feature = "client")]
+pub fn sign_typed(
+    wallet: &ethers::signers::LocalWallet,
+    domain: &ethers::types::transaction::eip712::EIP712Domain,
+    struct_hash: [u8; 32],
+) -> (u8, [u8; 32], [u8; 32]) {
+    use ethers::types::{H256, U256};
+    use ethers::utils::keccak256;
+
+    let digest = keccak256([&[0x19, 0x01][..], &domain.separator(), &struct_hash].concat());
+    let signature = wallet
+        .sign_hash(H256(digest))
+        .expect("a local key signs any hash");
+    let word = |x: U256| {
+        let mut bytes = [0; 32];
+        x.to_big_endian(&mut bytes);
+        bytes
+    };
+    (signature.v as u8, word(signature.r), word(signature.s))
+}
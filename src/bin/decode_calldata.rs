@@ -0,0 +1,72 @@
+//! Decodes a transaction's input data against the Erc20, Weth and Helper
+//! ABIs, printing the function called and its arguments:
+//!
+//! ```text
+//! cargo run --features cli --bin decode_calldata -- 0xa9059cbb000000...
+//! ```
+//!
+//! Input with a selector none of them has prints the raw selector instead.
+
+use clap::Parser;
+use ethers::{
+    abi::{Function, Token},
+    types::{Bytes, I256},
+    utils::hex,
+};
+use stylus_hello_world::bindings::exported_abis;
+
+#[derive(Parser)]
+struct Cli {
+    /// The calldata as hex, with or without a `0x` prefix.
+    calldata: Bytes,
+}
+
+fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+    let Some((selector, params)) = cli.calldata.split_first_chunk::<4>() else {
+        eyre::bail!(
+            "calldata is {} bytes, too short for a selector",
+            cli.calldata.len()
+        );
+    };
+
+    // Weth repeats Erc20's functions, so report the first contract that has one
+    let candidates = exported_abis().into_iter().flat_map(|(file, abi)| {
+        let contract = file.trim_end_matches(".json");
+        abi.functions().map(move |function| (contract, function))
+    });
+    for (contract, function) in candidates.filter(|(_, f)| f.short_signature() == *selector) {
+        let Ok(tokens) = function.decode_input(params) else {
+            continue;
+        };
+        print_call(contract, function, &tokens);
+        return Ok(());
+    }
+    println!("unknown selector 0x{}", hex::encode(selector));
+    Ok(())
+}
+
+fn print_call(contract: &str, function: &Function, tokens: &[Token]) {
+    let kinds: Vec<_> = function.inputs.iter().map(|p| p.kind.to_string()).collect();
+    println!("{contract}.{}({})", function.name, kinds.join(","));
+    for (input, token) in function.inputs.iter().zip(tokens) {
+        println!("  {}: {}", input.name, render_token(token));
+    }
+}
+
+// Numbers in decimal and bytes as 0x-prefixed hex, unlike Token's Display
+fn render_token(token: &Token) -> String {
+    let list = |tokens: &[Token]| -> Vec<String> { tokens.iter().map(render_token).collect() };
+    match token {
+        Token::Address(address) => format!("{address:?}"),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => format!("{value:?}"),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Array(tokens) | Token::FixedArray(tokens) => {
+            format!("[{}]", list(tokens).join(", "))
+        }
+        Token::Tuple(tokens) => format!("({})", list(tokens).join(", ")),
+    }
+}
@@ -0,0 +1,201 @@
+//! Deploys and activates a token program, initializes its metadata, mints
+//! its initial supply to the owner and hands the owner role over.
+//!
+//! Build a mintable token first, e.g. with
+//! `cargo build --release --target wasm32-unknown-unknown --features fee`
+//! (or `--features capped`, passing `--cap`), then:
+//!
+//! ```text
+//! PRIV_KEY_PATH=/opt/7d3f.pri RPC_URL=http://localhost:8547 \
+//! cargo run --features cli --bin deploy -- \
+//!     --name "Example" --symbol EX --decimals 18 --initial-supply 1000000 \
+//!     --owner 0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E
+//! ```
+//!
+//! With `--salt`, the program is deployed through a CREATE2 factory instead,
+//! at an address that is the same on every chain for the same salt and wasm.
+
+use clap::Parser;
+use ethers::{
+    abi::Detokenize,
+    contract::ContractCall,
+    providers::Middleware,
+    signers::Signer,
+    types::{Address, TransactionReceipt, H256, U256},
+    utils::{format_units, parse_units},
+};
+use stylus_hello_world::bindings::{CappedErc20, Erc20};
+use stylus_hello_world::client::{
+    check_gas_price, connect, deploy_program, deploy_program_create2, parse_checksummed,
+    read_wallet_from_file, read_wasm, Client, RetryPolicy,
+};
+
+#[derive(Parser)]
+struct Cli {
+    /// Your private key file path.
+    #[arg(long, env = "PRIV_KEY_PATH")]
+    priv_key_path: String,
+
+    /// Stylus RPC endpoint url.
+    #[arg(long, env = "RPC_URL")]
+    rpc_url: String,
+
+    /// The compiled program to deploy.
+    #[arg(
+        long,
+        default_value = "target/wasm32-unknown-unknown/release/stylus_hello_world.wasm"
+    )]
+    wasm: String,
+
+    #[arg(long)]
+    name: String,
+
+    #[arg(long)]
+    symbol: String,
+
+    #[arg(long, default_value_t = 18)]
+    decimals: u8,
+
+    /// Minted to the owner, in whole tokens such as `1000.5`.
+    #[arg(long, default_value = "0")]
+    initial_supply: String,
+
+    /// Receives the initial supply and the owner role; defaults to the deployer.
+    #[arg(long, value_parser = parse_checksummed)]
+    owner: Option<Address>,
+
+    /// Supply cap, in whole tokens, for a program built with `--features capped`.
+    #[arg(long)]
+    cap: Option<String>,
+
+    /// Deploys through `--create2-factory` with this 32-byte hex salt.
+    #[arg(long)]
+    salt: Option<H256>,
+
+    /// The CREATE2 factory `--salt` deploys through; defaults to `CREATE2_FACTORY`.
+    #[arg(
+        long,
+        default_value = "0x4e59b44847b379578588920cA78FbF26c0B4956C",
+        value_parser = parse_checksummed,
+        requires = "salt"
+    )]
+    create2_factory: Address,
+
+    /// Aborts before any transaction while the node quotes a gas price above
+    /// this, in gwei.
+    #[arg(long)]
+    max_gas_price: Option<String>,
+}
+
+// Sends the deployment's transactions one step at a time
+struct Steps<'a> {
+    client: &'a Client,
+    /// Most wei per gas to send any step at
+    max_gas_price: Option<U256>,
+}
+
+impl Steps<'_> {
+    // Aborts before sending while gas is priced above `max_gas_price`
+    async fn check_gas_price(&self) -> eyre::Result<()> {
+        if let Some(max) = self.max_gas_price {
+            check_gas_price(self.client.provider(), max).await?;
+        }
+        Ok(())
+    }
+
+    // Sends `call` and waits for it to succeed, so no step runs after a failed one
+    async fn send<D: Detokenize>(
+        &self,
+        step: &str,
+        call: ContractCall<Client, D>,
+    ) -> eyre::Result<TransactionReceipt> {
+        self.check_gas_price().await?;
+        let receipt = call
+            .send()
+            .await?
+            .await?
+            .ok_or_else(|| eyre::eyre!("{step}: transaction was dropped"))?;
+        eyre::ensure!(
+            receipt.status == Some(1.into()),
+            "{step}: transaction {:?} reverted",
+            receipt.transaction_hash
+        );
+        println!("{step}: ok in {:?}", receipt.transaction_hash);
+        Ok(receipt)
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+    let to_units = |amount: &str| -> eyre::Result<U256> {
+        Ok(parse_units(amount, u32::from(cli.decimals))?.into())
+    };
+    let initial_supply = to_units(&cli.initial_supply)?;
+    let cap = cli.cap.as_deref().map(to_units).transpose()?;
+    let max_gas_price = cli
+        .max_gas_price
+        .as_deref()
+        .map(|gwei| parse_units(gwei, "gwei").map(U256::from))
+        .transpose()?;
+    let tx_data = read_wasm(&cli.wasm)?;
+
+    let wallet = read_wallet_from_file(&cli.priv_key_path)?;
+    let client = connect(&cli.rpc_url, wallet, RetryPolicy::default()).await?;
+    let deployer = client.signer().address();
+    let owner = cli.owner.unwrap_or(deployer);
+
+    let steps = Steps {
+        client: &client,
+        max_gas_price,
+    };
+    steps.check_gas_price().await?;
+    let address = match cli.salt {
+        Some(salt) => {
+            let deployment =
+                deploy_program_create2(client.clone(), cli.create2_factory, salt, tx_data).await?;
+            deployment.actual
+        }
+        None => deploy_program(client.clone(), tx_data).await?,
+    };
+    println!("deploy and activate: ok at {address:?}");
+
+    let token = Erc20::new(address, client.clone());
+    let (name, symbol, decimals) = (cli.name.clone(), cli.symbol.clone(), cli.decimals);
+    match cap {
+        Some(cap) => {
+            let capped = CappedErc20::new(address, client.clone());
+            steps
+                .send("init", capped.init(name, symbol, decimals, cap))
+                .await?;
+        }
+        None => {
+            steps
+                .send("init", token.init(name, symbol, decimals))
+                .await?;
+        }
+    }
+    if !initial_supply.is_zero() {
+        steps
+            .send("mint", token.mint(owner, initial_supply))
+            .await?;
+    }
+    if owner != deployer {
+        steps
+            .send("transfer ownership", token.transfer_ownership(owner))
+            .await?;
+    }
+
+    let supply = token.total_supply().call().await?;
+    println!();
+    println!("token:        {address:?}");
+    println!("name:         {}", token.name().call().await?);
+    println!("symbol:       {}", token.symbol().call().await?);
+    println!("decimals:     {}", token.decimals().call().await?);
+    println!(
+        "total supply: {}",
+        format_units(supply, u32::from(decimals))?
+    );
+    println!("owner:        {:?}", token.owner().call().await?);
+    Ok(())
+}
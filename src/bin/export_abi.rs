@@ -0,0 +1,30 @@
+//! Writes the JSON ABIs of the Erc20, Weth and Helper programs, taken from
+//! [`stylus_hello_world::bindings`], for tooling that can't use the Rust
+//! bindings:
+//!
+//! ```text
+//! cargo run --features cli --bin export_abi -- --out abi
+//! ```
+
+use clap::Parser;
+use std::fs;
+use std::path::PathBuf;
+use stylus_hello_world::bindings::exported_abis;
+
+#[derive(Parser)]
+struct Cli {
+    /// Directory to write the files into, created if missing.
+    #[arg(long, default_value = "abi")]
+    out: PathBuf,
+}
+
+fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+    fs::create_dir_all(&cli.out)?;
+    for (file, abi) in exported_abis() {
+        let path = cli.out.join(file);
+        fs::write(&path, serde_json::to_string_pretty(abi)? + "\n")?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! Fires a batch of concurrent token transfers at a deployed program and
+//! reports confirmed throughput and latency percentiles.
+//!
+//! Nonces are handed out in order, so at most `--concurrency` transfers are
+//! awaiting confirmation at once. Before sending anything it estimates what
+//! the run costs in gas and refuses to spend more than `--max-spend`:
+//!
+//! ```text
+//! PRIV_KEY_PATH=/opt/7d3f.pri RPC_URL=http://localhost:8547 \
+//! cargo run --features cli --bin loadtest -- \
+//!     --program-address 0xC4CA13280b8EafD7A033670E620B1AF74950E147 \
+//!     --count 200 --concurrency 20
+//! ```
+
+use clap::Parser;
+use ethers::{
+    providers::{Middleware, PendingTransaction},
+    signers::Signer,
+    types::{Address, U256},
+    utils::{format_ether, parse_ether},
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use stylus_hello_world::bindings::Erc20;
+use stylus_hello_world::client::{
+    connect, parse_checksummed, read_wallet_from_file, NonceManager, RetryPolicy,
+    SharedNonceManager,
+};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+/// The most transfers one run may send, whatever `--count` says.
+const MAX_COUNT: usize = 10_000;
+
+#[derive(Parser)]
+struct Cli {
+    /// Your private key file path; its account pays for and sends every transfer.
+    #[arg(long, env = "PRIV_KEY_PATH")]
+    priv_key_path: String,
+
+    /// Stylus RPC endpoint url.
+    #[arg(long, env = "RPC_URL")]
+    rpc_url: String,
+
+    /// Deployed token address.
+    #[arg(long, env = "STYLUS_PROGRAM_ADDRESS", value_parser = parse_checksummed)]
+    program_address: Address,
+
+    /// Receives the transfers; defaults to the sender itself, so the run
+    /// only costs gas.
+    #[arg(long, value_parser = parse_checksummed)]
+    to: Option<Address>,
+
+    /// Transfers to send in total.
+    #[arg(long, default_value_t = 100)]
+    count: usize,
+
+    /// Transfers awaiting confirmation at once.
+    #[arg(long, default_value_t = 10)]
+    concurrency: usize,
+
+    /// Base units of the token each transfer moves.
+    #[arg(long, default_value_t = 1)]
+    amount: u64,
+
+    /// Most ETH the run may spend on gas, at the current gas price.
+    #[arg(long, default_value = "0.01")]
+    max_spend: String,
+}
+
+// The latency at quantile `q` of the ascending `latencies`
+fn percentile(latencies: &[Duration], q: f64) -> Duration {
+    let index = ((latencies.len() - 1) as f64 * q).round() as usize;
+    latencies[index]
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+    eyre::ensure!(
+        (1..=MAX_COUNT).contains(&cli.count),
+        "--count must be between 1 and {MAX_COUNT}"
+    );
+    eyre::ensure!(cli.concurrency > 0, "--concurrency must be at least 1");
+    let max_spend = parse_ether(&cli.max_spend)?;
+
+    let wallet = read_wallet_from_file(&cli.priv_key_path)?;
+    let client = connect(&cli.rpc_url, wallet, RetryPolicy::default()).await?;
+    let sender = client.signer().address();
+    let to = cli.to.unwrap_or(sender);
+    let token = Erc20::new(cli.program_address, client.clone());
+    let (count, amount) = (cli.count, U256::from(cli.amount));
+
+    // Every transfer must be affordable up front, both in tokens and in gas.
+    // Sending to itself keeps the balance, so then each only needs `amount`.
+    let needed = if to == sender { amount } else { amount * count };
+    let balance = token.balance_of(sender).call().await?;
+    eyre::ensure!(
+        balance >= needed,
+        "{sender:?} holds {balance} but {count} transfers need {needed}"
+    );
+    let gas = token.transfer(to, amount).estimate_gas().await?;
+    let cost = gas * client.get_gas_price().await? * count;
+    let eth = client.get_balance(sender, None).await?;
+    eyre::ensure!(
+        cost <= max_spend,
+        "the run would spend about {} ETH on gas, above --max-spend {}",
+        format_ether(cost),
+        cli.max_spend
+    );
+    eyre::ensure!(
+        cost <= eth,
+        "the run would spend about {} ETH on gas but {sender:?} has {}",
+        format_ether(cost),
+        format_ether(eth)
+    );
+    println!(
+        "sending {count} transfers, {} at a time, for about {} ETH of gas",
+        cli.concurrency,
+        format_ether(cost)
+    );
+
+    let nonces = SharedNonceManager::new(NonceManager::fetch(&*client, sender).await?);
+    let permits = Arc::new(Semaphore::new(cli.concurrency));
+    let started = Instant::now();
+    let mut tasks = JoinSet::new();
+    for _ in 0..count {
+        let permit = permits.clone().acquire_owned().await?;
+        let (client, token, nonces) = (client.clone(), token.clone(), nonces.clone());
+        tasks.spawn(async move {
+            let _permit = permit;
+            let sent = Instant::now();
+            // Gas is estimated up front, so the nonce lock isn't held across that too
+            let (_, hash) = nonces.send(token.transfer(to, amount).gas(gas * 2)).await?;
+            let receipt = PendingTransaction::new(hash, client.provider()).await?;
+            let confirmed = receipt.is_some_and(|r| r.status == Some(1.into()));
+            eyre::Ok((confirmed, sent.elapsed()))
+        });
+    }
+
+    let mut latencies = Vec::with_capacity(count);
+    let mut failed = 0;
+    while let Some(result) = tasks.join_next().await {
+        match result? {
+            Ok((true, latency)) => latencies.push(latency),
+            Ok((false, _)) => failed += 1,
+            Err(err) => {
+                eprintln!("transfer failed: {err}");
+                failed += 1;
+            }
+        }
+    }
+    let elapsed = started.elapsed();
+
+    println!();
+    println!("confirmed:   {} of {count}", latencies.len());
+    println!("failed:      {failed}");
+    println!("elapsed:     {elapsed:.2?}");
+    if latencies.is_empty() {
+        eyre::bail!("no transfer was confirmed");
+    }
+    latencies.sort();
+    let average = latencies.iter().sum::<Duration>() / latencies.len() as u32;
+    let throughput = latencies.len() as f64 / elapsed.as_secs_f64();
+    println!("throughput:  {throughput:.2} tx/s");
+    println!("latency avg: {average:.2?}");
+    println!("latency p50: {:.2?}", percentile(&latencies, 0.50));
+    println!("latency p95: {:.2?}", percentile(&latencies, 0.95));
+    Ok(())
+}
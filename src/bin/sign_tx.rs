@@ -0,0 +1,99 @@
+//! Signs a token `transfer` without touching the network and prints the raw
+//! transaction, ready for `eth_sendRawTransaction` from another machine.
+//!
+//! Everything a node would otherwise fill in is given up front, so this runs
+//! on an air-gapped machine:
+//!
+//! ```text
+//! PRIV_KEY_PATH=/opt/7d3f.pri cargo run --features cli --bin sign_tx -- \
+//!     --program-address 0xC4CA13280b8EafD7A033670E620B1AF74950E147 \
+//!     --to 0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E --amount 12.5 \
+//!     --nonce 7 --gas-limit 200000 --max-fee-per-gas 0.2 \
+//!     --max-priority-fee-per-gas 0 --chain-id 421614
+//! ```
+
+use clap::Parser;
+use ethers::{
+    abi::AbiEncode,
+    signers::Signer,
+    types::{Address, Eip1559TransactionRequest, U256},
+    utils::parse_units,
+};
+use stylus_hello_world::bindings::erc_20::TransferCall;
+use stylus_hello_world::client::{parse_checksummed, read_wallet_from_file};
+
+#[derive(Parser)]
+struct Cli {
+    /// Your private key file path; its account signs the transfer.
+    #[arg(long, env = "PRIV_KEY_PATH")]
+    priv_key_path: String,
+
+    /// Deployed token address.
+    #[arg(long, env = "STYLUS_PROGRAM_ADDRESS", value_parser = parse_checksummed)]
+    program_address: Address,
+
+    /// Receives the tokens.
+    #[arg(long, value_parser = parse_checksummed)]
+    to: Address,
+
+    /// Tokens to send, in whole tokens such as `12.5`.
+    #[arg(long)]
+    amount: String,
+
+    /// The token's decimals, which can't be read offline.
+    #[arg(long, default_value_t = 18)]
+    decimals: u8,
+
+    /// The sender's next nonce, e.g. from `cast nonce` on a connected machine.
+    #[arg(long)]
+    nonce: u64,
+
+    #[arg(long)]
+    gas_limit: u64,
+
+    /// Most wei per gas, in gwei, the transaction may pay in total.
+    #[arg(long, value_parser = parse_gwei)]
+    max_fee_per_gas: U256,
+
+    /// Most wei per gas, in gwei, the transaction may tip the sequencer.
+    #[arg(long, value_parser = parse_gwei, default_value = "0")]
+    max_priority_fee_per_gas: U256,
+
+    /// The chain the transaction is only valid on.
+    #[arg(long)]
+    chain_id: u64,
+}
+
+fn parse_gwei(input: &str) -> eyre::Result<U256> {
+    Ok(parse_units(input, 9)?.into())
+}
+
+fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+    eyre::ensure!(
+        cli.max_priority_fee_per_gas <= cli.max_fee_per_gas,
+        "--max-priority-fee-per-gas exceeds --max-fee-per-gas"
+    );
+    let value: U256 = parse_units(&cli.amount, u32::from(cli.decimals))?.into();
+    let wallet = read_wallet_from_file(&cli.priv_key_path)?.with_chain_id(cli.chain_id);
+
+    let tx = Eip1559TransactionRequest::new()
+        .from(wallet.address())
+        .to(cli.program_address)
+        .data(TransferCall { to: cli.to, value }.encode())
+        .nonce(cli.nonce)
+        .gas(cli.gas_limit)
+        .max_fee_per_gas(cli.max_fee_per_gas)
+        .max_priority_fee_per_gas(cli.max_priority_fee_per_gas)
+        .chain_id(cli.chain_id)
+        .into();
+    let signature = wallet.sign_transaction_sync(&tx)?;
+    eprintln!(
+        "signed a transfer of {value} base units to {:?} from {:?}, nonce {}",
+        cli.to,
+        wallet.address(),
+        cli.nonce
+    );
+    println!("{}", tx.rlp_signed(&signature));
+    Ok(())
+}
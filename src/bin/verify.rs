@@ -0,0 +1,69 @@
+//! Checks a deployed token's invariants and prints a PASS/FAIL line for
+//! each, exiting non-zero if any fails:
+//!
+//! ```text
+//! RPC_URL=http://localhost:8547 cargo run --features cli --bin verify -- \
+//!     --program-address 0xC4CA13280b8EafD7A033670E620B1AF74950E147 \
+//!     --decimals 18 --total-supply 1000000
+//! ```
+//!
+//! Pass `--weth` for a Weth program, to also check that its ETH covers its supply.
+
+use clap::Parser;
+use ethers::{
+    types::{Address, U256},
+    utils::parse_units,
+};
+use std::sync::Arc;
+use stylus_hello_world::client::{parse_checksummed, verify_deployment, ExpectedToken};
+use stylus_hello_world::transport;
+
+#[derive(Parser)]
+struct Cli {
+    /// Stylus RPC endpoint url.
+    #[arg(long, env = "RPC_URL")]
+    rpc_url: String,
+
+    /// Deployed token address.
+    #[arg(long, env = "STYLUS_PROGRAM_ADDRESS", value_parser = parse_checksummed)]
+    program_address: Address,
+
+    /// The decimals the token should report.
+    #[arg(long, default_value_t = 18)]
+    decimals: u8,
+
+    /// The supply the token should report, in whole tokens such as `1000.5`.
+    #[arg(long)]
+    total_supply: String,
+
+    /// Also checks that a Weth program is fully collateralized.
+    #[arg(long)]
+    weth: bool,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+    let total_supply: U256 = parse_units(&cli.total_supply, u32::from(cli.decimals))?.into();
+    let expected = ExpectedToken {
+        decimals: cli.decimals,
+        total_supply,
+        weth: cli.weth,
+    };
+    let provider = Arc::new(transport::connect(&cli.rpc_url).await?);
+    let checks = verify_deployment(provider, cli.program_address, &expected).await?;
+
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in &checks {
+        let verdict = if check.passed { "PASS" } else { "FAIL" };
+        println!("{verdict}  {:width$}  {}", check.name, check.detail);
+    }
+    let failed = checks.iter().filter(|c| !c.passed).count();
+    eyre::ensure!(
+        failed == 0,
+        "{failed} of {} checks failed for {:?}",
+        checks.len(),
+        cli.program_address
+    );
+    Ok(())
+}
@@ -0,0 +1,526 @@
+//! Typed ethers bindings for the programs in this crate. The ABI strings here
+//! are the single source of truth for Rust clients, so keep them in sync with
+//! `cargo stylus export-abi`.
+//!
+//! ```
+//! use ethers::{providers::{Http, Provider}, types::Address};
+//! use std::sync::Arc;
+//! use stylus_hello_world::bindings::Weth;
+//!
+//! // Instantiating doesn't touch the network, only calls do
+//! let provider = Provider::<Http>::try_from("http://localhost:8547").unwrap();
+//! let address: Address = "0xC4CA13280b8EafD7A033670E620B1AF74950E147".parse().unwrap();
+//! let weth = Weth::new(address, Arc::new(provider));
+//! assert_eq!(weth.address(), address);
+//! ```
+
+use ethers::{
+    abi::Abi,
+    prelude::abigen,
+    types::{Address, H160},
+};
+
+pub use crate::client::{connect, Client};
+
+abigen!(
+    Erc20,
+    r#"[
+        event Transfer(address indexed from, address indexed to, uint256 value)
+        event Approval(address indexed owner, address indexed spender, uint256 value)
+        event TransferAndCall(address indexed from, address indexed to, uint256 value, bytes data)
+        event Paused(address account)
+        event Unpaused(address account)
+        event Snapshot(uint256 id)
+        event AccountBlocked(address indexed account)
+        event AccountUnblocked(address indexed account)
+        event RateLimitSet(uint256 maxPerTx, uint256 maxPerWindow, uint256 windowSeconds)
+        event TokensRescued(address indexed token, address indexed to, uint256 amount)
+        event TokenUriUpdated(string uri)
+        event MetadataUpdated(string name, string symbol)
+        event EIP712DomainChanged()
+        event FlashFeeUpdated(uint256 feeBps, address recipient)
+        event AuthorizationUsed(address indexed authorizer, bytes32 indexed nonce)
+        event AuthorizationCanceled(address indexed authorizer, bytes32 indexed nonce)
+        event OwnershipTransferred(address indexed previousOwner, address indexed newOwner)
+        function init(string memory name, string memory symbol, uint8 decimals) external
+        function name() external view returns (string memory)
+        function symbol() external view returns (string memory)
+        function decimals() external view returns (uint8)
+        function setName(string memory name) external
+        function setSymbol(string memory symbol) external
+        function tokenMetadata() external view returns (string memory, string memory, uint8, uint256)
+        function tokenURI() external view returns (string memory)
+        function setTokenURI(string memory uri) external
+        function supportsInterface(bytes4 interfaceId) external pure returns (bool)
+        function totalSupply() external view returns (uint256)
+        function balanceOf(address account) external view returns (uint256)
+        function balancesOf(address[] accounts) external view returns (uint256[])
+        function holderCount() external view returns (uint256)
+        function transfer(address to, uint256 value) external returns (bool)
+        function transferAndCall(address to, uint256 value) external returns (bool)
+        function transferAndCall(address to, uint256 value, bytes memory data) external returns (bool)
+        function transferFromAndCall(address from, address to, uint256 value) external returns (bool)
+        function transferFromAndCall(address from, address to, uint256 value, bytes memory data) external returns (bool)
+        function approveAndCall(address spender, uint256 value) external returns (bool)
+        function approveAndCall(address spender, uint256 value, bytes memory data) external returns (bool)
+        function transferBatch(address[] memory recipients, uint256[] memory amounts) external returns (bool)
+        function transferBatch(address[] memory recipients, uint256[] memory amounts, bool strict) external returns (bool)
+        function approve(address spender, uint256 value) external returns (bool)
+        function increaseAllowance(address spender, uint256 added) external returns (bool)
+        function decreaseAllowance(address spender, uint256 subtracted) external returns (bool)
+        function transferFrom(address from, address to, uint256 value) external returns (bool)
+        function allowance(address owner, address spender) external view returns (uint256)
+        function mint(address to, uint256 value) external
+        function burn(uint256 value) external
+        function burnFrom(address account, uint256 value) external
+        function rescueTokens(address token, address to, uint256 amount) external
+        function maxFlashLoan(address token) external view returns (uint256)
+        function flashFee(address token, uint256 amount) external view returns (uint256)
+        function flashFeeRecipient() external view returns (address)
+        function setFlashFee(uint256 feeBps, address recipient) external
+        function flashLoan(address receiver, address token, uint256 amount, bytes memory data) external returns (bool)
+        function paused() external view returns (bool)
+        function pause() external
+        function unpause() external
+        function block(address account) external
+        function unblock(address account) external
+        function isBlocked(address account) external view returns (bool)
+        function rateLimit() external view returns (uint256, uint256, uint256)
+        function setRateLimit(uint256 maxPerTx, uint256 maxPerWindow, uint256 windowSeconds) external
+        function windowTotal(address account) external view returns (uint256)
+        function snapshot() external returns (uint256)
+        function balanceOfAt(address account, uint256 snapshotId) external view returns (uint256)
+        function totalSupplyAt(uint256 snapshotId) external view returns (uint256)
+        function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s) external
+        function nonces(address owner) external view returns (uint256)
+        function DOMAIN_SEPARATOR() external view returns (bytes32)
+        function eip712Domain() external view returns (bytes1 fields, string name, string version, uint256 chainId, address verifyingContract, bytes32 salt, uint256[] extensions)
+        function transferWithAuthorization(address from, address to, uint256 value, uint256 validAfter, uint256 validBefore, bytes32 nonce, uint8 v, bytes32 r, bytes32 s) external
+        function cancelAuthorization(address authorizer, bytes32 nonce, uint8 v, bytes32 r, bytes32 s) external
+        function authorizationState(address authorizer, bytes32 nonce) external view returns (bool)
+        function owner() external view returns (address)
+        function transferOwnership(address newOwner) external
+        function renounceOwnership() external
+        error InsufficientBalance(address from, uint256 have, uint256 want)
+        error InsufficientAllowance(address owner, address spender, uint256 have, uint256 want)
+        error InvalidReceiver(address receiver)
+        error InvalidSpender(address spender)
+        error Overflow()
+        error Underflow()
+        error ExpiredSignature(uint256 deadline)
+        error InvalidSigner(address signer, address owner)
+        error AuthorizationNotYetValid(uint256 validAfter)
+        error AuthorizationExpired(uint256 validBefore)
+        error AuthorizationAlreadyUsed(address authorizer, bytes32 nonce)
+        error LengthMismatch(uint256 recipients, uint256 amounts)
+        error DuplicateRecipient(address recipient)
+        error TooManyAccounts(uint256 count, uint256 max)
+        error TokenUriTooLong(uint256 length, uint256 max)
+        error MetadataTooLong(uint256 length, uint256 max)
+        error UnsupportedFlashToken(address token)
+        error FlashLoanTooLarge(uint256 amount, uint256 max)
+        error InvalidFlashFee(uint256 feeBps, uint256 max)
+        error CallbackFailed(address receiver)
+        error CannotRescueOwnToken(address token)
+        error RescueFailed(address token)
+        error UnauthorizedAccount(address account)
+        error InvalidOwner(address owner)
+        error AlreadyInitialized()
+        error EnforcedPause()
+        error ExpectedPause()
+        error InvalidSnapshotId(uint256 id)
+        error BlockedAccount(address account)
+        error ExceededTxLimit(uint256 value, uint256 maxPerTx)
+        error ExceededWindowLimit(address account, uint256 windowTotal, uint256 maxPerWindow)
+        error InvalidWindow()
+    ]"#
+);
+
+// Weth is an Erc20, so its ABI repeats Erc20's before adding its own methods
+abigen!(
+    Weth,
+    r#"[
+        event Transfer(address indexed from, address indexed to, uint256 value)
+        event Approval(address indexed owner, address indexed spender, uint256 value)
+        event TransferAndCall(address indexed from, address indexed to, uint256 value, bytes data)
+        event Paused(address account)
+        event Unpaused(address account)
+        event Snapshot(uint256 id)
+        event AccountBlocked(address indexed account)
+        event AccountUnblocked(address indexed account)
+        event RateLimitSet(uint256 maxPerTx, uint256 maxPerWindow, uint256 windowSeconds)
+        event TokensRescued(address indexed token, address indexed to, uint256 amount)
+        event TokenUriUpdated(string uri)
+        event MetadataUpdated(string name, string symbol)
+        event EIP712DomainChanged()
+        event FlashFeeUpdated(uint256 feeBps, address recipient)
+        event AuthorizationUsed(address indexed authorizer, bytes32 indexed nonce)
+        event AuthorizationCanceled(address indexed authorizer, bytes32 indexed nonce)
+        event OwnershipTransferred(address indexed previousOwner, address indexed newOwner)
+        function init(string memory name, string memory symbol, uint8 decimals) external
+        function name() external view returns (string memory)
+        function symbol() external view returns (string memory)
+        function decimals() external view returns (uint8)
+        function setName(string memory name) external
+        function setSymbol(string memory symbol) external
+        function tokenMetadata() external view returns (string memory, string memory, uint8, uint256)
+        function tokenURI() external view returns (string memory)
+        function setTokenURI(string memory uri) external
+        function supportsInterface(bytes4 interfaceId) external pure returns (bool)
+        function totalSupply() external view returns (uint256)
+        function balanceOf(address account) external view returns (uint256)
+        function balancesOf(address[] accounts) external view returns (uint256[])
+        function holderCount() external view returns (uint256)
+        function transfer(address to, uint256 value) external returns (bool)
+        function transferAndCall(address to, uint256 value) external returns (bool)
+        function transferAndCall(address to, uint256 value, bytes memory data) external returns (bool)
+        function transferFromAndCall(address from, address to, uint256 value) external returns (bool)
+        function transferFromAndCall(address from, address to, uint256 value, bytes memory data) external returns (bool)
+        function approveAndCall(address spender, uint256 value) external returns (bool)
+        function approveAndCall(address spender, uint256 value, bytes memory data) external returns (bool)
+        function transferBatch(address[] memory recipients, uint256[] memory amounts) external returns (bool)
+        function transferBatch(address[] memory recipients, uint256[] memory amounts, bool strict) external returns (bool)
+        function approve(address spender, uint256 value) external returns (bool)
+        function increaseAllowance(address spender, uint256 added) external returns (bool)
+        function decreaseAllowance(address spender, uint256 subtracted) external returns (bool)
+        function transferFrom(address from, address to, uint256 value) external returns (bool)
+        function allowance(address owner, address spender) external view returns (uint256)
+        function mint(address to, uint256 value) external
+        function burn(uint256 value) external
+        function burnFrom(address account, uint256 value) external
+        function rescueTokens(address token, address to, uint256 amount) external
+        function maxFlashLoan(address token) external view returns (uint256)
+        function flashFee(address token, uint256 amount) external view returns (uint256)
+        function flashFeeRecipient() external view returns (address)
+        function setFlashFee(uint256 feeBps, address recipient) external
+        function flashLoan(address receiver, address token, uint256 amount, bytes memory data) external returns (bool)
+        function paused() external view returns (bool)
+        function pause() external
+        function unpause() external
+        function block(address account) external
+        function unblock(address account) external
+        function isBlocked(address account) external view returns (bool)
+        function rateLimit() external view returns (uint256, uint256, uint256)
+        function setRateLimit(uint256 maxPerTx, uint256 maxPerWindow, uint256 windowSeconds) external
+        function windowTotal(address account) external view returns (uint256)
+        function snapshot() external returns (uint256)
+        function balanceOfAt(address account, uint256 snapshotId) external view returns (uint256)
+        function totalSupplyAt(uint256 snapshotId) external view returns (uint256)
+        function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s) external
+        function nonces(address owner) external view returns (uint256)
+        function DOMAIN_SEPARATOR() external view returns (bytes32)
+        function eip712Domain() external view returns (bytes1 fields, string name, string version, uint256 chainId, address verifyingContract, bytes32 salt, uint256[] extensions)
+        function transferWithAuthorization(address from, address to, uint256 value, uint256 validAfter, uint256 validBefore, bytes32 nonce, uint8 v, bytes32 r, bytes32 s) external
+        function cancelAuthorization(address authorizer, bytes32 nonce, uint8 v, bytes32 r, bytes32 s) external
+        function authorizationState(address authorizer, bytes32 nonce) external view returns (bool)
+        function owner() external view returns (address)
+        function transferOwnership(address newOwner) external
+        function renounceOwnership() external
+        error InsufficientBalance(address from, uint256 have, uint256 want)
+        error InsufficientAllowance(address owner, address spender, uint256 have, uint256 want)
+        error InvalidReceiver(address receiver)
+        error InvalidSpender(address spender)
+        error Overflow()
+        error Underflow()
+        error ExpiredSignature(uint256 deadline)
+        error InvalidSigner(address signer, address owner)
+        error AuthorizationNotYetValid(uint256 validAfter)
+        error AuthorizationExpired(uint256 validBefore)
+        error AuthorizationAlreadyUsed(address authorizer, bytes32 nonce)
+        error LengthMismatch(uint256 recipients, uint256 amounts)
+        error DuplicateRecipient(address recipient)
+        error TooManyAccounts(uint256 count, uint256 max)
+        error TokenUriTooLong(uint256 length, uint256 max)
+        error MetadataTooLong(uint256 length, uint256 max)
+        error UnsupportedFlashToken(address token)
+        error FlashLoanTooLarge(uint256 amount, uint256 max)
+        error InvalidFlashFee(uint256 feeBps, uint256 max)
+        error CallbackFailed(address receiver)
+        error CannotRescueOwnToken(address token)
+        error RescueFailed(address token)
+        error UnauthorizedAccount(address account)
+        error InvalidOwner(address owner)
+        error AlreadyInitialized()
+        error EnforcedPause()
+        error ExpectedPause()
+        error InvalidSnapshotId(uint256 id)
+        error BlockedAccount(address account)
+        error ExceededTxLimit(uint256 value, uint256 maxPerTx)
+        error ExceededWindowLimit(address account, uint256 windowTotal, uint256 maxPerWindow)
+        error InvalidWindow()
+        event Deposit(address indexed dst, uint256 wad)
+        event Withdrawal(address indexed src, uint256 wad)
+        event ExcessEthSwept(address indexed to, uint256 amount)
+        function deposit() external payable
+        function withdraw(uint256 amount) external
+        function withdrawAll() external returns (uint256)
+        function isFullyCollateralized() external view returns (bool)
+        function reserves() external view returns (uint256, uint256, uint256)
+        function sweepExcessEth(address to) external returns (uint256)
+        function depositTo(address account) external payable
+        function withdrawTo(address account, uint256 amount) external
+        function sum(uint256[] memory values) external pure returns (string memory, uint256)
+        function sumWithHelper(address helper, uint256[] memory values) external view returns (uint256)
+        error MintDisabled()
+        error NoExcessEth(uint256 balance, uint256 totalSupply)
+        error SumOverflow()
+        error InputTooLarge(uint256 length, uint256 max)
+        error ReentrantCall()
+        error HelperCallFailed(address helper, bool reverted)
+    ]"#
+);
+
+abigen!(
+    Helper,
+    r#"[
+        function sum(uint256[] memory values) external pure returns (string memory, uint256)
+        error SumOverflow()
+        error InputTooLarge(uint256 length, uint256 max)
+    ]"#
+);
+
+// Only CappedErc20's own methods; use `Erc20` at the same address for the rest
+abigen!(
+    CappedErc20,
+    r#"[
+        function init(string memory name, string memory symbol, uint8 decimals, uint256 cap) external
+        function cap() external view returns (uint256)
+        function mint(address to, uint256 value) external
+        error ExceededCap(uint256 increasedSupply, uint256 cap)
+        error CapRequired()
+        error ZeroCap()
+    ]"#
+);
+
+// Only FeeErc20's own methods; use `Erc20` at the same address for the rest
+abigen!(
+    FeeErc20,
+    r#"[
+        event FeeUpdated(uint256 feeBps)
+        event FeeRecipientUpdated(address indexed feeRecipient)
+        function feeBps() external view returns (uint256)
+        function feeRecipient() external view returns (address)
+        function setFeeBps(uint256 feeBps) external
+        function setFeeRecipient(address feeRecipient) external
+        error InvalidFee(uint256 feeBps)
+    ]"#
+);
+
+// Only HookedErc20's own events; use `Erc20` at the same address for its methods
+abigen!(
+    HookedErc20,
+    r#"[
+        event BeforeTokenTransfer(address from, address to, uint256 value, uint256 fromBalance, uint256 toBalance)
+        event AfterTokenTransfer(address from, address to, uint256 value, uint256 fromBalance, uint256 toBalance)
+    ]"#
+);
+
+// Only VotesErc20's own methods; use `Erc20` at the same address for the rest
+abigen!(
+    VotesErc20,
+    r#"[
+        event DelegateChanged(address indexed delegator, address indexed fromDelegate, address indexed toDelegate)
+        event DelegateVotesChanged(address indexed delegate, uint256 previousVotes, uint256 newVotes)
+        function clock() external view returns (uint64)
+        function delegate(address delegatee) external
+        function delegates(address account) external view returns (address)
+        function getVotes(address account) external view returns (uint256)
+        function getPastVotes(address account, uint256 blockNumber) external view returns (uint256)
+        error FutureLookup(uint256 blockNumber, uint256 currentBlock)
+    ]"#
+);
+
+/// Erc4626's own methods; use `Erc20` at the same address for the rest. In a
+/// module of its own, as its `Deposit` event and `MintDisabled` error would
+/// clash with Weth's.
+pub mod vault {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        Erc4626,
+        r#"[
+            event Deposit(address indexed sender, address indexed owner, uint256 assets, uint256 shares)
+            event Withdraw(address indexed sender, address indexed receiver, address indexed owner, uint256 assets, uint256 shares)
+            function init(string memory name, string memory symbol, address asset) external
+            function asset() external view returns (address)
+            function totalAssets() external view returns (uint256)
+            function convertToShares(uint256 assets) external view returns (uint256)
+            function convertToAssets(uint256 shares) external view returns (uint256)
+            function maxDeposit(address receiver) external view returns (uint256)
+            function maxMint(address receiver) external view returns (uint256)
+            function maxWithdraw(address owner) external view returns (uint256)
+            function maxRedeem(address owner) external view returns (uint256)
+            function previewDeposit(uint256 assets) external view returns (uint256)
+            function previewMint(uint256 shares) external view returns (uint256)
+            function previewWithdraw(uint256 assets) external view returns (uint256)
+            function previewRedeem(uint256 shares) external view returns (uint256)
+            function deposit(uint256 assets, address receiver) external returns (uint256)
+            function mint(uint256 shares, address receiver) external returns (uint256)
+            function withdraw(uint256 assets, address receiver, address owner) external returns (uint256)
+            function redeem(uint256 shares, address receiver, address owner) external returns (uint256)
+            function rescueTokens(address token, address to, uint256 amount) external
+            error AssetRequired()
+            error MintDisabled()
+            error AssetTransferFailed(address asset)
+            error CannotRescueAsset(address asset)
+        ]"#
+    );
+}
+
+/// Erc721's methods. In a module of its own, as its `Transfer` and
+/// `Approval` events and `InvalidReceiver` error would clash with Erc20's.
+pub mod nft {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        Erc721,
+        r#"[
+            event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)
+            event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId)
+            event ApprovalForAll(address indexed owner, address indexed operator, bool approved)
+            event OwnershipTransferred(address indexed previousOwner, address indexed newOwner)
+            function init(string memory name, string memory symbol) external
+            function name() external view returns (string memory)
+            function symbol() external view returns (string memory)
+            function supportsInterface(bytes4 interfaceId) external pure returns (bool)
+            function balanceOf(address owner) external view returns (uint256)
+            function ownerOf(uint256 tokenId) external view returns (address)
+            function approve(address approved, uint256 tokenId) external
+            function getApproved(uint256 tokenId) external view returns (address)
+            function setApprovalForAll(address operator, bool approved) external
+            function isApprovedForAll(address owner, address operator) external view returns (bool)
+            function transferFrom(address from, address to, uint256 tokenId) external
+            function safeTransferFrom(address from, address to, uint256 tokenId, bytes memory data) external
+            function safeTransferFrom(address from, address to, uint256 tokenId) external
+            function mint(address to, uint256 tokenId) external
+            function burn(uint256 tokenId) external
+            function owner() external view returns (address)
+            function transferOwnership(address newOwner) external
+            function renounceOwnership() external
+            error InvalidAccount(address account)
+            error NonexistentToken(uint256 tokenId)
+            error TokenAlreadyMinted(uint256 tokenId)
+            error IncorrectOwner(address from, uint256 tokenId, address owner)
+            error NotOwnerOrApproved(address operator, uint256 tokenId)
+            error InvalidReceiver(address receiver)
+            error UnauthorizedAccount(address account)
+            error InvalidOwner(address owner)
+            error AlreadyInitialized()
+        ]"#
+    );
+}
+
+/// Vesting's methods. In a module of its own, as its `AlreadyInitialized`
+/// error would clash with Erc20's.
+pub mod vesting {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        Vesting,
+        r#"[
+            event Released(address indexed token, address indexed beneficiary, uint256 amount)
+            function init(address token, address beneficiary, uint64 start, uint64 cliff, uint64 duration) external
+            function token() external view returns (address)
+            function beneficiary() external view returns (address)
+            function schedule() external view returns (uint64, uint64, uint64)
+            function released() external view returns (uint256)
+            function vestedAmount(uint64 timestamp) external view returns (uint256)
+            function releasable() external view returns (uint256)
+            function release() external returns (uint256)
+            error AlreadyInitialized()
+            error InvalidToken(address token)
+            error InvalidBeneficiary(address beneficiary)
+            error InvalidSchedule(uint64 start, uint64 cliff, uint64 duration)
+            error NothingToRelease()
+            error SafeErc20FailedOperation(address token)
+            error Overflow()
+        ]"#
+    );
+}
+
+/// Faucet's methods. In a module of its own, as its `Ownable` events and
+/// errors would clash with Erc20's.
+pub mod faucet {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        Faucet,
+        r#"[
+            event Claimed(address indexed account, uint256 amount)
+            event Refilled(address indexed from, uint256 amount)
+            event DripAmountSet(uint256 amount)
+            event CooldownSet(uint256 cooldownSeconds)
+            event OwnershipTransferred(address indexed previousOwner, address indexed newOwner)
+            function init(address token, uint256 dripAmount, uint256 cooldownSeconds) external
+            function token() external view returns (address)
+            function dripAmount() external view returns (uint256)
+            function cooldownSeconds() external view returns (uint256)
+            function nextClaimAt(address account) external view returns (uint256)
+            function claim() external returns (uint256)
+            function setDripAmount(uint256 amount) external
+            function setCooldown(uint256 cooldownSeconds) external
+            function refill(uint256 amount) external
+            function owner() external view returns (address)
+            function transferOwnership(address newOwner) external
+            function renounceOwnership() external
+            error ClaimTooSoon(address account, uint256 nextClaimAt)
+            error FaucetEmpty(uint256 balance, uint256 dripAmount)
+            error SafeErc20FailedOperation(address token)
+            error Overflow()
+            error UnauthorizedAccount(address account)
+            error InvalidOwner(address owner)
+            error AlreadyInitialized()
+        ]"#
+    );
+}
+
+/// Wrapper's own methods; use `Erc20` at the same address for the rest. In a
+/// module of its own, as its `init` and `MintDisabled` error would clash
+/// with Erc20's and Weth's.
+pub mod wrapper {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        Wrapper,
+        r#"[
+            event Deposited(address indexed account, uint256 amount)
+            event Withdrawn(address indexed account, uint256 amount)
+            event Recovered(address indexed to, uint256 amount)
+            function init(string memory name, string memory symbol, address underlying) external
+            function underlying() external view returns (address)
+            function deposit(uint256 amount) external
+            function withdraw(uint256 amount) external
+            function isFullyCollateralized() external view returns (bool)
+            function recover(address to) external returns (uint256)
+            function rescueTokens(address token, address to, uint256 amount) external
+            error UnderlyingRequired()
+            error MintDisabled()
+            error NothingToRecover(uint256 balance, uint256 totalSupply)
+            error CannotRescueUnderlying(address token)
+            error SafeErc20FailedOperation(address token)
+        ]"#
+    );
+}
+
+/// The ABIs the `export_abi` command writes, by file name. They are parsed
+/// from the same strings as the bindings above, so tooling reading the files
+/// sees exactly what Rust clients call.
+pub fn exported_abis() -> [(&'static str, &'static Abi); 3] {
+    [
+        ("Erc20.json", &ERC20_ABI),
+        ("Weth.json", &WETH_ABI),
+        ("Helper.json", &HELPER_ABI),
+    ]
+}
+
+/// The ArbWasm precompile at `0x71`, which activates deployed Stylus programs.
+pub const ARB_WASM: Address = H160([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x71,
+]);
+
+abigen!(
+    ArbWasm,
+    r#"[
+        function activateProgram(address program) external returns (uint16 version)
+    ]"#
+);
@@ -0,0 +1,58 @@
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::Address,
+    alloy_sol_types::{sol, SolError},
+    evm,
+    prelude::*,
+};
+
+sol_storage! {
+    /// Blocklist freezes accounts so they can neither send nor receive tokens.
+    pub struct Blocklist {
+        mapping(address => bool) blocked;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event AccountBlocked(address indexed account);
+    event AccountUnblocked(address indexed account);
+
+    error BlockedAccount(address account);
+}
+
+pub enum BlocklistError {
+    BlockedAccount(BlockedAccount),
+}
+
+impl From<BlocklistError> for Vec<u8> {
+    fn from(err: BlocklistError) -> Vec<u8> {
+        match err {
+            BlocklistError::BlockedAccount(e) => e.encode(),
+        }
+    }
+}
+
+// Access control is left to the parent contract, which knows who may block
+impl Blocklist {
+    pub fn is_blocked(&self, account: Address) -> bool {
+        self.blocked.get(account)
+    }
+
+    pub fn when_not_blocked(&self, account: Address) -> Result<(), BlocklistError> {
+        if self.blocked.get(account) {
+            return Err(BlocklistError::BlockedAccount(BlockedAccount { account }));
+        }
+        Ok(())
+    }
+
+    pub fn block(&mut self, account: Address) {
+        self.blocked.insert(account, true);
+        evm::log(AccountBlocked { account });
+    }
+
+    pub fn unblock(&mut self, account: Address) {
+        self.blocked.insert(account, false);
+        evm::log(AccountUnblocked { account });
+    }
+}
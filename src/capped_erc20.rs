@@ -0,0 +1,109 @@
+//! An Erc20 whose owner can only mint up to a fixed supply cap.
+//!
+//! Build with `--features capped` to make `CappedErc20` the program's
+//! entrypoint in place of `Weth`.
+
+use crate::erc20::{Erc20, Erc20Params};
+use crate::ownable::Ownable;
+use alloc::{string::String, vec::Vec};
+use core::borrow::{Borrow, BorrowMut};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::{sol, SolError},
+    prelude::*,
+};
+
+struct CappedParams;
+
+/// Metadata reported until `init` sets it
+impl Erc20Params for CappedParams {
+    const NAME: &'static str = "Capped Token Example";
+    const SYMBOL: &'static str = "CAP";
+    const DECIMALS: u8 = 18;
+    type Extension = SupplyCap;
+
+    // Flash loans mint too, so they must fit under the cap as well
+    fn max_flash_loan(token: &Erc20<Self>) -> U256 {
+        let cap = token.extension.cap.get();
+        cap.saturating_sub(token.total_supply.get())
+    }
+}
+
+sol_storage! {
+    #[entrypoint]
+    struct CappedErc20 {
+        #[borrow]
+        Erc20<CappedParams> erc20;
+    }
+
+    /// Kept in the Erc20 so its flash loan hook can see it
+    struct SupplyCap {
+        /// The most the total supply may ever reach, set by `init`
+        uint256 cap;
+    }
+}
+
+sol! {
+    error ExceededCap(uint256 increased_supply, uint256 cap);
+    error CapRequired();
+    error ZeroCap();
+}
+
+// Lets the router reach components nested inside erc20
+impl Borrow<Ownable> for CappedErc20 {
+    fn borrow(&self) -> &Ownable {
+        &self.erc20.ownable
+    }
+}
+
+impl BorrowMut<Ownable> for CappedErc20 {
+    fn borrow_mut(&mut self) -> &mut Ownable {
+        &mut self.erc20.ownable
+    }
+}
+
+#[external]
+#[inherit(Erc20<CappedParams>, Ownable)]
+impl CappedErc20 {
+    /// Makes the caller the owner and sets the metadata and supply cap,
+    /// which must be nonzero: a zero cap would leave nothing to mint.
+    #[selector(name = "init")]
+    pub fn init_with_cap(
+        &mut self,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        cap: U256,
+    ) -> Result<(), Vec<u8>> {
+        if cap == U256::ZERO {
+            return Err(ZeroCap {}.encode());
+        }
+        self.erc20.init(name, symbol, decimals)?;
+        self.erc20.extension.cap.set(cap);
+        Ok(())
+    }
+
+    // overrides Erc20's init, which would leave the cap unset
+    pub fn init(&mut self, _name: String, _symbol: String, _decimals: u8) -> Result<(), Vec<u8>> {
+        Err(CapRequired {}.encode())
+    }
+
+    pub fn cap(&self) -> Result<U256, Vec<u8>> {
+        Ok(self.erc20.extension.cap.get())
+    }
+
+    // overrides Erc20's mint to keep the total supply within the cap
+    pub fn mint(&mut self, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        self.erc20.ownable.only_owner()?;
+        let cap = self.erc20.extension.cap.get();
+        let increased_supply = self.erc20.total_supply.get().saturating_add(value);
+        if increased_supply > cap {
+            return Err(ExceededCap {
+                increased_supply,
+                cap,
+            }
+            .encode());
+        }
+        Ok(self.erc20.mint_impl(to, value)?)
+    }
+}
@@ -0,0 +1,807 @@
+//! Connection and wallet helpers for Rust clients of the deployed programs.
+
+use crate::bindings::{ArbWasm, Erc20, Weth, ARB_WASM};
+use crate::signer::ClientSigner;
+use crate::transport::{self, Transport};
+use ethers::{
+    abi::Detokenize,
+    contract::{ContractCall, ContractError},
+    middleware::{signer::SignerMiddlewareError, SignerMiddleware},
+    providers::{JsonRpcClient, Middleware, MiddlewareError, Provider, ProviderError},
+    signers::{LocalWallet, Signer, WalletError},
+    types::{
+        Address, BlockNumber, Bytes, TransactionReceipt, TransactionRequest, H160, H256, U256,
+    },
+    utils::{get_create2_address_from_hash, keccak256, to_checksum},
+};
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A provider that signs transactions with any [`ClientSigner`].
+pub type Client = SignerMiddleware<Provider<Transport>, ClientSigner>;
+
+/// Failures of the client helpers, kept distinct so callers can match on them.
+#[derive(Debug)]
+pub enum ClientError {
+    KeyFileRead(std::io::Error),
+    InvalidKeyFile(&'static str),
+    InvalidKey(WalletError),
+    InvalidAddress(String),
+    /// Mixed-case hex whose casing isn't the address's EIP-55 checksum
+    InvalidChecksum(String),
+    InvalidRpcUrl(String),
+    Rpc(ProviderError),
+    TxDataRead(std::io::Error),
+    WasmRead(std::io::Error),
+    /// The file doesn't start with the WASM magic number
+    InvalidWasm,
+    Send(SignerMiddlewareError<Provider<Transport>, ClientSigner>),
+    Activation(ContractError<Client>),
+    /// The node dropped the transaction from its mempool
+    Dropped,
+    Reverted(H256),
+    /// The node is on another chain than the one asked for
+    ChainIdMismatch {
+        expected: u64,
+        actual: u64,
+    },
+    /// The address has no code, e.g. an EOA or a program that was never deployed
+    NoContract(Address),
+    /// The node quotes a gas price, in wei, above the most the caller will pay
+    GasPriceTooHigh {
+        price: U256,
+        max: U256,
+    },
+    /// A CREATE2 factory would deploy somewhere other than predicted
+    Create2Mismatch {
+        predicted: Address,
+        actual: Address,
+    },
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeyFileRead(err) => write!(f, "could not read private key file: {err}"),
+            Self::InvalidKeyFile(reason) => write!(f, "invalid private key file: {reason}"),
+            Self::InvalidKey(err) => write!(f, "invalid private key: {err}"),
+            Self::InvalidAddress(input) => write!(f, "invalid address: {input}"),
+            Self::InvalidChecksum(input) => write!(f, "invalid EIP-55 checksum: {input}"),
+            Self::InvalidRpcUrl(url) => write!(f, "invalid rpc url: {url}"),
+            Self::Rpc(err) => write!(f, "rpc error: {err}"),
+            Self::TxDataRead(err) => write!(f, "could not read deployment tx data: {err}"),
+            Self::WasmRead(err) => write!(f, "could not read wasm: {err}"),
+            Self::InvalidWasm => write!(f, "not a wasm module"),
+            Self::Send(err) => write!(f, "could not send transaction: {err}"),
+            Self::Activation(err) => write!(f, "could not activate program: {err}"),
+            Self::Dropped => write!(f, "transaction was dropped"),
+            Self::Reverted(hash) => write!(f, "transaction {hash:?} reverted"),
+            Self::ChainIdMismatch { expected, actual } => write!(
+                f,
+                "expected chain id {expected}, but the node is on chain {actual}"
+            ),
+            Self::NoContract(address) => write!(f, "no contract deployed at {address:?}"),
+            Self::GasPriceTooHigh { price, max } => {
+                write!(f, "gas price of {price} wei is above the {max} wei limit")
+            }
+            Self::Create2Mismatch { predicted, actual } => write!(
+                f,
+                "CREATE2 deployment predicted at {predicted:?} would land at {actual:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::KeyFileRead(err) => Some(err),
+            Self::InvalidKey(err) => Some(err),
+            Self::Rpc(err) => Some(err),
+            Self::TxDataRead(err) => Some(err),
+            Self::WasmRead(err) => Some(err),
+            Self::Send(err) => Some(err),
+            Self::Activation(err) => Some(err),
+            Self::InvalidKeyFile(_)
+            | Self::InvalidAddress(_)
+            | Self::InvalidChecksum(_)
+            | Self::InvalidRpcUrl(_)
+            | Self::InvalidWasm
+            | Self::Dropped
+            | Self::Reverted(_)
+            | Self::ChainIdMismatch { .. }
+            | Self::NoContract(_)
+            | Self::GasPriceTooHigh { .. }
+            | Self::Create2Mismatch { .. } => None,
+        }
+    }
+}
+
+pub fn parse_address(input: &str) -> Result<Address, ClientError> {
+    input
+        .parse()
+        .map_err(|_| ClientError::InvalidAddress(input.to_string()))
+}
+
+/// Like [`parse_address`], but also rejects mixed-case input that isn't
+/// EIP-55 checksummed, which is likely a mistyped address. All-lowercase and
+/// all-uppercase input carries no checksum and is accepted as is.
+///
+/// ```
+/// use stylus_hello_world::client::{parse_checksummed, ClientError};
+///
+/// let checksummed = "0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E";
+/// let address = parse_checksummed(checksummed).unwrap();
+/// assert_eq!(parse_checksummed(&checksummed.to_lowercase()).unwrap(), address);
+/// assert_eq!(parse_checksummed("0x3F1EAE7D46D88F08FC2F8ED27FCB2AB183EB2D0E").unwrap(), address);
+///
+/// let miscased = "0x3f1eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E";
+/// assert!(matches!(parse_checksummed(miscased), Err(ClientError::InvalidChecksum(_))));
+/// assert!(matches!(parse_checksummed("0x3f1E"), Err(ClientError::InvalidAddress(_))));
+/// ```
+pub fn parse_checksummed(input: &str) -> Result<Address, ClientError> {
+    let address = parse_address(input)?;
+    let hex = input.strip_prefix("0x").unwrap_or(input);
+    let mixed_case =
+        hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && to_checksum(&address, None)[2..] != *hex {
+        return Err(ClientError::InvalidChecksum(input.to_string()));
+    }
+    Ok(address)
+}
+
+/// Reads a hex private key, with or without a `0x` prefix, from a single-line file.
+pub fn read_secret_from_file(fpath: &str) -> Result<String, ClientError> {
+    let contents = std::fs::read_to_string(fpath).map_err(ClientError::KeyFileRead)?;
+    let mut lines = contents.lines().map(str::trim).filter(|l| !l.is_empty());
+    let secret = lines.next().unwrap_or_default();
+    // A second key line would otherwise be silently ignored
+    if lines.next().is_some() {
+        return Err(ClientError::InvalidKeyFile(
+            "key file must contain exactly one non-empty line",
+        ));
+    }
+    let secret = secret
+        .strip_prefix("0x")
+        .or_else(|| secret.strip_prefix("0X"))
+        .unwrap_or(secret);
+    if secret.len() != 64 || !secret.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ClientError::InvalidKeyFile(
+            "private key must be 32 bytes of hex",
+        ));
+    }
+    Ok(secret.to_string())
+}
+
+/// Loads the wallet whose private key is stored in `fpath`.
+pub fn read_wallet_from_file(fpath: &str) -> Result<LocalWallet, ClientError> {
+    let privkey = read_secret_from_file(fpath)?;
+    LocalWallet::from_str(&privkey).map_err(ClientError::InvalidKey)
+}
+
+/// Connects to `rpc_url` over the transport its scheme selects, see
+/// [`Endpoint::parse`](crate::transport::Endpoint::parse), and signs with
+/// `signer` for the chain the node reports.
+pub async fn connect(
+    rpc_url: &str,
+    signer: impl Into<ClientSigner>,
+    policy: RetryPolicy,
+) -> Result<Arc<Client>, ClientError> {
+    connect_to_chain(rpc_url, signer, policy, None).await
+}
+
+/// Like [`connect`], but with `expected_chain_id` set, fails unless the node is
+/// on that chain, so a wrong `rpc_url` can't send transactions to another network.
+pub async fn connect_to_chain(
+    rpc_url: &str,
+    signer: impl Into<ClientSigner>,
+    policy: RetryPolicy,
+    expected_chain_id: Option<u64>,
+) -> Result<Arc<Client>, ClientError> {
+    let provider = transport::connect(rpc_url).await?;
+    let chain_id = resolve_chain_id(&provider, expected_chain_id, policy).await?;
+    Ok(Arc::new(SignerMiddleware::new(
+        provider,
+        signer.into().with_chain_id(chain_id),
+    )))
+}
+
+/// The chain id `provider` reports, checked against `expected` when given.
+///
+/// ```
+/// use ethers::providers::Provider;
+/// use ethers::types::U256;
+/// use stylus_hello_world::client::{resolve_chain_id, ClientError, RetryPolicy};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (provider, mock) = Provider::mocked();
+/// mock.push(U256::from(42161)).unwrap();
+/// let err = resolve_chain_id(&provider, Some(421614), RetryPolicy::default()).await;
+/// assert!(matches!(
+///     err,
+///     Err(ClientError::ChainIdMismatch { expected: 421614, actual: 42161 })
+/// ));
+///
+/// mock.push(U256::from(42161)).unwrap();
+/// let chain_id = resolve_chain_id(&provider, None, RetryPolicy::default()).await;
+/// assert_eq!(chain_id.unwrap(), 42161);
+/// # }
+/// ```
+pub async fn resolve_chain_id<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    expected: Option<u64>,
+    policy: RetryPolicy,
+) -> Result<u64, ClientError> {
+    let actual = retry(policy, || provider.get_chainid())
+        .await
+        .map_err(ClientError::Rpc)?
+        .as_u64();
+    match expected {
+        Some(expected) if expected != actual => {
+            Err(ClientError::ChainIdMismatch { expected, actual })
+        }
+        _ => Ok(actual),
+    }
+}
+
+/// Fails with [`ClientError::NoContract`] unless there is code at `address`,
+/// so calls to a mistyped address don't fail later with confusing decode errors.
+///
+/// ```
+/// use ethers::providers::Provider;
+/// use ethers::types::{Address, Bytes};
+/// use stylus_hello_world::client::{ensure_deployed, ClientError};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (provider, mock) = Provider::mocked();
+/// mock.push::<Bytes, _>(Bytes::new()).unwrap();
+/// let address = Address::repeat_byte(0x11);
+/// let err = ensure_deployed(&provider, address).await.unwrap_err();
+/// assert!(matches!(err, ClientError::NoContract(a) if a == address));
+/// assert_eq!(
+///     err.to_string(),
+///     "no contract deployed at 0x1111111111111111111111111111111111111111"
+/// );
+///
+/// mock.push::<Bytes, _>(Bytes::from(vec![0xef, 0xf0, 0x00])).unwrap();
+/// assert!(ensure_deployed(&provider, address).await.is_ok());
+/// # }
+/// ```
+pub async fn ensure_deployed<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    address: Address,
+) -> Result<(), ClientError> {
+    let code = provider
+        .get_code(address, None)
+        .await
+        .map_err(ClientError::Rpc)?;
+    if code.is_empty() {
+        return Err(ClientError::NoContract(address));
+    }
+    Ok(())
+}
+
+/// Fails with [`ClientError::GasPriceTooHigh`] if the node quotes a gas price
+/// above `max` wei, so nothing is sent while the network is congested. On
+/// Arbitrum the quote is the current base fee, which every transaction pays.
+/// Returns the quoted price.
+///
+/// ```
+/// use ethers::providers::Provider;
+/// use ethers::types::U256;
+/// use stylus_hello_world::client::{check_gas_price, ClientError};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (provider, mock) = Provider::mocked();
+/// let gwei = U256::exp10(9);
+/// mock.push(gwei * 50).unwrap();
+/// let err = check_gas_price(&provider, gwei * 2).await.unwrap_err();
+/// assert!(matches!(
+///     err,
+///     ClientError::GasPriceTooHigh { price, max } if price == gwei * 50 && max == gwei * 2
+/// ));
+///
+/// mock.push(gwei / 10).unwrap();
+/// assert_eq!(check_gas_price(&provider, gwei * 2).await.unwrap(), gwei / 10);
+/// # }
+/// ```
+pub async fn check_gas_price<P: JsonRpcClient>(
+    provider: &Provider<P>,
+    max: U256,
+) -> Result<U256, ClientError> {
+    let price = provider.get_gas_price().await.map_err(ClientError::Rpc)?;
+    if price > max {
+        return Err(ClientError::GasPriceTooHigh { price, max });
+    }
+    Ok(price)
+}
+
+/// The name of a known Arbitrum chain.
+///
+/// ```
+/// use stylus_hello_world::client::chain_name;
+///
+/// assert_eq!(chain_name(42161), Some("Arbitrum One"));
+/// assert_eq!(chain_name(1), None);
+/// ```
+pub fn chain_name(chain_id: u64) -> Option<&'static str> {
+    match chain_id {
+        42161 => Some("Arbitrum One"),
+        42170 => Some("Arbitrum Nova"),
+        421614 => Some("Arbitrum Sepolia"),
+        23011913 => Some("Stylus testnet"),
+        _ => None,
+    }
+}
+
+/// Reads deployment tx data, as written by
+/// `cargo stylus deploy --dry-run --output-tx-data`, in hex or raw bytes.
+pub fn read_tx_data(fpath: &str) -> Result<Bytes, ClientError> {
+    let contents = std::fs::read(fpath).map_err(ClientError::TxDataRead)?;
+    let hex = std::str::from_utf8(&contents)
+        .ok()
+        .and_then(|text| text.trim().parse::<Bytes>().ok());
+    Ok(hex.unwrap_or_else(|| contents.into()))
+}
+
+/// Reads a compiled program, e.g.
+/// `target/wasm32-unknown-unknown/release/stylus_hello_world.wasm`, and
+/// builds its deployment tx data with [`deployment_tx_data`].
+pub fn read_wasm(fpath: &str) -> Result<Bytes, ClientError> {
+    let wasm = std::fs::read(fpath).map_err(ClientError::WasmRead)?;
+    if !wasm.starts_with(b"\0asm") {
+        return Err(ClientError::InvalidWasm);
+    }
+    Ok(deployment_tx_data(&wasm))
+}
+
+/// Builds deployment tx data the way `cargo stylus deploy` does: init code
+/// that returns the brotli-compressed WASM, behind the `0xEFF000` prefix
+/// marking it as a Stylus program, as the contract's code.
+///
+/// ```
+/// use stylus_hello_world::client::deployment_tx_data;
+///
+/// let tx_data = deployment_tx_data(b"\0asm\x01\0\0\0");
+/// // PUSH32 <code length> DUP1 PUSH1 43 PUSH1 0 CODECOPY PUSH1 0 RETURN <version 0>
+/// assert_eq!(tx_data[0], 0x7f);
+/// assert_eq!(tx_data[33..43], [0x80, 0x60, 43, 0x60, 0, 0x39, 0x60, 0, 0xf3, 0]);
+/// assert_eq!(tx_data[43..46], [0xef, 0xf0, 0x00]);
+/// assert_eq!(tx_data[32] as usize, tx_data.len() - 43);
+/// ```
+pub fn deployment_tx_data(wasm: &[u8]) -> Bytes {
+    let params = brotli::enc::BrotliEncoderParams {
+        quality: 11,
+        lgwin: 22,
+        ..Default::default()
+    };
+    let mut code = vec![0xef, 0xf0, 0x00];
+    brotli::BrotliCompress(&mut &wasm[..], &mut code, &params).expect("writes to a Vec");
+
+    let mut tx_data = vec![0x7f];
+    tx_data.extend(H256::from_low_u64_be(code.len() as u64).as_bytes());
+    // Copy the code that follows this 43-byte prelude into memory and return it
+    tx_data.extend([0x80, 0x60, 43, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3]);
+    tx_data.push(0x00);
+    tx_data.extend(code);
+    tx_data.into()
+}
+
+/// Deploys a Stylus program from its deployment tx data, then activates it
+/// through ArbWasm so it can be called. Returns the program's address.
+pub async fn deploy_program(client: Arc<Client>, tx_data: Bytes) -> Result<Address, ClientError> {
+    let tx = TransactionRequest::new().data(tx_data);
+    let pending = client
+        .send_transaction(tx, None)
+        .await
+        .map_err(ClientError::Send)?;
+    let receipt = succeeded(pending.await.map_err(ClientError::Rpc)?)?;
+    let address = receipt
+        .contract_address
+        .ok_or(ClientError::Reverted(receipt.transaction_hash))?;
+    activate(client, address).await?;
+    Ok(address)
+}
+
+async fn activate(client: Arc<Client>, address: Address) -> Result<(), ClientError> {
+    let activate = ArbWasm::new(ARB_WASM, client).activate_program(address);
+    let pending = activate.send().await.map_err(ClientError::Activation)?;
+    succeeded(pending.await.map_err(ClientError::Rpc)?)?;
+    Ok(())
+}
+
+/// The widely deployed deterministic deployment proxy, which CREATE2-deploys
+/// the init code that follows a 32-byte salt in its calldata and returns the
+/// new address.
+pub const CREATE2_FACTORY: Address = H160([
+    0x4e, 0x59, 0xb4, 0x48, 0x47, 0xb3, 0x79, 0x57, 0x85, 0x88, 0x92, 0x0c, 0xa7, 0x8f, 0xbf, 0x26,
+    0xc0, 0xb4, 0x95, 0x6c,
+]);
+
+/// Where `factory` CREATE2-deploys init code hashing to `init_code_hash`
+/// with `salt`: the last 20 bytes of
+/// `keccak256(0xff ++ factory ++ salt ++ init_code_hash)`.
+///
+/// ```
+/// use ethers::types::{Address, H256};
+/// use ethers::utils::keccak256;
+/// use stylus_hello_world::client::predict_create2_address;
+///
+/// // Example 0 of EIP-1014: the one-byte init code 0x00 with a zero salt
+/// let address = predict_create2_address(Address::zero(), H256::zero(), keccak256([0x00]).into());
+/// assert_eq!(address, "0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38".parse().unwrap());
+/// ```
+pub fn predict_create2_address(factory: Address, salt: H256, init_code_hash: H256) -> Address {
+    get_create2_address_from_hash(factory, salt, init_code_hash)
+}
+
+/// Where [`deploy_program_create2`] put a program, which is the same
+/// address on every chain for the same factory, salt and tx data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Create2Deployment {
+    pub predicted: Address,
+    pub actual: Address,
+}
+
+/// Like [`deploy_program`], but deploys through the CREATE2 `factory`, see
+/// [`CREATE2_FACTORY`], so the address depends only on it, `salt` and
+/// `tx_data`. The factory is simulated first and nothing is sent unless it
+/// would deploy at the predicted address.
+pub async fn deploy_program_create2(
+    client: Arc<Client>,
+    factory: Address,
+    salt: H256,
+    tx_data: Bytes,
+) -> Result<Create2Deployment, ClientError> {
+    ensure_deployed(client.provider(), factory).await?;
+    let predicted = predict_create2_address(factory, salt, keccak256(&tx_data).into());
+    let calldata = [salt.as_bytes(), &tx_data].concat();
+    let tx = TransactionRequest::new()
+        .from(client.address())
+        .to(factory)
+        .data(calldata)
+        .into();
+    let output = client
+        .provider()
+        .call(&tx, None)
+        .await
+        .map_err(ClientError::Rpc)?;
+    // An address already taken, or init code that fails, deploys nothing
+    let actual = match output.len() {
+        20 => Address::from_slice(&output),
+        _ => Address::zero(),
+    };
+    if actual != predicted {
+        return Err(ClientError::Create2Mismatch { predicted, actual });
+    }
+
+    let pending = client
+        .send_transaction(tx, None)
+        .await
+        .map_err(ClientError::Send)?;
+    succeeded(pending.await.map_err(ClientError::Rpc)?)?;
+    ensure_deployed(client.provider(), predicted).await?;
+    activate(client, predicted).await?;
+    Ok(Create2Deployment { predicted, actual })
+}
+
+fn succeeded(receipt: Option<TransactionReceipt>) -> Result<TransactionReceipt, ClientError> {
+    let receipt = receipt.ok_or(ClientError::Dropped)?;
+    if receipt.status != Some(1.into()) {
+        return Err(ClientError::Reverted(receipt.transaction_hash));
+    }
+    Ok(receipt)
+}
+
+/// What [`verify_deployment`] expects of a deployed token.
+#[derive(Clone, Debug)]
+pub struct ExpectedToken {
+    pub decimals: u8,
+    /// In base units
+    pub total_supply: U256,
+    /// Also checks that the program is a fully collateralized Weth
+    pub weth: bool,
+}
+
+/// One invariant [`verify_deployment`] checked, and what it found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, passed: bool, detail: impl fmt::Display) -> Self {
+        Self {
+            name,
+            passed,
+            detail: detail.to_string(),
+        }
+    }
+
+    // A failed call fails its check rather than the whole verification
+    fn from_call<T, E: fmt::Display>(
+        name: &'static str,
+        result: Result<T, E>,
+        check: impl FnOnce(&T) -> (bool, String),
+    ) -> Self {
+        match result {
+            Ok(value) => {
+                let (passed, detail) = check(&value);
+                Self::new(name, passed, detail)
+            }
+            Err(err) => Self::new(name, false, format!("call failed: {err}")),
+        }
+    }
+}
+
+/// Checks the token at `address` against `expected`, one [`Check`] per
+/// invariant. Only an unreachable node is an error; everything else is a
+/// failed check. With no code at `address` that is the only check made.
+pub async fn verify_deployment<P: JsonRpcClient + 'static>(
+    provider: Arc<Provider<P>>,
+    address: Address,
+    expected: &ExpectedToken,
+) -> Result<Vec<Check>, ClientError> {
+    let code = provider
+        .get_code(address, None)
+        .await
+        .map_err(ClientError::Rpc)?;
+    let mut checks = vec![Check::new(
+        "code present",
+        !code.is_empty(),
+        format!("{} bytes", code.len()),
+    )];
+    if code.is_empty() {
+        return Ok(checks);
+    }
+
+    let token = Erc20::new(address, provider.clone());
+    checks.push(Check::from_call(
+        "decimals",
+        token.decimals().call().await,
+        |&decimals| {
+            let passed = decimals == expected.decimals;
+            (
+                passed,
+                format!("{decimals}, expected {}", expected.decimals),
+            )
+        },
+    ));
+    checks.push(Check::from_call(
+        "name",
+        token.name().call().await,
+        |name| (!name.is_empty(), format!("{name:?}")),
+    ));
+    checks.push(Check::from_call(
+        "symbol",
+        token.symbol().call().await,
+        |symbol| (!symbol.is_empty(), format!("{symbol:?}")),
+    ));
+    checks.push(Check::from_call(
+        "total supply",
+        token.total_supply().call().await,
+        |&supply| {
+            let passed = supply == expected.total_supply;
+            (
+                passed,
+                format!("{supply}, expected {}", expected.total_supply),
+            )
+        },
+    ));
+    if expected.weth {
+        let weth = Weth::new(address, provider);
+        checks.push(Check::from_call(
+            "fully collateralized",
+            weth.is_fully_collateralized().call().await,
+            |&collateralized| (collateralized, collateralized.to_string()),
+        ));
+    }
+    Ok(checks)
+}
+
+/// How many times, and how patiently, [`retry`] repeats a transient failure.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub retries: u32,
+    /// Delay before the first retry, doubled before each one after it
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Errors that may succeed if the request is simply sent again.
+pub trait Transient {
+    fn is_transient(&self) -> bool;
+}
+
+impl Transient for ProviderError {
+    // Connection failures and rate limiting are worth retrying. Any other
+    // JSON-RPC error response, reverts included, is the node's final answer.
+    fn is_transient(&self) -> bool {
+        match self {
+            ProviderError::JsonRpcClientError(err) => match err.as_error_response() {
+                // EIP-1474 "Limit exceeded"
+                Some(response) => response.code == -32005,
+                None => !err.is_serde_error(),
+            },
+            ProviderError::HTTPError(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<M: Middleware> Transient for ContractError<M> {
+    fn is_transient(&self) -> bool {
+        match self {
+            ContractError::MiddlewareError { e } => {
+                e.as_provider_error().is_some_and(Transient::is_transient)
+            }
+            ContractError::ProviderError { e } => e.is_transient(),
+            _ => false,
+        }
+    }
+}
+
+impl Transient for ClientError {
+    fn is_transient(&self) -> bool {
+        matches!(self, Self::Rpc(err) if err.is_transient())
+    }
+}
+
+/// Runs `op` until it succeeds, fails with a non-transient error, or runs out
+/// of retries, backing off exponentially between attempts.
+///
+/// ```
+/// use ethers::providers::{JsonRpcError, Middleware, MockResponse, Provider};
+/// use ethers::types::U256;
+/// use std::time::Duration;
+/// use stylus_hello_world::client::{retry, RetryPolicy};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (provider, mock) = Provider::mocked();
+/// // Responses are popped from the back: two rate limits, then the chain id
+/// mock.push(U256::from(23011913)).unwrap();
+/// for _ in 0..2 {
+///     mock.push_response(MockResponse::Error(JsonRpcError {
+///         code: -32005,
+///         message: "limit exceeded".into(),
+///         data: None,
+///     }));
+/// }
+/// let policy = RetryPolicy { retries: 2, base_delay: Duration::from_millis(1) };
+/// let chain_id = retry(policy, || provider.get_chainid()).await.unwrap();
+/// assert_eq!(chain_id, U256::from(23011913));
+/// # }
+/// ```
+pub async fn retry<T, E, F, Fut>(policy: RetryPolicy, mut op: F) -> Result<T, E>
+where
+    E: Transient,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut delay = policy.base_delay;
+    let mut retries = 0;
+    loop {
+        match op().await {
+            Err(err) if retries < policy.retries && err.is_transient() => {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Hands out consecutive nonces so a batch of transactions can be sent
+/// without waiting for each one to be mined first.
+///
+/// ```
+/// use ethers::providers::Provider;
+/// use ethers::types::{Address, U256};
+/// use std::sync::Arc;
+/// use stylus_hello_world::bindings::Erc20;
+/// use stylus_hello_world::client::NonceManager;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (provider, mock) = Provider::mocked();
+/// // The account's pending transaction count
+/// mock.push(U256::from(7)).unwrap();
+/// let mut nonces = NonceManager::fetch(&provider, Address::zero()).await.unwrap();
+///
+/// let token = Erc20::new(Address::zero(), Arc::new(provider));
+/// let calls = [
+///     nonces.assign(token.approve(Address::zero(), U256::one())),
+///     nonces.assign(token.transfer(Address::zero(), U256::one())),
+/// ];
+/// assert_eq!(calls[0].tx.nonce(), Some(&U256::from(7)));
+/// assert_eq!(calls[1].tx.nonce(), Some(&U256::from(8)));
+/// assert_eq!(nonces.next(), U256::from(9));
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct NonceManager {
+    next: U256,
+}
+
+impl NonceManager {
+    /// Starts from `account`'s pending nonce, so transactions already in the
+    /// mempool are not replaced.
+    pub async fn fetch<M: Middleware>(client: &M, account: Address) -> Result<Self, M::Error> {
+        let next = client
+            .get_transaction_count(account, Some(BlockNumber::Pending.into()))
+            .await?;
+        Ok(Self { next })
+    }
+
+    /// The nonce the next assigned call will get.
+    pub fn next(&self) -> U256 {
+        self.next
+    }
+
+    /// Sets the next nonce on `call`.
+    pub fn assign<M: Middleware, D: Detokenize>(
+        &mut self,
+        call: ContractCall<M, D>,
+    ) -> ContractCall<M, D> {
+        let nonce = self.next;
+        self.next += U256::one();
+        call.nonce(nonce)
+    }
+}
+
+/// A [`NonceManager`] for concurrent senders. Each [`send`] holds it from
+/// assigning the nonce until the node has accepted the transaction, so
+/// transactions reach the node in nonce order however the senders race.
+///
+/// [`send`]: SharedNonceManager::send
+#[derive(Clone, Debug)]
+pub struct SharedNonceManager {
+    nonces: Arc<tokio::sync::Mutex<NonceManager>>,
+}
+
+impl SharedNonceManager {
+    pub fn new(nonces: NonceManager) -> Self {
+        Self {
+            nonces: Arc::new(tokio::sync::Mutex::new(nonces)),
+        }
+    }
+
+    /// The nonce the next sent call will get.
+    pub async fn next(&self) -> U256 {
+        self.nonces.lock().await.next()
+    }
+
+    /// Sends `call` with the next nonce, returning the nonce and the
+    /// transaction hash. A failed send gives its nonce back for the next call.
+    pub async fn send<M: Middleware, D: Detokenize>(
+        &self,
+        call: ContractCall<M, D>,
+    ) -> Result<(U256, H256), ContractError<M>> {
+        let mut nonces = self.nonces.lock().await;
+        let nonce = nonces.next();
+        let pending = nonces.assign(call).send().await.map(|tx| *tx);
+        if pending.is_err() {
+            nonces.next = nonce;
+        }
+        Ok((nonce, pending?))
+    }
+}
@@ -0,0 +1,30 @@
+//! ERC-165 interface detection. An interface id is the XOR of the selectors
+//! of the functions it declares, computed here at compile time.
+
+use stylus_sdk::{alloy_primitives::FixedBytes, function_selector};
+
+/// XORs function selectors into an interface id.
+///
+/// ```
+/// use stylus_hello_world::erc165::{interface_id, IERC165_ID};
+///
+/// assert_eq!(IERC165_ID, interface_id([[0x01, 0xff, 0xc9, 0xa7]]));
+/// assert_eq!(interface_id([[0xf0; 4], [0x0f; 4]]).0, [0xff; 4]);
+/// ```
+pub const fn interface_id<const N: usize>(selectors: [[u8; 4]; N]) -> FixedBytes<4> {
+    let mut id = [0; 4];
+    let mut i = 0;
+    while i < N {
+        let mut j = 0;
+        while j < 4 {
+            id[j] ^= selectors[i][j];
+            j += 1;
+        }
+        i += 1;
+    }
+    FixedBytes(id)
+}
+
+/// `supportsInterface(bytes4)`, i.e. `0x01ffc9a7`.
+pub const IERC165_ID: FixedBytes<4> =
+    interface_id([function_selector!("supportsInterface", FixedBytes<4>)]);
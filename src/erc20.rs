@@ -1,27 +1,203 @@
-use alloc::{string::String, vec::Vec};
+use crate::abi;
+use crate::blocklist::{Blocklist, BlocklistError};
+use crate::erc165::{interface_id, IERC165_ID};
+use crate::math::{self, MathError};
+use crate::ownable::{Ownable, OwnableError};
+use crate::pausable::{Pausable, PausableError};
+use crate::rate_limit::{RateLimit, RateLimitError};
+use crate::snapshots::{Snapshots, SnapshotsError};
+use alloc::{borrow::Cow, string::String, vec::Vec};
 use core::marker::PhantomData;
 use stylus_sdk::{
-    alloy_primitives::{Address, U256},
-    alloy_sol_types::{sol, SolError},
-    evm, msg,
+    abi::Bytes,
+    alloy_primitives::{address, Address, FixedBytes, B256, U256, U8},
+    alloy_sol_types::{sol, Eip712Domain, SolCall, SolError, SolStruct},
+    block,
+    call::RawCall,
+    contract, evm, function_selector, msg,
     prelude::*,
+    storage::StorageType,
 };
 
-pub trait Erc20Params {
+/// The ERC-20 interface id, `0x36372b07`.
+///
+/// ```
+/// use stylus_hello_world::erc165::IERC165_ID;
+/// use stylus_hello_world::erc20::{Erc20, Erc20Params, NoExtension, IERC1363_ID, IERC20_ID};
+///
+/// struct Params;
+/// impl Erc20Params for Params {
+///     const NAME: &'static str = "Example";
+///     const SYMBOL: &'static str = "EX";
+///     const DECIMALS: u8 = 18;
+///     type Extension = NoExtension;
+/// }
+///
+/// assert_eq!(IERC20_ID, [0x36, 0x37, 0x2b, 0x07]);
+/// assert_eq!(IERC1363_ID, [0xb0, 0x20, 0x2a, 0x11]);
+/// let unknown = [0xde, 0xad, 0xbe, 0xef].into();
+/// for (id, supported) in [
+///     (IERC20_ID, true),
+///     (IERC165_ID, true),
+///     (IERC1363_ID, true),
+///     (unknown, false),
+/// ] {
+///     assert!(matches!(Erc20::<Params>::supports_interface(id), Ok(s) if s == supported));
+/// }
+/// ```
+pub const IERC20_ID: FixedBytes<4> = interface_id([
+    function_selector!("totalSupply"),
+    function_selector!("balanceOf", Address),
+    function_selector!("transfer", Address, U256),
+    function_selector!("transferFrom", Address, Address, U256),
+    function_selector!("approve", Address, U256),
+    function_selector!("allowance", Address, Address),
+]);
+
+/// The ERC-1363 interface id, `0xb0202a11`: `transferAndCall`,
+/// `transferFromAndCall` and `approveAndCall`, each with and without data.
+pub const IERC1363_ID: FixedBytes<4> = interface_id([
+    function_selector!("transferAndCall", Address, U256),
+    function_selector!("transferAndCall", Address, U256, Bytes),
+    function_selector!("transferFromAndCall", Address, Address, U256),
+    function_selector!("transferFromAndCall", Address, Address, U256, Bytes),
+    function_selector!("approveAndCall", Address, U256),
+    function_selector!("approveAndCall", Address, U256, Bytes),
+]);
+
+/// The most accounts `balancesOf` reads in one call, bounding its gas.
+pub const MAX_BALANCES_OF: usize = 500;
+
+/// The longest `tokenURI`, in bytes, bounding what setting it costs.
+pub const MAX_TOKEN_URI_LEN: usize = 256;
+
+/// The longest `name`, in bytes, that `init` or `setName` stores.
+pub const MAX_NAME_LEN: usize = 64;
+
+/// The longest `symbol`, in bytes, that `init` or `setSymbol` stores.
+pub const MAX_SYMBOL_LEN: usize = 16;
+
+/// What an ERC-3156 borrower's `onFlashLoan` returns to accept a loan,
+/// `keccak256("ERC3156FlashBorrower.onFlashLoan")`.
+pub const FLASH_LOAN_CALLBACK_SUCCESS: B256 = B256::new([
+    0x43, 0x91, 0x48, 0xf0, 0xbb, 0xc6, 0x82, 0xca, 0x07, 0x9e, 0x46, 0xd6, 0xe2, 0xc2, 0xf0, 0xc1,
+    0xe3, 0xb8, 0x20, 0xf1, 0xa2, 0x91, 0xb0, 0x69, 0xd8, 0x88, 0x2a, 0xbf, 0x8c, 0xf1, 0x8d, 0xd9,
+]);
+
+/// The highest flash loan fee, in basis points: all of the loan.
+pub const MAX_FLASH_FEE_BPS: u64 = 10_000;
+
+/// Compile-time settings of an [`Erc20`], and hooks for contracts built on it.
+///
+/// Every transfer, mint and burn runs, in order: the built-in checks (pause,
+/// blocklist, rate limit) and snapshot updates, then [`before_token_transfer`], then the
+/// balance and supply updates and the `Transfer` event, then
+/// [`after_token_transfer`]. The zero address stands for the mint or burn
+/// side. A hook's error reverts the whole operation.
+///
+/// A transfer that charges a [`transfer_fee`] debits `from` once and logs two
+/// `Transfer`s, the rest to `to` and the fee to its recipient, and
+/// [`after_token_transfer`] runs for each.
+///
+/// State the hooks need beyond the token's own lives in [`Extension`], so
+/// only the contracts that use it pay for its storage.
+///
+/// [`before_token_transfer`]: Erc20Params::before_token_transfer
+/// [`after_token_transfer`]: Erc20Params::after_token_transfer
+/// [`transfer_fee`]: Erc20Params::transfer_fee
+/// [`Extension`]: Erc20Params::Extension
+pub trait Erc20Params: Sized {
     const NAME: &'static str;
     const SYMBOL: &'static str;
     const DECIMALS: u8;
+
+    /// Storage kept alongside the token's, reachable as `token.extension`
+    /// from the hooks; [`NoExtension`] for none.
+    type Extension: StorageType;
+
+    /// Runs before any balance changes; `token` still has the old balances.
+    fn before_token_transfer(
+        _token: &mut Erc20<Self>,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+    ) -> Result<(), Erc20Error> {
+        Ok(())
+    }
+
+    /// Runs once the balances and supply are updated.
+    fn after_token_transfer(
+        _token: &mut Erc20<Self>,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+    ) -> Result<(), Erc20Error> {
+        Ok(())
+    }
+
+    /// How much of a transfer of `value` from `from` to `to` is held back as
+    /// a fee, at most `value`, and who receives it; the zero address burns
+    /// it. `to` is credited the rest. No fee by default.
+    fn transfer_fee(
+        _token: &Erc20<Self>,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+    ) -> Result<(U256, Address), Erc20Error> {
+        Ok((U256::ZERO, Address::ZERO))
+    }
+
+    /// The most `flashLoan` may mint on top of the current supply: whatever
+    /// still fits in a uint256. Tokens with a supply cap lower it to the
+    /// room left under the cap, and tokens whose every unit must be backed,
+    /// such as Weth, to zero.
+    fn max_flash_loan(token: &Erc20<Self>) -> U256 {
+        U256::MAX - token.total_supply.get()
+    }
 }
 
+/// An [`Erc20Params::Extension`] that stores nothing.
+pub type NoExtension = PhantomData<()>;
+
 sol_storage! {
     /// Erc20 implements all ERC-20 methods.
-    pub struct Erc20<T> {
+    pub struct Erc20<T: Erc20Params> {
         /// Maps users to balances
         mapping(address => uint256) balances; // becomes a StorageMap
         /// Maps users to a mapping of each spender's allowance
         mapping(address => mapping(address => uint256)) allowances;
         /// The total supply of the token
         uint256 total_supply;
+        /// Metadata set by `init`, in place of [`Erc20Params`]'s defaults
+        bytes name;
+        bytes symbol;
+        uint8 decimals;
+        /// Maps owners to their next EIP-2612 permit nonce
+        mapping(address => uint256) nonces;
+        /// Maps authorizers to the EIP-3009 nonces they have used or canceled
+        mapping(address => mapping(bytes32 => bool)) authorizations;
+        /// Gates admin methods such as `mint`. Entrypoints should also
+        /// inherit [`Ownable`] to expose its methods.
+        Ownable ownable;
+        /// Lets the owner halt transfers, mints and burns
+        Pausable pausable;
+        /// Historical balances and supply for token-weighted voting
+        Snapshots snapshots;
+        /// Accounts the owner has frozen out of transfers
+        Blocklist blocklist;
+        /// Per-transfer and per-window caps on what each account sends
+        RateLimit rate_limit;
+        /// Points wallets at a JSON blob with the token's logo and
+        /// description; set by the owner
+        bytes token_uri;
+        /// How many accounts hold a nonzero balance
+        uint256 holder_count;
+        /// The ERC-3156 flash loan fee, in basis points of the loan, and who
+        /// receives it; fees are burned while there is no recipient
+        uint256 flash_fee_bps;
+        address flash_fee_recipient;
+        /// What `T`'s hooks store, such as `VotesErc20`'s voting power
+        T::Extension extension;
         /// Used to allow [`Erc20Params`]
         PhantomData<T> phantom;
     }
@@ -31,14 +207,139 @@ sol_storage! {
 sol! {
     event Transfer(address indexed from, address indexed to, uint256 value);
     event Approval(address indexed owner, address indexed spender, uint256 value);
+    event TransferAndCall(address indexed from, address indexed to, uint256 value, bytes data);
+    event TokensRescued(address indexed token, address indexed to, uint256 amount);
+    event TokenUriUpdated(string uri);
+    event MetadataUpdated(string name, string symbol);
+    event EIP712DomainChanged();
+    event FlashFeeUpdated(uint256 fee_bps, address recipient);
+    event AuthorizationUsed(address indexed authorizer, bytes32 indexed nonce);
+    event AuthorizationCanceled(address indexed authorizer, bytes32 indexed nonce);
 
     error InsufficientBalance(address from, uint256 have, uint256 want);
     error InsufficientAllowance(address owner, address spender, uint256 have, uint256 want);
+    error InvalidReceiver(address receiver);
+    error InvalidSpender(address spender);
+    error ExpiredSignature(uint256 deadline);
+    error InvalidSigner(address signer, address owner);
+    error LengthMismatch(uint256 recipients, uint256 amounts);
+    error DuplicateRecipient(address recipient);
+    error TooManyAccounts(uint256 count, uint256 max);
+    error TokenUriTooLong(uint256 length, uint256 max);
+    error MetadataTooLong(uint256 length, uint256 max);
+    error UnsupportedFlashToken(address token);
+    error FlashLoanTooLarge(uint256 amount, uint256 max);
+    error InvalidFlashFee(uint256 fee_bps, uint256 max);
+    error CallbackFailed(address receiver);
+    error CannotRescueOwnToken(address token);
+    error RescueFailed(address token);
+    error AuthorizationNotYetValid(uint256 valid_after);
+    error AuthorizationExpired(uint256 valid_before);
+    error AuthorizationAlreadyUsed(address authorizer, bytes32 nonce);
+
+    // ERC-1363 hook invoked on the recipient by `transferAndCall` and
+    // `transferFromAndCall`, which accepts by returning its selector
+    function onTransferReceived(address operator, address from, uint256 value, bytes data)
+        returns (bytes4);
+
+    // ERC-3156 hook invoked on the borrower by `flashLoan`, which accepts by
+    // returning FLASH_LOAN_CALLBACK_SUCCESS
+    function onFlashLoan(address initiator, address token, uint256 amount, uint256 fee, bytes data)
+        returns (bytes32);
+
+    // ERC-1363 hook invoked on the spender by `approveAndCall`, which
+    // accepts by returning its selector
+    function onApprovalReceived(address owner, uint256 value, bytes data) returns (bytes4);
+
+    // EIP-2612 typed data signed by the owner
+    struct Permit {
+        address owner;
+        address spender;
+        uint256 value;
+        uint256 nonce;
+        uint256 deadline;
+    }
+
+    // EIP-3009 typed data signed by the sender
+    struct TransferWithAuthorization {
+        address from;
+        address to;
+        uint256 value;
+        uint256 validAfter;
+        uint256 validBefore;
+        bytes32 nonce;
+    }
+
+    // EIP-3009 typed data signed to void an unused authorization
+    struct CancelAuthorization {
+        address authorizer;
+        bytes32 nonce;
+    }
 }
 
 pub enum Erc20Error {
     InsufficientBalance(InsufficientBalance),
     InsufficientAllowance(InsufficientAllowance),
+    InvalidReceiver(InvalidReceiver),
+    InvalidSpender(InvalidSpender),
+    ExpiredSignature(ExpiredSignature),
+    InvalidSigner(InvalidSigner),
+    LengthMismatch(LengthMismatch),
+    DuplicateRecipient(DuplicateRecipient),
+    CallbackFailed(CallbackFailed),
+    CannotRescueOwnToken(CannotRescueOwnToken),
+    RescueFailed(RescueFailed),
+    AuthorizationNotYetValid(AuthorizationNotYetValid),
+    AuthorizationExpired(AuthorizationExpired),
+    AuthorizationAlreadyUsed(AuthorizationAlreadyUsed),
+    TooManyAccounts(TooManyAccounts),
+    TokenUriTooLong(TokenUriTooLong),
+    MetadataTooLong(MetadataTooLong),
+    UnsupportedFlashToken(UnsupportedFlashToken),
+    FlashLoanTooLarge(FlashLoanTooLarge),
+    InvalidFlashFee(InvalidFlashFee),
+    Ownable(OwnableError),
+    Pausable(PausableError),
+    Snapshots(SnapshotsError),
+    Blocklist(BlocklistError),
+    RateLimit(RateLimitError),
+    Math(MathError),
+}
+
+impl From<OwnableError> for Erc20Error {
+    fn from(err: OwnableError) -> Self {
+        Erc20Error::Ownable(err)
+    }
+}
+
+impl From<PausableError> for Erc20Error {
+    fn from(err: PausableError) -> Self {
+        Erc20Error::Pausable(err)
+    }
+}
+
+impl From<SnapshotsError> for Erc20Error {
+    fn from(err: SnapshotsError) -> Self {
+        Erc20Error::Snapshots(err)
+    }
+}
+
+impl From<BlocklistError> for Erc20Error {
+    fn from(err: BlocklistError) -> Self {
+        Erc20Error::Blocklist(err)
+    }
+}
+
+impl From<RateLimitError> for Erc20Error {
+    fn from(err: RateLimitError) -> Self {
+        Erc20Error::RateLimit(err)
+    }
+}
+
+impl From<MathError> for Erc20Error {
+    fn from(err: MathError) -> Self {
+        Erc20Error::Math(err)
+    }
 }
 
 // We will soon provide a #[derive(SolidityError)] to clean this up
@@ -47,19 +348,113 @@ impl From<Erc20Error> for Vec<u8> {
         match err {
             Erc20Error::InsufficientBalance(e) => e.encode(),
             Erc20Error::InsufficientAllowance(e) => e.encode(),
+            Erc20Error::InvalidReceiver(e) => e.encode(),
+            Erc20Error::InvalidSpender(e) => e.encode(),
+            Erc20Error::ExpiredSignature(e) => e.encode(),
+            Erc20Error::InvalidSigner(e) => e.encode(),
+            Erc20Error::LengthMismatch(e) => e.encode(),
+            Erc20Error::DuplicateRecipient(e) => e.encode(),
+            Erc20Error::CallbackFailed(e) => e.encode(),
+            Erc20Error::CannotRescueOwnToken(e) => e.encode(),
+            Erc20Error::RescueFailed(e) => e.encode(),
+            Erc20Error::AuthorizationNotYetValid(e) => e.encode(),
+            Erc20Error::AuthorizationExpired(e) => e.encode(),
+            Erc20Error::AuthorizationAlreadyUsed(e) => e.encode(),
+            Erc20Error::TooManyAccounts(e) => e.encode(),
+            Erc20Error::TokenUriTooLong(e) => e.encode(),
+            Erc20Error::MetadataTooLong(e) => e.encode(),
+            Erc20Error::UnsupportedFlashToken(e) => e.encode(),
+            Erc20Error::FlashLoanTooLarge(e) => e.encode(),
+            Erc20Error::InvalidFlashFee(e) => e.encode(),
+            Erc20Error::Ownable(e) => e.into(),
+            Erc20Error::Pausable(e) => e.into(),
+            Erc20Error::Snapshots(e) => e.into(),
+            Erc20Error::Blocklist(e) => e.into(),
+            Erc20Error::RateLimit(e) => e.into(),
+            Erc20Error::Math(e) => e.into(),
         }
     }
 }
 
+// Approvals to the zero address would be unspendable, so they are refused
+fn require_spender(spender: Address) -> Result<(), Erc20Error> {
+    if spender == Address::ZERO {
+        return Err(Erc20Error::InvalidSpender(InvalidSpender { spender }));
+    }
+    Ok(())
+}
+
 // These methods aren't exposed to other contracts
 // Note: modifying storage will become much prettier soon
 impl<T: Erc20Params> Erc20<T> {
+    // Runs before every balance change; the zero address stands for mint/burn
+    fn before_token_transfer(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<(), Erc20Error> {
+        if from != Address::ZERO && to != Address::ZERO {
+            self.blocklist.when_not_blocked(from)?;
+            self.blocklist.when_not_blocked(to)?;
+            self.rate_limit.check_limit(from, value)?;
+        }
+        self.pausable.when_not_paused()?;
+        for account in [from, to] {
+            if account == Address::ZERO {
+                self.snapshots.update_total_supply(self.total_supply.get());
+            } else {
+                self.snapshots
+                    .update_account(account, self.balances.get(account));
+            }
+        }
+        T::before_token_transfer(self, from, to, value)
+    }
+
+    // Runs after every balance change, seeing the new balances
+    fn after_token_transfer(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<(), Erc20Error> {
+        self.count_holders(from, to, value)?;
+        T::after_token_transfer(self, from, to, value)
+    }
+
+    // A nonzero move empties `from` if it leaves nothing, and gives `to` its
+    // first tokens if it now holds just `value`. A self-transfer changes neither.
+    fn count_holders(&mut self, from: Address, to: Address, value: U256) -> Result<(), Erc20Error> {
+        if value == U256::ZERO || from == to {
+            return Ok(());
+        }
+        let mut count = self.holder_count.get();
+        if from != Address::ZERO && self.balances.get(from) == U256::ZERO {
+            count = math::sub(count, U256::from(1))?;
+        }
+        if to != Address::ZERO && self.balances.get(to) == value {
+            count = math::add(count, U256::from(1))?;
+        }
+        self.holder_count.set(count);
+        Ok(())
+    }
+
+    /// Moves `value` from `from` to `to`, which can't be the zero address:
+    /// tokens sent there would be lost without lowering the supply.
     pub fn transfer_impl(
         &mut self,
         from: Address,
         to: Address,
         value: U256,
     ) -> Result<(), Erc20Error> {
+        if to == Address::ZERO {
+            return Err(Erc20Error::InvalidReceiver(InvalidReceiver {
+                receiver: to,
+            }));
+        }
+        self.before_token_transfer(from, to, value)?;
+        let (fee, fee_recipient) = T::transfer_fee(self, from, to, value)?;
+        let amount = math::sub(value, fee)?;
         let mut sender_balance = self.balances.setter(from);
         let old_sender_balance = sender_balance.get();
         if old_sender_balance < value {
@@ -71,71 +466,487 @@ impl<T: Erc20Params> Erc20<T> {
         }
         sender_balance.set(old_sender_balance - value);
         let mut to_balance = self.balances.setter(to);
-        let new_to_balance = to_balance.get() + value;
+        let new_to_balance = math::add(to_balance.get(), amount)?;
         to_balance.set(new_to_balance);
-        evm::log(Transfer { from, to, value });
+        evm::log(Transfer {
+            from,
+            to,
+            value: amount,
+        });
+        if fee == U256::ZERO {
+            return self.after_token_transfer(from, to, amount);
+        }
+        self.collect_fee(from, fee_recipient, fee)?;
+        self.after_token_transfer(from, to, amount)?;
+        // `from` counts as emptied once, by whichever part is nonzero
+        let emptied = if amount == U256::ZERO {
+            from
+        } else {
+            Address::ZERO
+        };
+        self.count_holders(emptied, fee_recipient, fee)?;
+        T::after_token_transfer(self, from, fee_recipient, fee)
+    }
+
+    // The fee part of a transfer out of `from`, whose balance already covers
+    // it: credited to `recipient`, or burned if that is the zero address.
+    // Only the transfer as a whole is checked against the blocklist and the
+    // rate limit.
+    fn collect_fee(
+        &mut self,
+        from: Address,
+        recipient: Address,
+        fee: U256,
+    ) -> Result<(), Erc20Error> {
+        if recipient == Address::ZERO {
+            let supply = self.total_supply.get();
+            self.snapshots.update_total_supply(supply);
+            self.total_supply.set(math::sub(supply, fee)?);
+        } else {
+            let mut balance = self.balances.setter(recipient);
+            let old_balance = balance.get();
+            self.snapshots.update_account(recipient, old_balance);
+            balance.set(math::add(old_balance, fee)?);
+        }
+        evm::log(Transfer {
+            from,
+            to: recipient,
+            value: fee,
+        });
         Ok(())
     }
 
-    pub fn mint(&mut self, address: Address, value: U256) {
-        let mut balance = self.balances.setter(address);
-        let new_balance = balance.get() + value;
+    pub fn mint_impl(&mut self, to: Address, value: U256) -> Result<(), Erc20Error> {
+        if to == Address::ZERO {
+            return Err(Erc20Error::InvalidReceiver(InvalidReceiver {
+                receiver: to,
+            }));
+        }
+        self.before_token_transfer(Address::ZERO, to, value)?;
+        // Balances never exceed the total supply, so checking the supply covers both
+        let new_supply = math::add(self.total_supply.get(), value)?;
+        self.total_supply.set(new_supply);
+        let mut balance = self.balances.setter(to);
+        let new_balance = math::add(balance.get(), value)?;
         balance.set(new_balance);
-        self.total_supply.set(self.total_supply.get() + value);
         evm::log(Transfer {
             from: Address::ZERO,
-            to: address,
+            to,
             value,
         });
+        self.after_token_transfer(Address::ZERO, to, value)
     }
 
-    pub fn burn(&mut self, address: Address, value: U256) -> Result<(), Erc20Error> {
-        let mut balance = self.balances.setter(address);
+    pub fn burn_impl(&mut self, from: Address, value: U256) -> Result<(), Erc20Error> {
+        self.before_token_transfer(from, Address::ZERO, value)?;
+        let mut balance = self.balances.setter(from);
         let old_balance = balance.get();
-        if old_balance < value {
-            return Err(Erc20Error::InsufficientBalance(InsufficientBalance {
-                from: address,
+        let new_balance = old_balance
+            .checked_sub(value)
+            .ok_or(Erc20Error::InsufficientBalance(InsufficientBalance {
+                from,
                 have: old_balance,
                 want: value,
-            }));
-        }
-        balance.set(old_balance - value);
-        self.total_supply.set(self.total_supply.get() - value);
+            }))?;
+        balance.set(new_balance);
+        let new_supply = math::sub(self.total_supply.get(), value)?;
+        self.total_supply.set(new_supply);
         evm::log(Transfer {
-            from: address,
+            from,
             to: Address::ZERO,
             value,
         });
+        self.after_token_transfer(from, Address::ZERO, value)
+    }
+
+    // Metadata is only read from storage once `init` has stored it
+    fn name_impl(&self) -> String {
+        if !self.ownable.is_initialized() {
+            return T::NAME.into();
+        }
+        String::from_utf8_lossy(&self.name.get_bytes()).into_owned()
+    }
+
+    fn symbol_impl(&self) -> String {
+        if !self.ownable.is_initialized() {
+            return T::SYMBOL.into();
+        }
+        String::from_utf8_lossy(&self.symbol.get_bytes()).into_owned()
+    }
+
+    fn check_metadata_len(value: &str, max: usize) -> Result<(), Erc20Error> {
+        if value.len() > max {
+            return Err(Erc20Error::MetadataTooLong(MetadataTooLong {
+                length: U256::from(value.len()),
+                max: U256::from(max),
+            }));
+        }
+        Ok(())
+    }
+
+    fn log_metadata_updated(&self) {
+        evm::log(MetadataUpdated {
+            name: self.name_impl(),
+            symbol: self.symbol_impl(),
+        });
+    }
+
+    // Display-only: balances and supply are always in base units
+    fn decimals_impl(&self) -> u8 {
+        if !self.ownable.is_initialized() {
+            return T::DECIMALS;
+        }
+        self.decimals.get().to()
+    }
+
+    // Marks `nonce` used, failing if `authorizer` already used or canceled it
+    fn use_authorization(&mut self, authorizer: Address, nonce: B256) -> Result<(), Erc20Error> {
+        let mut used = self.authorizations.setter(authorizer);
+        let mut used = used.setter(nonce);
+        if used.get() {
+            return Err(Erc20Error::AuthorizationAlreadyUsed(
+                AuthorizationAlreadyUsed {
+                    authorizer,
+                    nonce: nonce.0,
+                },
+            ));
+        }
+        used.set(true);
+        Ok(())
+    }
+
+    /// Spends `value` of `spender`'s allowance over `owner`'s tokens,
+    /// logging the allowance left. Every path that consumes an allowance
+    /// goes through here.
+    pub fn spend_allowance(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+    ) -> Result<(), Erc20Error> {
+        let mut allowance = self.allowances.setter(owner);
+        let mut allowance = allowance.setter(spender);
+        let old_allowance = allowance.get();
+        // An allowance of U256::MAX is infinite and never spent down
+        if old_allowance == U256::MAX {
+            return Ok(());
+        }
+        if old_allowance < value {
+            return Err(Erc20Error::InsufficientAllowance(InsufficientAllowance {
+                owner,
+                spender,
+                have: old_allowance,
+                want: value,
+            }));
+        }
+        allowance.set(old_allowance - value);
+        evm::log(Approval {
+            owner,
+            spender,
+            value: old_allowance - value,
+        });
+        Ok(())
+    }
+
+    // ERC-1363's receiving half: `to` accepts by returning
+    // `onTransferReceived`'s selector, so a hook that reverts or returns
+    // anything else, or an account without code, reverts the transfer too
+    fn transfer_and_call_impl(
+        &mut self,
+        operator: Address,
+        from: Address,
+        to: Address,
+        value: U256,
+        data: Vec<u8>,
+    ) -> Result<(), Erc20Error> {
+        self.transfer_impl(from, to, value)?;
+        let calldata = onTransferReceivedCall {
+            operator,
+            from,
+            value,
+            data: data.clone(),
+        }
+        .encode();
+        let accepted = RawCall::new().call(to, &calldata).is_ok_and(|output| {
+            onTransferReceivedCall::decode_returns(&output, true)
+                .is_ok_and(|r| r._0 == onTransferReceivedCall::SELECTOR)
+        });
+        if !accepted {
+            return Err(Erc20Error::CallbackFailed(CallbackFailed { receiver: to }));
+        }
+        evm::log(TransferAndCall {
+            from,
+            to,
+            value,
+            data,
+        });
+        Ok(())
+    }
+
+    fn require_flash_token(token: Address) -> Result<(), Erc20Error> {
+        if token != contract::address() {
+            return Err(Erc20Error::UnsupportedFlashToken(UnsupportedFlashToken {
+                token,
+            }));
+        }
         Ok(())
     }
+
+    // Checks the whole batch against the sender's balance before moving
+    // anything, so a batch it can't cover reverts before any transfer runs
+    fn transfer_batch_impl(
+        &mut self,
+        recipients: Vec<Address>,
+        amounts: Vec<U256>,
+        strict: bool,
+    ) -> Result<(), Erc20Error> {
+        if recipients.len() != amounts.len() {
+            return Err(Erc20Error::LengthMismatch(LengthMismatch {
+                recipients: U256::from(recipients.len()),
+                amounts: U256::from(amounts.len()),
+            }));
+        }
+        if strict {
+            let mut sorted = recipients.clone();
+            sorted.sort_unstable();
+            if let Some(pair) = sorted.windows(2).find(|pair| pair[0] == pair[1]) {
+                return Err(Erc20Error::DuplicateRecipient(DuplicateRecipient {
+                    recipient: pair[0],
+                }));
+            }
+        }
+        let from = msg::sender();
+        let total = amounts
+            .iter()
+            .try_fold(U256::ZERO, |total, &value| math::add(total, value))?;
+        let have = self.balances.get(from);
+        if have < total {
+            return Err(Erc20Error::InsufficientBalance(InsufficientBalance {
+                from,
+                have,
+                want: total,
+            }));
+        }
+        for (to, value) in recipients.into_iter().zip(amounts) {
+            self.transfer_impl(from, to, value)?;
+        }
+        Ok(())
+    }
+
+    // Signs over `name()`, the name wallets read, so a rename changes the domain
+    pub fn signing_domain(&self) -> Eip712Domain {
+        Eip712Domain {
+            name: Some(Cow::Owned(self.name_impl())),
+            version: Some(Cow::Borrowed("1")),
+            chain_id: Some(U256::from(block::chainid())),
+            verifying_contract: Some(contract::address()),
+            salt: None,
+        }
+    }
 }
 
 // These methods are external to other contracts
 // Note: modifying storage will become much prettier soon
 #[external]
 impl<T: Erc20Params> Erc20<T> {
-    pub fn name() -> Result<String, Erc20Error> {
-        Ok(T::NAME.into())
+    /// Makes the caller the owner and sets the token's metadata.
+    /// Must be called right after deployment. Only the name and symbol can
+    /// change later; `decimals` is fixed here for good.
+    pub fn init(&mut self, name: String, symbol: String, decimals: u8) -> Result<(), Erc20Error> {
+        Self::check_metadata_len(&name, MAX_NAME_LEN)?;
+        Self::check_metadata_len(&symbol, MAX_SYMBOL_LEN)?;
+        self.ownable.initialize(msg::sender())?;
+        self.name.set_bytes(name);
+        self.symbol.set_bytes(symbol);
+        self.decimals.set(U8::from(decimals));
+        Ok(())
+    }
+
+    pub fn name(&self) -> Result<String, Erc20Error> {
+        Ok(self.name_impl())
     }
 
-    pub fn symbol() -> Result<String, Erc20Error> {
-        Ok(T::SYMBOL.into())
+    pub fn symbol(&self) -> Result<String, Erc20Error> {
+        Ok(self.symbol_impl())
     }
 
-    pub fn decimals() -> Result<u8, Erc20Error> {
-        Ok(T::DECIMALS)
+    pub fn decimals(&self) -> Result<u8, Erc20Error> {
+        Ok(self.decimals_impl())
+    }
+
+    /// Renames the token, to at most `MAX_NAME_LEN` bytes. The EIP-712
+    /// domain is built from the name, so permits and authorizations signed
+    /// before the rename stop verifying. Only the owner may call this.
+    pub fn set_name(&mut self, name: String) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        Self::check_metadata_len(&name, MAX_NAME_LEN)?;
+        self.name.set_bytes(name);
+        self.log_metadata_updated();
+        evm::log(EIP712DomainChanged {});
+        Ok(())
+    }
+
+    /// Changes the symbol, to at most `MAX_SYMBOL_LEN` bytes. Only the
+    /// owner may call this.
+    pub fn set_symbol(&mut self, symbol: String) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        Self::check_metadata_len(&symbol, MAX_SYMBOL_LEN)?;
+        self.symbol.set_bytes(symbol);
+        self.log_metadata_updated();
+        Ok(())
+    }
+
+    pub fn total_supply(&self) -> Result<U256, Erc20Error> {
+        Ok(self.total_supply.get())
+    }
+
+    /// Name, symbol, decimals and total supply in a single call.
+    pub fn token_metadata(&self) -> Result<(String, String, u8, U256), Erc20Error> {
+        Ok((
+            self.name_impl(),
+            self.symbol_impl(),
+            self.decimals_impl(),
+            self.total_supply.get(),
+        ))
+    }
+
+    /// A URI of the token's metadata JSON, such as its logo and
+    /// description; empty until the owner sets one.
+    #[selector(name = "tokenURI")]
+    pub fn token_uri(&self) -> Result<String, Erc20Error> {
+        Ok(String::from_utf8_lossy(&self.token_uri.get_bytes()).into_owned())
+    }
+
+    /// Sets `tokenURI`, at most `MAX_TOKEN_URI_LEN` bytes. Only the owner
+    /// may call this.
+    #[selector(name = "setTokenURI")]
+    pub fn set_token_uri(&mut self, uri: String) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        if uri.len() > MAX_TOKEN_URI_LEN {
+            return Err(Erc20Error::TokenUriTooLong(TokenUriTooLong {
+                length: U256::from(uri.len()),
+                max: U256::from(MAX_TOKEN_URI_LEN),
+            }));
+        }
+        self.token_uri.set_bytes(&uri);
+        evm::log(TokenUriUpdated { uri });
+        Ok(())
+    }
+
+    /// ERC-165: true for the ERC-20, ERC-1363 and ERC-165 interface ids.
+    pub fn supports_interface(interface_id: FixedBytes<4>) -> Result<bool, Erc20Error> {
+        Ok([IERC20_ID, IERC1363_ID, IERC165_ID].contains(&interface_id))
+    }
+
+    /// How many accounts hold a nonzero balance.
+    pub fn holder_count(&self) -> Result<U256, Erc20Error> {
+        Ok(self.holder_count.get())
     }
 
     pub fn balance_of(&self, address: Address) -> Result<U256, Erc20Error> {
         Ok(self.balances.get(address))
     }
 
+    /// The balance of each of `accounts`, in order. At most `MAX_BALANCES_OF`.
+    pub fn balances_of(&self, accounts: Vec<Address>) -> Result<Vec<U256>, Erc20Error> {
+        if accounts.len() > MAX_BALANCES_OF {
+            return Err(Erc20Error::TooManyAccounts(TooManyAccounts {
+                count: U256::from(accounts.len()),
+                max: U256::from(MAX_BALANCES_OF),
+            }));
+        }
+        Ok(accounts
+            .into_iter()
+            .map(|account| self.balances.get(account))
+            .collect())
+    }
+
     pub fn transfer(&mut self, to: Address, value: U256) -> Result<bool, Erc20Error> {
         self.transfer_impl(msg::sender(), to, value)?;
         Ok(true)
     }
 
+    /// ERC-1363: transfers, then calls `onTransferReceived` on `to`,
+    /// reverting unless it returns that function's selector.
+    pub fn transfer_and_call(
+        &mut self,
+        to: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<bool, Erc20Error> {
+        let from = msg::sender();
+        self.transfer_and_call_impl(from, from, to, value, data.0)?;
+        Ok(true)
+    }
+
+    /// `transferAndCall` with empty data.
+    #[selector(name = "transferAndCall")]
+    pub fn transfer_and_call_without_data(
+        &mut self,
+        to: Address,
+        value: U256,
+    ) -> Result<bool, Erc20Error> {
+        self.transfer_and_call(to, value, Bytes(Vec::new()))
+    }
+
+    /// ERC-1363: `transferFrom`, then calls `onTransferReceived` on `to` with
+    /// the caller as operator, reverting unless it returns that function's
+    /// selector.
+    pub fn transfer_from_and_call(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<bool, Erc20Error> {
+        let operator = msg::sender();
+        if from != operator {
+            self.spend_allowance(from, operator, value)?;
+        }
+        self.transfer_and_call_impl(operator, from, to, value, data.0)?;
+        Ok(true)
+    }
+
+    /// `transferFromAndCall` with empty data.
+    #[selector(name = "transferFromAndCall")]
+    pub fn transfer_from_and_call_without_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<bool, Erc20Error> {
+        self.transfer_from_and_call(from, to, value, Bytes(Vec::new()))
+    }
+
+    /// Sends `amounts[i]` to `recipients[i]`. Reverts up front unless the
+    /// caller holds the sum of `amounts`, and any failing transfer reverts
+    /// the whole batch.
+    pub fn transfer_batch(
+        &mut self,
+        recipients: Vec<Address>,
+        amounts: Vec<U256>,
+    ) -> Result<bool, Erc20Error> {
+        self.transfer_batch_impl(recipients, amounts, false)?;
+        Ok(true)
+    }
+
+    /// Like `transferBatch`, but when `strict` also reverts if any recipient
+    /// appears twice.
+    #[selector(name = "transferBatch")]
+    pub fn transfer_batch_strict(
+        &mut self,
+        recipients: Vec<Address>,
+        amounts: Vec<U256>,
+        strict: bool,
+    ) -> Result<bool, Erc20Error> {
+        self.transfer_batch_impl(recipients, amounts, strict)?;
+        Ok(true)
+    }
+
+    /// Sets `spender`'s allowance over the caller's tokens. The zero address
+    /// can't be a spender; the caller itself can, harmlessly.
     pub fn approve(&mut self, spender: Address, value: U256) -> Result<bool, Erc20Error> {
+        require_spender(spender)?;
         self.allowances.setter(msg::sender()).insert(spender, value);
         evm::log(Approval {
             owner: msg::sender(),
@@ -145,24 +956,200 @@ impl<T: Erc20Params> Erc20<T> {
         Ok(true)
     }
 
+    /// ERC-3156: the most of `token` that `flashLoan` can mint, as set by
+    /// [`Erc20Params::max_flash_loan`]. Zero for any token but this one.
+    pub fn max_flash_loan(&self, token: Address) -> Result<U256, Erc20Error> {
+        match token == contract::address() {
+            true => Ok(T::max_flash_loan(self)),
+            false => Ok(U256::ZERO),
+        }
+    }
+
+    /// ERC-3156: what borrowing `amount` of `token`, which must be this
+    /// one, costs on top of the loan.
+    pub fn flash_fee(&self, token: Address, amount: U256) -> Result<U256, Erc20Error> {
+        Self::require_flash_token(token)?;
+        let fee_bps = self.flash_fee_bps.get();
+        Ok(math::mul_div(
+            amount,
+            fee_bps,
+            U256::from(MAX_FLASH_FEE_BPS),
+        )?)
+    }
+
+    pub fn flash_fee_recipient(&self) -> Result<Address, Erc20Error> {
+        Ok(self.flash_fee_recipient.get())
+    }
+
+    /// Sets the flash loan fee, at most `MAX_FLASH_FEE_BPS`, and who
+    /// receives it; fees are burned while that is the zero address. Only the
+    /// owner may call this.
+    pub fn set_flash_fee(&mut self, fee_bps: U256, recipient: Address) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        if fee_bps > U256::from(MAX_FLASH_FEE_BPS) {
+            return Err(Erc20Error::InvalidFlashFee(InvalidFlashFee {
+                fee_bps,
+                max: U256::from(MAX_FLASH_FEE_BPS),
+            }));
+        }
+        self.flash_fee_bps.set(fee_bps);
+        self.flash_fee_recipient.set(recipient);
+        evm::log(FlashFeeUpdated { fee_bps, recipient });
+        Ok(())
+    }
+
+    /// ERC-3156: mints `amount` to `receiver` and calls its `onFlashLoan`,
+    /// which must return `FLASH_LOAN_CALLBACK_SUCCESS`. `amount` plus the fee
+    /// are then taken back out of `receiver`'s allowance for this token: the
+    /// loan is burned and the fee sent to the fee recipient.
+    ///
+    /// The SDK reverts any call back into this program while it runs, so
+    /// `receiver` must approve this token before the loan, in an earlier
+    /// transaction, and can't move the borrowed tokens through it, such as to
+    /// a DEX, during `onFlashLoan`.
+    pub fn flash_loan(
+        &mut self,
+        receiver: Address,
+        token: Address,
+        amount: U256,
+        data: Bytes,
+    ) -> Result<bool, Erc20Error> {
+        let max = self.max_flash_loan(token)?;
+        if amount > max {
+            return Err(Erc20Error::FlashLoanTooLarge(FlashLoanTooLarge {
+                amount,
+                max,
+            }));
+        }
+        let fee = self.flash_fee(token, amount)?;
+        self.mint_impl(receiver, amount)?;
+
+        let calldata = onFlashLoanCall {
+            initiator: msg::sender(),
+            token,
+            amount,
+            fee,
+            data: data.0,
+        }
+        .encode();
+        let accepted = RawCall::new()
+            .call(receiver, &calldata)
+            .is_ok_and(|output| {
+                onFlashLoanCall::decode_returns(&output, true)
+                    .is_ok_and(|r| r._0 == FLASH_LOAN_CALLBACK_SUCCESS)
+            });
+        if !accepted {
+            return Err(Erc20Error::CallbackFailed(CallbackFailed { receiver }));
+        }
+
+        let repayment = math::add(amount, fee)?;
+        self.spend_allowance(receiver, contract::address(), repayment)?;
+        let recipient = self.flash_fee_recipient.get();
+        if fee == U256::ZERO || recipient == Address::ZERO {
+            self.burn_impl(receiver, repayment)?;
+        } else {
+            self.burn_impl(receiver, amount)?;
+            self.transfer_impl(receiver, recipient, fee)?;
+        }
+        Ok(true)
+    }
+
+    /// Approves, then calls ERC-1363's `onApprovalReceived` on `spender`,
+    /// reverting unless it returns that function's selector.
+    pub fn approve_and_call(
+        &mut self,
+        spender: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<bool, Erc20Error> {
+        let owner = msg::sender();
+        self.approve(spender, value)?;
+        let calldata = onApprovalReceivedCall {
+            owner,
+            value,
+            data: data.0,
+        }
+        .encode();
+        let accepted = RawCall::new().call(spender, &calldata).is_ok_and(|output| {
+            onApprovalReceivedCall::decode_returns(&output, true)
+                .is_ok_and(|r| r._0 == onApprovalReceivedCall::SELECTOR)
+        });
+        if !accepted {
+            return Err(Erc20Error::CallbackFailed(CallbackFailed {
+                receiver: spender,
+            }));
+        }
+        Ok(true)
+    }
+
+    /// `approveAndCall` with empty data.
+    #[selector(name = "approveAndCall")]
+    pub fn approve_and_call_without_data(
+        &mut self,
+        spender: Address,
+        value: U256,
+    ) -> Result<bool, Erc20Error> {
+        self.approve_and_call(spender, value, Bytes(Vec::new()))
+    }
+
+    pub fn increase_allowance(
+        &mut self,
+        spender: Address,
+        added: U256,
+    ) -> Result<bool, Erc20Error> {
+        require_spender(spender)?;
+        let owner = msg::sender();
+        let mut allowance = self.allowances.setter(owner);
+        let mut allowance = allowance.setter(spender);
+        let value = math::add(allowance.get(), added)?;
+        allowance.set(value);
+        evm::log(Approval {
+            owner,
+            spender,
+            value,
+        });
+        Ok(true)
+    }
+
+    pub fn decrease_allowance(
+        &mut self,
+        spender: Address,
+        subtracted: U256,
+    ) -> Result<bool, Erc20Error> {
+        let owner = msg::sender();
+        let mut allowance = self.allowances.setter(owner);
+        let mut allowance = allowance.setter(spender);
+        let old_allowance = allowance.get();
+        let value =
+            old_allowance
+                .checked_sub(subtracted)
+                .ok_or(Erc20Error::InsufficientAllowance(InsufficientAllowance {
+                    owner,
+                    spender,
+                    have: old_allowance,
+                    want: subtracted,
+                }))?;
+        allowance.set(value);
+        evm::log(Approval {
+            owner,
+            spender,
+            value,
+        });
+        Ok(true)
+    }
+
+    /// Moves `value` of `from`'s tokens out of the caller's allowance. When
+    /// the caller is `from` itself no allowance is needed or spent, and this
+    /// is a plain `transfer`, as in WETH9 and DAI.
     pub fn transfer_from(
         &mut self,
         from: Address,
         to: Address,
         value: U256,
     ) -> Result<bool, Erc20Error> {
-        let mut sender_allowances = self.allowances.setter(from);
-        let mut allowance = sender_allowances.setter(msg::sender());
-        let old_allowance = allowance.get();
-        if old_allowance < value {
-            return Err(Erc20Error::InsufficientAllowance(InsufficientAllowance {
-                owner: from,
-                spender: msg::sender(),
-                have: old_allowance,
-                want: value,
-            }));
+        if from != msg::sender() {
+            self.spend_allowance(from, msg::sender(), value)?;
         }
-        allowance.set(old_allowance - value);
         self.transfer_impl(from, to, value)?;
         Ok(true)
     }
@@ -170,4 +1157,296 @@ impl<T: Erc20Params> Erc20<T> {
     pub fn allowance(&self, owner: Address, spender: Address) -> Result<U256, Erc20Error> {
         Ok(self.allowances.getter(owner).get(spender))
     }
+
+    pub fn mint(&mut self, to: Address, value: U256) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        self.mint_impl(to, value)
+    }
+
+    pub fn burn(&mut self, value: U256) -> Result<(), Erc20Error> {
+        self.burn_impl(msg::sender(), value)
+    }
+
+    /// Burns `value` of `account`'s tokens out of the caller's allowance.
+    /// A failed burn reverts the allowance update along with it.
+    pub fn burn_from(&mut self, account: Address, value: U256) -> Result<(), Erc20Error> {
+        self.spend_allowance(account, msg::sender(), value)?;
+        self.burn_impl(account, value)
+    }
+
+    /// Sends `amount` of a foreign `token` stuck at this contract to `to`.
+    /// Tokens that return nothing from `transfer`, such as USDT, are supported.
+    /// This contract's own token can't be rescued, as that would take it from
+    /// whatever backs it.
+    pub fn rescue_tokens(
+        &mut self,
+        token: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        if token == contract::address() {
+            return Err(Erc20Error::CannotRescueOwnToken(CannotRescueOwnToken {
+                token,
+            }));
+        }
+        abi::safe_transfer(token, to, amount)
+            .map_err(|_| Erc20Error::RescueFailed(RescueFailed { token }))?;
+        evm::log(TokensRescued { token, to, amount });
+        Ok(())
+    }
+
+    pub fn paused(&self) -> Result<bool, Erc20Error> {
+        Ok(self.pausable.is_paused())
+    }
+
+    pub fn pause(&mut self) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        Ok(self.pausable.pause()?)
+    }
+
+    pub fn unpause(&mut self) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        Ok(self.pausable.unpause()?)
+    }
+
+    /// Blocked accounts can't transfer or receive, but keep their balances.
+    pub fn block(&mut self, account: Address) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        self.blocklist.block(account);
+        Ok(())
+    }
+
+    pub fn unblock(&mut self, account: Address) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        self.blocklist.unblock(account);
+        Ok(())
+    }
+
+    pub fn is_blocked(&self, account: Address) -> Result<bool, Erc20Error> {
+        Ok(self.blocklist.is_blocked(account))
+    }
+
+    /// The per-transfer cap, the per-window cap and the window length in
+    /// seconds. Zero caps are no caps.
+    pub fn rate_limit(&self) -> Result<(U256, U256, U256), Erc20Error> {
+        Ok(self.rate_limit.limits())
+    }
+
+    pub fn set_rate_limit(
+        &mut self,
+        max_per_tx: U256,
+        max_per_window: U256,
+        window_seconds: U256,
+    ) -> Result<(), Erc20Error> {
+        self.ownable.only_owner()?;
+        Ok(self
+            .rate_limit
+            .set_limits(max_per_tx, max_per_window, window_seconds)?)
+    }
+
+    /// What `account` has sent so far in the current window.
+    pub fn window_total(&self, account: Address) -> Result<U256, Erc20Error> {
+        Ok(self.rate_limit.window_total(account))
+    }
+
+    /// Takes a snapshot of all balances and returns its id.
+    pub fn snapshot(&mut self) -> Result<U256, Erc20Error> {
+        self.ownable.only_owner()?;
+        Ok(self.snapshots.snapshot())
+    }
+
+    pub fn balance_of_at(&self, account: Address, snapshot_id: U256) -> Result<U256, Erc20Error> {
+        let balance = self.balances.get(account);
+        Ok(self
+            .snapshots
+            .balance_of_at(account, snapshot_id, balance)?)
+    }
+
+    pub fn total_supply_at(&self, snapshot_id: U256) -> Result<U256, Erc20Error> {
+        let supply = self.total_supply.get();
+        Ok(self.snapshots.total_supply_at(snapshot_id, supply)?)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn permit(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        deadline: U256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Erc20Error> {
+        if U256::from(block::timestamp()) > deadline {
+            return Err(Erc20Error::ExpiredSignature(ExpiredSignature { deadline }));
+        }
+        let domain = self.signing_domain();
+        let mut nonce = self.nonces.setter(owner);
+        let permit = Permit {
+            owner,
+            spender,
+            value,
+            nonce: nonce.get(),
+            deadline,
+        };
+        let hash = permit.eip712_signing_hash(&domain);
+        let signer = ecrecover(hash, v, r, s).unwrap_or_default();
+        if signer.is_zero() || signer != owner {
+            return Err(Erc20Error::InvalidSigner(InvalidSigner { signer, owner }));
+        }
+        nonce.set(math::add(permit.nonce, U256::from(1))?);
+        self.allowances.setter(owner).insert(spender, value);
+        evm::log(Approval {
+            owner,
+            spender,
+            value,
+        });
+        Ok(())
+    }
+
+    /// Transfers `value` from `from` to `to` on the strength of `from`'s
+    /// EIP-3009 signature, so anyone can submit it and pay the gas. Valid only
+    /// strictly after `valid_after` and before `valid_before`, and only once
+    /// per `nonce`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_with_authorization(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Erc20Error> {
+        let now = U256::from(block::timestamp());
+        if now <= valid_after {
+            return Err(Erc20Error::AuthorizationNotYetValid(
+                AuthorizationNotYetValid { valid_after },
+            ));
+        }
+        if now >= valid_before {
+            return Err(Erc20Error::AuthorizationExpired(AuthorizationExpired {
+                valid_before,
+            }));
+        }
+        let authorization = TransferWithAuthorization {
+            from,
+            to,
+            value,
+            validAfter: valid_after,
+            validBefore: valid_before,
+            nonce: nonce.0,
+        };
+        let hash = authorization.eip712_signing_hash(&self.signing_domain());
+        let signer = ecrecover(hash, v, r, s).unwrap_or_default();
+        if signer.is_zero() || signer != from {
+            return Err(Erc20Error::InvalidSigner(InvalidSigner {
+                signer,
+                owner: from,
+            }));
+        }
+        self.use_authorization(from, nonce)?;
+        evm::log(AuthorizationUsed {
+            authorizer: from,
+            nonce: nonce.0,
+        });
+        self.transfer_impl(from, to, value)
+    }
+
+    /// Voids `authorizer`'s unused authorization with `nonce`.
+    pub fn cancel_authorization(
+        &mut self,
+        authorizer: Address,
+        nonce: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Erc20Error> {
+        let cancel = CancelAuthorization {
+            authorizer,
+            nonce: nonce.0,
+        };
+        let hash = cancel.eip712_signing_hash(&self.signing_domain());
+        let signer = ecrecover(hash, v, r, s).unwrap_or_default();
+        if signer.is_zero() || signer != authorizer {
+            return Err(Erc20Error::InvalidSigner(InvalidSigner {
+                signer,
+                owner: authorizer,
+            }));
+        }
+        self.use_authorization(authorizer, nonce)?;
+        evm::log(AuthorizationCanceled {
+            authorizer,
+            nonce: nonce.0,
+        });
+        Ok(())
+    }
+
+    /// Whether `authorizer` has used or canceled `nonce`.
+    pub fn authorization_state(
+        &self,
+        authorizer: Address,
+        nonce: B256,
+    ) -> Result<bool, Erc20Error> {
+        Ok(self.authorizations.getter(authorizer).get(nonce))
+    }
+
+    pub fn nonces(&self, owner: Address) -> Result<U256, Erc20Error> {
+        Ok(self.nonces.get(owner))
+    }
+
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> Result<B256, Erc20Error> {
+        Ok(self.signing_domain().hash_struct())
+    }
+
+    /// EIP-5267: the fields of the domain permits are signed over. `0x0f`
+    /// marks the name, version, chain id and verifying contract as used;
+    /// there is no salt and no extensions.
+    #[selector(name = "eip712Domain")]
+    #[allow(clippy::type_complexity)]
+    pub fn eip712_domain(
+        &self,
+    ) -> Result<(FixedBytes<1>, String, String, U256, Address, B256, Vec<U256>), Erc20Error> {
+        Ok((
+            FixedBytes([0x0f]),
+            self.name_impl(),
+            "1".into(),
+            U256::from(block::chainid()),
+            contract::address(),
+            B256::ZERO,
+            Vec::new(),
+        ))
+    }
+}
+
+/// Recovers the signer of `hash` via the ecrecover precompile.
+/// Returns `None` for malformed or malleable (high-s) signatures.
+fn ecrecover(hash: B256, v: u8, r: B256, s: B256) -> Option<Address> {
+    const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+    // secp256k1n / 2, as required by EIP-2
+    const HALF_ORDER: U256 = U256::from_limbs([
+        0xdfe92f46681b20a0,
+        0x5d576e7357a4501d,
+        0xffffffffffffffff,
+        0x7fffffffffffffff,
+    ]);
+    if U256::from_be_bytes(s.0) > HALF_ORDER || (v != 27 && v != 28) {
+        return None;
+    }
+    let mut input = [0u8; 128];
+    input[..32].copy_from_slice(hash.as_slice());
+    input[63] = v;
+    input[64..96].copy_from_slice(r.as_slice());
+    input[96..].copy_from_slice(s.as_slice());
+    let output = RawCall::new_static().call(ECRECOVER, &input).ok()?;
+    if output.len() != 32 {
+        return None;
+    }
+    Some(Address::from_slice(&output[12..]))
 }
@@ -0,0 +1,180 @@
+//! A wrapper token: holds an underlying Erc20 and mints its own Erc20
+//! tokens against it 1:1, as Weth does for ETH. Deposits pull the underlying
+//! through `transferFrom`, so tokens that charge a fee on transfer would
+//! leave the wrapper undercollateralized and aren't supported.
+//!
+//! Build with `--features wrapper` to make `Wrapper` the program's entrypoint
+//! in place of `Weth`.
+
+use crate::abi;
+use crate::erc20::{Erc20, Erc20Params, NoExtension};
+use crate::ownable::Ownable;
+use alloc::{string::String, vec::Vec};
+use core::borrow::{Borrow, BorrowMut};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::{sol, SolError},
+    contract, evm, msg,
+    prelude::*,
+};
+
+struct WrapperParams;
+
+/// Metadata reported until `init` sets it
+impl Erc20Params for WrapperParams {
+    const NAME: &'static str = "Wrapped Token Example";
+    const SYMBOL: &'static str = "WTKN";
+    const DECIMALS: u8 = 18;
+    type Extension = NoExtension;
+    // Wrapper tokens are only minted against deposits of the underlying
+    fn max_flash_loan(_token: &Erc20<Self>) -> U256 {
+        U256::ZERO
+    }
+}
+
+sol_storage! {
+    #[entrypoint]
+    struct Wrapper {
+        #[borrow]
+        Erc20<WrapperParams> erc20;
+        /// The wrapped token, set by `init`
+        address underlying;
+    }
+}
+
+sol! {
+    event Deposited(address indexed account, uint256 amount);
+    event Withdrawn(address indexed account, uint256 amount);
+    event Recovered(address indexed to, uint256 amount);
+
+    error UnderlyingRequired();
+    error MintDisabled();
+    error NothingToRecover(uint256 balance, uint256 total_supply);
+    error CannotRescueUnderlying(address token);
+}
+
+sol_interface! {
+    interface IErc20 {
+        function decimals() external view returns (uint8);
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+// Lets the router reach components nested inside erc20
+impl Borrow<Ownable> for Wrapper {
+    fn borrow(&self) -> &Ownable {
+        &self.erc20.ownable
+    }
+}
+
+impl BorrowMut<Ownable> for Wrapper {
+    fn borrow_mut(&mut self) -> &mut Ownable {
+        &mut self.erc20.ownable
+    }
+}
+
+impl Wrapper {
+    fn held(&self) -> Result<U256, Vec<u8>> {
+        let underlying = IErc20::new(self.underlying.get());
+        Ok(underlying.balance_of(self, contract::address())?)
+    }
+}
+
+#[external]
+#[inherit(Erc20<WrapperParams>, Ownable)]
+impl Wrapper {
+    /// Makes the caller the owner, sets the metadata and the underlying
+    /// token, and takes the decimals from the underlying's.
+    #[selector(name = "init")]
+    pub fn init_with_underlying(
+        &mut self,
+        name: String,
+        symbol: String,
+        underlying: Address,
+    ) -> Result<(), Vec<u8>> {
+        let decimals = IErc20::new(underlying).decimals(&*self)?;
+        self.erc20.init(name, symbol, decimals)?;
+        self.underlying.set(underlying);
+        Ok(())
+    }
+
+    // overrides Erc20's init, which would leave the underlying unset
+    pub fn init(&mut self, _name: String, _symbol: String, _decimals: u8) -> Result<(), Vec<u8>> {
+        Err(UnderlyingRequired {}.encode())
+    }
+
+    // overrides Erc20's mint: wrapper tokens are only minted against deposits
+    pub fn mint(&mut self, _to: Address, _value: U256) -> Result<(), Vec<u8>> {
+        Err(MintDisabled {}.encode())
+    }
+
+    // overrides Erc20's rescueTokens, which would hand the owner the deposits
+    // backing the supply; surplus underlying leaves through `recover` instead
+    pub fn rescue_tokens(
+        &mut self,
+        token: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.erc20.ownable.only_owner()?;
+        if token == self.underlying.get() {
+            return Err(CannotRescueUnderlying { token }.encode());
+        }
+        Ok(self.erc20.rescue_tokens(token, to, amount)?)
+    }
+
+    pub fn underlying(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.underlying.get())
+    }
+
+    /// Takes `amount` of the underlying from the caller, who must have
+    /// approved the wrapper for it, and mints the caller as many wrapper
+    /// tokens. The underlying arrives before anything is minted.
+    pub fn deposit(&mut self, amount: U256) -> Result<(), Vec<u8>> {
+        let account = msg::sender();
+        abi::safe_transfer_from(
+            self.underlying.get(),
+            account,
+            contract::address(),
+            amount,
+        )?;
+        self.erc20.mint_impl(account, amount)?;
+        evm::log(Deposited { account, amount });
+        Ok(())
+    }
+
+    /// Burns `amount` of the caller's wrapper tokens and sends it as much of
+    /// the underlying. The burn comes first, so a reentrant call would
+    /// already see the tokens gone.
+    pub fn withdraw(&mut self, amount: U256) -> Result<(), Vec<u8>> {
+        let account = msg::sender();
+        self.erc20.burn_impl(account, amount)?;
+        abi::safe_transfer(self.underlying.get(), account, amount)?;
+        evm::log(Withdrawn { account, amount });
+        Ok(())
+    }
+
+    /// Whether the underlying held covers every wrapper token in circulation.
+    pub fn is_fully_collateralized(&self) -> Result<bool, Vec<u8>> {
+        Ok(self.held()? >= self.erc20.total_supply.get())
+    }
+
+    /// Sends `to` the underlying held beyond what backs the supply, such as
+    /// tokens transferred in without `deposit`, returning how much. Only the
+    /// owner may call this, and only while there is a surplus.
+    pub fn recover(&mut self, to: Address) -> Result<U256, Vec<u8>> {
+        self.erc20.ownable.only_owner()?;
+        let (balance, total_supply) = (self.held()?, self.erc20.total_supply.get());
+        if balance <= total_supply {
+            return Err(NothingToRecover {
+                balance,
+                total_supply,
+            }
+            .encode());
+        }
+        let amount = balance - total_supply;
+        abi::safe_transfer(self.underlying.get(), to, amount)?;
+        evm::log(Recovered { to, amount });
+        Ok(amount)
+    }
+}
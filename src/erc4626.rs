@@ -0,0 +1,300 @@
+//! An ERC-4626 tokenized vault: holds an underlying Erc20 asset and mints
+//! shares of it, which are Erc20 tokens themselves.
+//!
+//! Conversions round in the vault's favor, down when handing out shares or
+//! assets and up when taking them. They count `10^DECIMALS_OFFSET` virtual
+//! shares and one virtual asset, so the first depositor can't inflate the
+//! share price to round later deposits down to nothing.
+//!
+//! Build with `--features vault` to make `Erc4626` the program's entrypoint
+//! in place of `Weth`.
+
+use crate::abi;
+use crate::erc20::{Erc20, Erc20Params, NoExtension};
+use crate::math;
+use crate::ownable::Ownable;
+use alloc::{string::String, vec::Vec};
+use core::borrow::{Borrow, BorrowMut};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::{sol, SolError},
+    contract, evm, msg,
+    prelude::*,
+};
+
+struct VaultParams;
+
+/// Metadata reported until `init` sets it
+impl Erc20Params for VaultParams {
+    const NAME: &'static str = "Vault Example";
+    const SYMBOL: &'static str = "VLT";
+    const DECIMALS: u8 = 18 + DECIMALS_OFFSET;
+    type Extension = NoExtension;
+    // Unbacked shares would dilute the vault's price mid-loan
+    fn max_flash_loan(_token: &Erc20<Self>) -> U256 {
+        U256::ZERO
+    }
+}
+
+/// Shares have this many more decimals than the asset
+const DECIMALS_OFFSET: u8 = 3;
+
+sol_storage! {
+    #[entrypoint]
+    struct Erc4626 {
+        #[borrow]
+        Erc20<VaultParams> erc20;
+        /// The underlying token, set by `init`
+        address asset;
+    }
+}
+
+sol! {
+    event Deposit(address indexed sender, address indexed owner, uint256 assets, uint256 shares);
+    event Withdraw(
+        address indexed sender,
+        address indexed receiver,
+        address indexed owner,
+        uint256 assets,
+        uint256 shares
+    );
+
+    error AssetRequired();
+    error MintDisabled();
+    error AssetTransferFailed(address asset);
+    error CannotRescueAsset(address asset);
+}
+
+sol_interface! {
+    interface IErc20 {
+        function decimals() external view returns (uint8);
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+// Lets the router reach components nested inside erc20
+impl Borrow<Ownable> for Erc4626 {
+    fn borrow(&self) -> &Ownable {
+        &self.erc20.ownable
+    }
+}
+
+impl BorrowMut<Ownable> for Erc4626 {
+    fn borrow_mut(&mut self) -> &mut Ownable {
+        &mut self.erc20.ownable
+    }
+}
+
+impl Erc4626 {
+    fn asset_token(&self) -> IErc20 {
+        IErc20::new(self.asset.get())
+    }
+
+    fn virtual_shares(&self) -> Result<U256, Vec<u8>> {
+        let virtual_shares = U256::from(10).pow(U256::from(DECIMALS_OFFSET));
+        Ok(math::add(self.erc20.total_supply.get(), virtual_shares)?)
+    }
+
+    fn virtual_assets(&self) -> Result<U256, Vec<u8>> {
+        Ok(math::add(self.total_assets()?, U256::from(1))?)
+    }
+
+    fn to_shares(&self, assets: U256, round_up: bool) -> Result<U256, Vec<u8>> {
+        let (supply, total) = (self.virtual_shares()?, self.virtual_assets()?);
+        Ok(match round_up {
+            true => math::mul_div_up(assets, supply, total)?,
+            false => math::mul_div(assets, supply, total)?,
+        })
+    }
+
+    fn to_assets(&self, shares: U256, round_up: bool) -> Result<U256, Vec<u8>> {
+        let (supply, total) = (self.virtual_shares()?, self.virtual_assets()?);
+        Ok(match round_up {
+            true => math::mul_div_up(shares, total, supply)?,
+            false => math::mul_div(shares, total, supply)?,
+        })
+    }
+
+    // Takes the assets before minting, so a reentrant call sees them counted
+    fn deposit_impl(
+        &mut self,
+        receiver: Address,
+        assets: U256,
+        shares: U256,
+    ) -> Result<(), Vec<u8>> {
+        let asset = self.asset.get();
+        abi::safe_transfer_from(asset, msg::sender(), contract::address(), assets)
+            .map_err(|_| AssetTransferFailed { asset }.encode())?;
+        self.erc20.mint_impl(receiver, shares)?;
+        evm::log(Deposit {
+            sender: msg::sender(),
+            owner: receiver,
+            assets,
+            shares,
+        });
+        Ok(())
+    }
+
+    // Burns the shares before sending the assets, for the same reason
+    fn withdraw_impl(
+        &mut self,
+        receiver: Address,
+        owner: Address,
+        assets: U256,
+        shares: U256,
+    ) -> Result<(), Vec<u8>> {
+        match msg::sender() == owner {
+            true => self.erc20.burn_impl(owner, shares)?,
+            false => self.erc20.burn_from(owner, shares)?,
+        }
+        let asset = self.asset.get();
+        abi::safe_transfer(asset, receiver, assets)
+            .map_err(|_| AssetTransferFailed { asset }.encode())?;
+        evm::log(Withdraw {
+            sender: msg::sender(),
+            receiver,
+            owner,
+            assets,
+            shares,
+        });
+        Ok(())
+    }
+}
+
+#[external]
+#[inherit(Erc20<VaultParams>, Ownable)]
+impl Erc4626 {
+    /// Makes the caller the owner, sets the metadata and the underlying
+    /// asset, and takes the share decimals from the asset's.
+    #[selector(name = "init")]
+    pub fn init_with_asset(
+        &mut self,
+        name: String,
+        symbol: String,
+        asset: Address,
+    ) -> Result<(), Vec<u8>> {
+        let asset_decimals = IErc20::new(asset).decimals(&*self)?;
+        self.erc20
+            .init(name, symbol, asset_decimals.saturating_add(DECIMALS_OFFSET))?;
+        self.asset.set(asset);
+        Ok(())
+    }
+
+    // overrides Erc20's init, which would leave the asset unset
+    pub fn init(&mut self, _name: String, _symbol: String, _decimals: u8) -> Result<(), Vec<u8>> {
+        Err(AssetRequired {}.encode())
+    }
+
+    // overrides Erc20's mint, as shares not backed by assets would dilute holders
+    #[selector(name = "mint")]
+    pub fn owner_mint(&mut self, _to: Address, _value: U256) -> Result<(), Vec<u8>> {
+        Err(MintDisabled {}.encode())
+    }
+
+    // overrides Erc20's rescueTokens, which would let the owner empty the
+    // vault and leave every share worthless
+    pub fn rescue_tokens(
+        &mut self,
+        token: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.erc20.ownable.only_owner()?;
+        if token == self.asset.get() {
+            return Err(CannotRescueAsset { asset: token }.encode());
+        }
+        Ok(self.erc20.rescue_tokens(token, to, amount)?)
+    }
+
+    pub fn asset(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.asset.get())
+    }
+
+    /// The vault's holdings of the asset, donations included.
+    pub fn total_assets(&self) -> Result<U256, Vec<u8>> {
+        Ok(self.asset_token().balance_of(self, contract::address())?)
+    }
+
+    pub fn convert_to_shares(&self, assets: U256) -> Result<U256, Vec<u8>> {
+        self.to_shares(assets, false)
+    }
+
+    pub fn convert_to_assets(&self, shares: U256) -> Result<U256, Vec<u8>> {
+        self.to_assets(shares, false)
+    }
+
+    pub fn max_deposit(&self, _receiver: Address) -> Result<U256, Vec<u8>> {
+        Ok(U256::MAX)
+    }
+
+    pub fn max_mint(&self, _receiver: Address) -> Result<U256, Vec<u8>> {
+        Ok(U256::MAX)
+    }
+
+    pub fn max_withdraw(&self, owner: Address) -> Result<U256, Vec<u8>> {
+        self.to_assets(self.erc20.balances.get(owner), false)
+    }
+
+    pub fn max_redeem(&self, owner: Address) -> Result<U256, Vec<u8>> {
+        Ok(self.erc20.balances.get(owner))
+    }
+
+    pub fn preview_deposit(&self, assets: U256) -> Result<U256, Vec<u8>> {
+        self.to_shares(assets, false)
+    }
+
+    pub fn preview_mint(&self, shares: U256) -> Result<U256, Vec<u8>> {
+        self.to_assets(shares, true)
+    }
+
+    pub fn preview_withdraw(&self, assets: U256) -> Result<U256, Vec<u8>> {
+        self.to_shares(assets, true)
+    }
+
+    pub fn preview_redeem(&self, shares: U256) -> Result<U256, Vec<u8>> {
+        self.to_assets(shares, false)
+    }
+
+    /// Takes `assets` from the caller and mints `receiver` the shares they
+    /// are worth, rounded down. Returns the shares.
+    pub fn deposit(&mut self, assets: U256, receiver: Address) -> Result<U256, Vec<u8>> {
+        let shares = self.to_shares(assets, false)?;
+        self.deposit_impl(receiver, assets, shares)?;
+        Ok(shares)
+    }
+
+    /// Mints `receiver` exactly `shares`, taking the assets they are worth,
+    /// rounded up, from the caller. Returns the assets.
+    pub fn mint(&mut self, shares: U256, receiver: Address) -> Result<U256, Vec<u8>> {
+        let assets = self.to_assets(shares, true)?;
+        self.deposit_impl(receiver, assets, shares)?;
+        Ok(assets)
+    }
+
+    /// Sends `receiver` exactly `assets`, burning the shares they are worth,
+    /// rounded up, from `owner`. A caller other than `owner` spends its share
+    /// allowance. Returns the shares.
+    pub fn withdraw(
+        &mut self,
+        assets: U256,
+        receiver: Address,
+        owner: Address,
+    ) -> Result<U256, Vec<u8>> {
+        let shares = self.to_shares(assets, true)?;
+        self.withdraw_impl(receiver, owner, assets, shares)?;
+        Ok(shares)
+    }
+
+    /// Burns `shares` from `owner` and sends `receiver` the assets they are
+    /// worth, rounded down. Returns the assets.
+    pub fn redeem(
+        &mut self,
+        shares: U256,
+        receiver: Address,
+        owner: Address,
+    ) -> Result<U256, Vec<u8>> {
+        let assets = self.to_assets(shares, false)?;
+        self.withdraw_impl(receiver, owner, assets, shares)?;
+        Ok(assets)
+    }
+}
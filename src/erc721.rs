@@ -0,0 +1,360 @@
+//! A minimal ERC-721 non-fungible token: the owner mints ids, holders
+//! transfer and burn them, and safe transfers only reach contracts that
+//! accept them through `onERC721Received`.
+//!
+//! Build with `--features nft` to make `Erc721` the program's entrypoint in
+//! place of `Weth`.
+
+use crate::abi::has_code;
+use crate::erc165::{interface_id, IERC165_ID};
+use crate::ownable::{Ownable, OwnableError};
+use alloc::{string::String, vec::Vec};
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, U256},
+    alloy_sol_types::{sol, SolCall, SolError},
+    call::RawCall,
+    evm, function_selector, msg,
+    prelude::*,
+};
+
+/// The ERC-721 interface id, `0x80ac58cd`.
+pub const IERC721_ID: FixedBytes<4> = interface_id([
+    function_selector!("balanceOf", Address),
+    function_selector!("ownerOf", U256),
+    function_selector!("safeTransferFrom", Address, Address, U256, Bytes),
+    function_selector!("safeTransferFrom", Address, Address, U256),
+    function_selector!("transferFrom", Address, Address, U256),
+    function_selector!("approve", Address, U256),
+    function_selector!("setApprovalForAll", Address, bool),
+    function_selector!("getApproved", U256),
+    function_selector!("isApprovedForAll", Address, Address),
+]);
+
+sol_storage! {
+    #[entrypoint]
+    struct Erc721 {
+        /// Maps token ids to their holders; zero for ids not minted or burned
+        mapping(uint256 => address) owners;
+        /// Maps holders to how many tokens they hold
+        mapping(address => uint256) balances;
+        /// Maps token ids to the one account approved to move each
+        mapping(uint256 => address) token_approvals;
+        /// Maps holders to the operators they let move all their tokens
+        mapping(address => mapping(address => bool)) operator_approvals;
+        /// Metadata set by `init`
+        bytes name;
+        bytes symbol;
+        /// Gates `mint`
+        #[borrow]
+        Ownable ownable;
+    }
+}
+
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 indexed token_id);
+    event Approval(address indexed owner, address indexed approved, uint256 indexed token_id);
+    event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+
+    error InvalidAccount(address account);
+    error NonexistentToken(uint256 token_id);
+    error TokenAlreadyMinted(uint256 token_id);
+    error IncorrectOwner(address from, uint256 token_id, address owner);
+    error NotOwnerOrApproved(address operator, uint256 token_id);
+    error InvalidReceiver(address receiver);
+
+    // ERC-721 hook invoked on contract recipients of `safeTransferFrom`,
+    // which accept by returning its selector
+    function onERC721Received(address operator, address from, uint256 token_id, bytes data)
+        returns (bytes4);
+}
+
+pub enum Erc721Error {
+    InvalidAccount(InvalidAccount),
+    NonexistentToken(NonexistentToken),
+    TokenAlreadyMinted(TokenAlreadyMinted),
+    IncorrectOwner(IncorrectOwner),
+    NotOwnerOrApproved(NotOwnerOrApproved),
+    InvalidReceiver(InvalidReceiver),
+    Ownable(OwnableError),
+}
+
+impl From<OwnableError> for Erc721Error {
+    fn from(err: OwnableError) -> Self {
+        Erc721Error::Ownable(err)
+    }
+}
+
+impl From<Erc721Error> for Vec<u8> {
+    fn from(err: Erc721Error) -> Vec<u8> {
+        match err {
+            Erc721Error::InvalidAccount(e) => e.encode(),
+            Erc721Error::NonexistentToken(e) => e.encode(),
+            Erc721Error::TokenAlreadyMinted(e) => e.encode(),
+            Erc721Error::IncorrectOwner(e) => e.encode(),
+            Erc721Error::NotOwnerOrApproved(e) => e.encode(),
+            Erc721Error::InvalidReceiver(e) => e.encode(),
+            Erc721Error::Ownable(e) => e.into(),
+        }
+    }
+}
+
+// These methods aren't exposed to other contracts
+impl Erc721 {
+    fn require_owned(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        let owner = self.owners.get(token_id);
+        if owner == Address::ZERO {
+            return Err(Erc721Error::NonexistentToken(NonexistentToken { token_id }));
+        }
+        Ok(owner)
+    }
+
+    fn is_approved_or_owner(&self, operator: Address, owner: Address, token_id: U256) -> bool {
+        operator == owner
+            || self.token_approvals.get(token_id) == operator
+            || self.operator_approvals.getter(owner).get(operator)
+    }
+
+    fn approve_impl(&mut self, owner: Address, approved: Address, token_id: U256) {
+        self.token_approvals.insert(token_id, approved);
+        evm::log(Approval {
+            owner,
+            approved,
+            token_id,
+        });
+    }
+
+    // Moves `token_id` from `from`, which must hold it, to `to`, clearing its approval
+    fn transfer_impl(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        let owner = self.require_owned(token_id)?;
+        if owner != from {
+            return Err(Erc721Error::IncorrectOwner(IncorrectOwner {
+                from,
+                token_id,
+                owner,
+            }));
+        }
+        if to == Address::ZERO {
+            return Err(Erc721Error::InvalidReceiver(InvalidReceiver {
+                receiver: to,
+            }));
+        }
+        let operator = msg::sender();
+        if !self.is_approved_or_owner(operator, owner, token_id) {
+            return Err(Erc721Error::NotOwnerOrApproved(NotOwnerOrApproved {
+                operator,
+                token_id,
+            }));
+        }
+        self.token_approvals.delete(token_id);
+        let from_balance = self.balances.get(from);
+        self.balances.insert(from, from_balance - U256::from(1));
+        let to_balance = self.balances.get(to);
+        self.balances.insert(to, to_balance + U256::from(1));
+        self.owners.insert(token_id, to);
+        evm::log(Transfer { from, to, token_id });
+        Ok(())
+    }
+
+    // Transfers, then asks a contract recipient whether it accepts the token
+    fn safe_transfer_impl(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Vec<u8>,
+    ) -> Result<(), Erc721Error> {
+        self.transfer_impl(from, to, token_id)?;
+        if !has_code(to) {
+            return Ok(());
+        }
+        let calldata = onERC721ReceivedCall {
+            operator: msg::sender(),
+            from,
+            token_id,
+            data,
+        }
+        .encode();
+        let accepted = RawCall::new().call(to, &calldata).is_ok_and(|output| {
+            onERC721ReceivedCall::decode_returns(&output, true)
+                .is_ok_and(|r| r._0 == onERC721ReceivedCall::SELECTOR)
+        });
+        if !accepted {
+            return Err(Erc721Error::InvalidReceiver(InvalidReceiver {
+                receiver: to,
+            }));
+        }
+        Ok(())
+    }
+}
+
+// These methods are external to other contracts
+#[external]
+#[inherit(Ownable)]
+impl Erc721 {
+    /// Makes the caller the owner and sets the metadata.
+    pub fn init(&mut self, name: String, symbol: String) -> Result<(), Erc721Error> {
+        self.ownable.initialize(msg::sender())?;
+        self.name.set_bytes(name);
+        self.symbol.set_bytes(symbol);
+        Ok(())
+    }
+
+    pub fn name(&self) -> Result<String, Erc721Error> {
+        Ok(String::from_utf8_lossy(&self.name.get_bytes()).into_owned())
+    }
+
+    pub fn symbol(&self) -> Result<String, Erc721Error> {
+        Ok(String::from_utf8_lossy(&self.symbol.get_bytes()).into_owned())
+    }
+
+    /// ERC-165: true for the ERC-721 and ERC-165 interface ids.
+    pub fn supports_interface(interface_id: FixedBytes<4>) -> Result<bool, Erc721Error> {
+        Ok(interface_id == IERC721_ID || interface_id == IERC165_ID)
+    }
+
+    /// How many tokens `owner` holds. The zero address holds none by
+    /// definition, so asking about it reverts.
+    pub fn balance_of(&self, owner: Address) -> Result<U256, Erc721Error> {
+        if owner == Address::ZERO {
+            return Err(Erc721Error::InvalidAccount(InvalidAccount {
+                account: owner,
+            }));
+        }
+        Ok(self.balances.get(owner))
+    }
+
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        self.require_owned(token_id)
+    }
+
+    /// Lets `approved` move `token_id` until its next transfer; the zero
+    /// address clears the approval. Only the holder or its operators may call this.
+    pub fn approve(&mut self, approved: Address, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.require_owned(token_id)?;
+        let operator = msg::sender();
+        if operator != owner && !self.operator_approvals.getter(owner).get(operator) {
+            return Err(Erc721Error::NotOwnerOrApproved(NotOwnerOrApproved {
+                operator,
+                token_id,
+            }));
+        }
+        self.approve_impl(owner, approved, token_id);
+        Ok(())
+    }
+
+    pub fn get_approved(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        self.require_owned(token_id)?;
+        Ok(self.token_approvals.get(token_id))
+    }
+
+    /// Lets `operator` move, and approve others for, all of the caller's tokens.
+    pub fn set_approval_for_all(
+        &mut self,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), Erc721Error> {
+        let owner = msg::sender();
+        self.operator_approvals
+            .setter(owner)
+            .insert(operator, approved);
+        evm::log(ApprovalForAll {
+            owner,
+            operator,
+            approved,
+        });
+        Ok(())
+    }
+
+    pub fn is_approved_for_all(
+        &self,
+        owner: Address,
+        operator: Address,
+    ) -> Result<bool, Erc721Error> {
+        Ok(self.operator_approvals.getter(owner).get(operator))
+    }
+
+    /// Moves `token_id` without checking that a contract `to` can handle it.
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        self.transfer_impl(from, to, token_id)
+    }
+
+    /// Like `transferFrom`, but reverts unless a contract `to` returns the
+    /// `onERC721Received` selector when called with `data`.
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Erc721Error> {
+        self.safe_transfer_impl(from, to, token_id, data.0)
+    }
+
+    /// `safeTransferFrom` with empty data.
+    pub fn safe_transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        self.safe_transfer_impl(from, to, token_id, Vec::new())
+    }
+
+    /// Creates `token_id` for `to`. Only the owner may call this.
+    pub fn mint(&mut self, to: Address, token_id: U256) -> Result<(), Erc721Error> {
+        self.ownable.only_owner()?;
+        if to == Address::ZERO {
+            return Err(Erc721Error::InvalidReceiver(InvalidReceiver {
+                receiver: to,
+            }));
+        }
+        if self.owners.get(token_id) != Address::ZERO {
+            return Err(Erc721Error::TokenAlreadyMinted(TokenAlreadyMinted {
+                token_id,
+            }));
+        }
+        let balance = self.balances.get(to);
+        self.balances.insert(to, balance + U256::from(1));
+        self.owners.insert(token_id, to);
+        evm::log(Transfer {
+            from: Address::ZERO,
+            to,
+            token_id,
+        });
+        Ok(())
+    }
+
+    /// Destroys `token_id`. Its holder, approved account or operators may call this.
+    pub fn burn(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.require_owned(token_id)?;
+        let operator = msg::sender();
+        if !self.is_approved_or_owner(operator, owner, token_id) {
+            return Err(Erc721Error::NotOwnerOrApproved(NotOwnerOrApproved {
+                operator,
+                token_id,
+            }));
+        }
+        self.token_approvals.delete(token_id);
+        let balance = self.balances.get(owner);
+        self.balances.insert(owner, balance - U256::from(1));
+        self.owners.delete(token_id);
+        evm::log(Transfer {
+            from: owner,
+            to: Address::ZERO,
+            token_id,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! A testnet faucet: holds an Erc20 token and hands a fixed amount of it to
+//! each caller of `claim`, at most once per cooldown per account.
+//!
+//! Build with `--features faucet` to make `Faucet` the program's entrypoint
+//! in place of `Weth`.
+
+use crate::abi;
+use crate::math;
+use crate::ownable::Ownable;
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::{sol, SolError},
+    block, contract, evm, msg,
+    prelude::*,
+};
+
+sol_storage! {
+    #[entrypoint]
+    struct Faucet {
+        /// The dispensed token, set by `init`
+        address token;
+        /// What each claim pays out
+        uint256 drip_amount;
+        /// Seconds an account must wait between claims
+        uint256 cooldown_seconds;
+        /// Maps accounts to the timestamp of their last claim
+        mapping(address => uint256) last_claim;
+        /// Gates the settings and `refill`
+        #[borrow]
+        Ownable ownable;
+    }
+}
+
+sol! {
+    event Claimed(address indexed account, uint256 amount);
+    event Refilled(address indexed from, uint256 amount);
+    event DripAmountSet(uint256 amount);
+    event CooldownSet(uint256 cooldown_seconds);
+
+    error ClaimTooSoon(address account, uint256 next_claim_at);
+    error FaucetEmpty(uint256 balance, uint256 drip_amount);
+}
+
+sol_interface! {
+    interface IErc20 {
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+#[external]
+#[inherit(Ownable)]
+impl Faucet {
+    /// Makes the caller the owner and sets what the faucet dispenses.
+    pub fn init(
+        &mut self,
+        token: Address,
+        drip_amount: U256,
+        cooldown_seconds: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.ownable.initialize(msg::sender())?;
+        self.token.set(token);
+        self.drip_amount.set(drip_amount);
+        self.cooldown_seconds.set(cooldown_seconds);
+        Ok(())
+    }
+
+    pub fn token(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.token.get())
+    }
+
+    pub fn drip_amount(&self) -> Result<U256, Vec<u8>> {
+        Ok(self.drip_amount.get())
+    }
+
+    pub fn cooldown_seconds(&self) -> Result<U256, Vec<u8>> {
+        Ok(self.cooldown_seconds.get())
+    }
+
+    /// The timestamp from which `account` may claim again; zero if it
+    /// never has.
+    pub fn next_claim_at(&self, account: Address) -> Result<U256, Vec<u8>> {
+        match self.last_claim.get(account) {
+            last if last == U256::ZERO => Ok(U256::ZERO),
+            last => Ok(math::add(last, self.cooldown_seconds.get())?),
+        }
+    }
+
+    /// Sends the caller `dripAmount` tokens. Reverts during the caller's
+    /// cooldown and while the faucet holds less than that.
+    pub fn claim(&mut self) -> Result<U256, Vec<u8>> {
+        let account = msg::sender();
+        let now = U256::from(block::timestamp());
+        let next_claim_at = self.next_claim_at(account)?;
+        if now < next_claim_at {
+            return Err(ClaimTooSoon {
+                account,
+                next_claim_at,
+            }
+            .encode());
+        }
+        let (token, drip_amount) = (self.token.get(), self.drip_amount.get());
+        let balance = IErc20::new(token).balance_of(&*self, contract::address())?;
+        if balance < drip_amount {
+            return Err(FaucetEmpty {
+                balance,
+                drip_amount,
+            }
+            .encode());
+        }
+        // Recorded first, so a reentrant claim is too soon
+        self.last_claim.insert(account, now);
+        abi::safe_transfer(token, account, drip_amount)?;
+        evm::log(Claimed {
+            account,
+            amount: drip_amount,
+        });
+        Ok(drip_amount)
+    }
+
+    /// Only the owner may call this.
+    pub fn set_drip_amount(&mut self, amount: U256) -> Result<(), Vec<u8>> {
+        self.ownable.only_owner()?;
+        self.drip_amount.set(amount);
+        evm::log(DripAmountSet { amount });
+        Ok(())
+    }
+
+    /// Only the owner may call this.
+    pub fn set_cooldown(&mut self, cooldown_seconds: U256) -> Result<(), Vec<u8>> {
+        self.ownable.only_owner()?;
+        self.cooldown_seconds.set(cooldown_seconds);
+        evm::log(CooldownSet { cooldown_seconds });
+        Ok(())
+    }
+
+    /// Pulls `amount` tokens from the caller, who must have approved the
+    /// faucet for them. Only the owner may call this; anyone else can
+    /// simply transfer tokens to the faucet.
+    pub fn refill(&mut self, amount: U256) -> Result<(), Vec<u8>> {
+        self.ownable.only_owner()?;
+        let from = msg::sender();
+        abi::safe_transfer_from(self.token.get(), from, contract::address(), amount)?;
+        evm::log(Refilled { from, amount });
+        Ok(())
+    }
+}
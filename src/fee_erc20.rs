@@ -0,0 +1,117 @@
+//! An Erc20 that charges a fee, in basis points, on every transfer and
+//! sends it to a fee recipient, burning it while there is none. Both are set
+//! by the owner. The fee is split off inside the transfer itself, so every
+//! method that moves tokens pays it, `transferBatch`, `transferAndCall` and
+//! `transferWithAuthorization` included: `from` is debited `value` once, `to`
+//! receives the rest and the recipient the fee, each with its own `Transfer`.
+//! Transfers to the fee recipient, mints and burns pay none.
+//!
+//! Build with `--features fee` to make `FeeErc20` the program's entrypoint
+//! in place of `Weth`.
+
+use crate::erc20::{Erc20, Erc20Error, Erc20Params};
+use crate::math;
+use crate::ownable::Ownable;
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::{sol, SolError},
+    evm,
+    prelude::*,
+};
+
+struct FeeParams;
+
+/// Metadata reported until `init` sets it
+impl Erc20Params for FeeParams {
+    const NAME: &'static str = "Fee Token Example";
+    const SYMBOL: &'static str = "FEE";
+    const DECIMALS: u8 = 18;
+    type Extension = TransferFee;
+
+    // `value * fee_bps / 10000` rounded down, split off the transfer itself
+    fn transfer_fee(
+        token: &Erc20<Self>,
+        _from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<(U256, Address), Erc20Error> {
+        let recipient = token.extension.fee_recipient.get();
+        if to == recipient {
+            return Ok((U256::ZERO, recipient));
+        }
+        let fee_bps = token.extension.fee_bps.get();
+        let fee = math::mul_div(value, fee_bps, U256::from(MAX_BPS))?;
+        Ok((fee, recipient))
+    }
+}
+
+const MAX_BPS: u64 = 10_000;
+
+sol_storage! {
+    #[entrypoint]
+    struct FeeErc20 {
+        #[borrow]
+        Erc20<FeeParams> erc20;
+    }
+
+    /// Kept in the Erc20 so its transfer hook can see it
+    struct TransferFee {
+        /// The fee in basis points of each transferred value, at most 10000
+        uint256 fee_bps;
+        /// Receives the fees; they are burned while this is the zero address
+        address fee_recipient;
+    }
+}
+
+sol! {
+    event FeeUpdated(uint256 fee_bps);
+    event FeeRecipientUpdated(address indexed fee_recipient);
+
+    error InvalidFee(uint256 fee_bps);
+}
+
+// Lets the router reach components nested inside erc20
+impl Borrow<Ownable> for FeeErc20 {
+    fn borrow(&self) -> &Ownable {
+        &self.erc20.ownable
+    }
+}
+
+impl BorrowMut<Ownable> for FeeErc20 {
+    fn borrow_mut(&mut self) -> &mut Ownable {
+        &mut self.erc20.ownable
+    }
+}
+
+#[external]
+#[inherit(Erc20<FeeParams>, Ownable)]
+impl FeeErc20 {
+    pub fn fee_bps(&self) -> Result<U256, Vec<u8>> {
+        Ok(self.erc20.extension.fee_bps.get())
+    }
+
+    pub fn fee_recipient(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.erc20.extension.fee_recipient.get())
+    }
+
+    pub fn set_fee_bps(&mut self, fee_bps: U256) -> Result<(), Vec<u8>> {
+        self.erc20.ownable.only_owner()?;
+        if fee_bps > U256::from(MAX_BPS) {
+            return Err(InvalidFee { fee_bps }.encode());
+        }
+        self.erc20.extension.fee_bps.set(fee_bps);
+        evm::log(FeeUpdated { fee_bps });
+        Ok(())
+    }
+
+    /// Sends future fees to `fee_recipient`, or burns them if it is the
+    /// zero address.
+    pub fn set_fee_recipient(&mut self, fee_recipient: Address) -> Result<(), Vec<u8>> {
+        self.erc20.ownable.only_owner()?;
+        self.erc20.extension.fee_recipient.set(fee_recipient);
+        evm::log(FeeRecipientUpdated { fee_recipient });
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+//! The helper contract that WETH's `sumWithHelper` cross-calls.
+//! WETH calls `sum(uint256[])`, i.e. selector `0x0194db8e` (see
+//! [`crate::abi::selector`]), and expects the tag `"sum"` alongside the total.
+//! Both reject more than [`MAX_SUM_VALUES`] values.
+//!
+//! Build with `--features helper` to make `Helper` the program's entrypoint
+//! in place of `Weth`.
+
+use crate::math;
+use alloc::{string::String, vec::Vec};
+use stylus_sdk::{
+    alloy_primitives::U256,
+    alloy_sol_types::{sol, SolError},
+    prelude::*,
+};
+
+/// The most values `sum` and `sumWithHelper` add up in one call, bounding
+/// their decoding and gas costs.
+pub const MAX_SUM_VALUES: usize = 1024;
+
+sol_storage! {
+    #[cfg_attr(feature = "helper", entrypoint)]
+    pub struct Helper {}
+}
+
+sol! {
+    error SumOverflow();
+    error InputTooLarge(uint256 length, uint256 max);
+}
+
+/// Reverts with `InputTooLarge` for more than [`MAX_SUM_VALUES`] values.
+pub fn check_sum_input(values: &[U256]) -> Result<(), Vec<u8>> {
+    if values.len() > MAX_SUM_VALUES {
+        return Err(InputTooLarge {
+            length: U256::from(values.len()),
+            max: U256::from(MAX_SUM_VALUES),
+        }
+        .encode());
+    }
+    Ok(())
+}
+
+#[external]
+impl Helper {
+    // sums at most MAX_SUM_VALUES numbers, tagging an empty input with "empty" instead of "sum"
+    pub fn sum(values: Vec<U256>) -> Result<(String, U256), Vec<u8>> {
+        check_sum_input(&values)?;
+        if values.is_empty() {
+            return Ok(("empty".into(), U256::ZERO));
+        }
+        let sum = values
+            .iter()
+            .try_fold(U256::ZERO, |acc, value| math::add(acc, *value))
+            .map_err(|_| SumOverflow {}.encode())?;
+        Ok(("sum".into(), sum))
+    }
+}
@@ -0,0 +1,87 @@
+//! An Erc20 whose transfer hooks log the balances they see, so tests can
+//! check when the hooks run relative to the balance updates.
+//!
+//! Build with `--features hooks` to make `HookedErc20` the program's
+//! entrypoint in place of `Weth`.
+
+use crate::erc20::{Erc20, Erc20Error, Erc20Params, NoExtension};
+use crate::ownable::Ownable;
+use core::borrow::{Borrow, BorrowMut};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    evm,
+    prelude::*,
+};
+
+struct HookedParams;
+
+sol! {
+    event BeforeTokenTransfer(address from, address to, uint256 value, uint256 from_balance, uint256 to_balance);
+    event AfterTokenTransfer(address from, address to, uint256 value, uint256 from_balance, uint256 to_balance);
+}
+
+/// Metadata reported until `init` sets it
+impl Erc20Params for HookedParams {
+    const NAME: &'static str = "Hooked Token Example";
+    const SYMBOL: &'static str = "HOOK";
+    const DECIMALS: u8 = 18;
+    type Extension = NoExtension;
+
+    fn before_token_transfer(
+        token: &mut Erc20<Self>,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<(), Erc20Error> {
+        evm::log(BeforeTokenTransfer {
+            from,
+            to,
+            value,
+            from_balance: token.balances.get(from),
+            to_balance: token.balances.get(to),
+        });
+        Ok(())
+    }
+
+    fn after_token_transfer(
+        token: &mut Erc20<Self>,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<(), Erc20Error> {
+        evm::log(AfterTokenTransfer {
+            from,
+            to,
+            value,
+            from_balance: token.balances.get(from),
+            to_balance: token.balances.get(to),
+        });
+        Ok(())
+    }
+}
+
+sol_storage! {
+    #[entrypoint]
+    struct HookedErc20 {
+        #[borrow]
+        Erc20<HookedParams> erc20;
+    }
+}
+
+// Lets the router reach components nested inside erc20
+impl Borrow<Ownable> for HookedErc20 {
+    fn borrow(&self) -> &Ownable {
+        &self.erc20.ownable
+    }
+}
+
+impl BorrowMut<Ownable> for HookedErc20 {
+    fn borrow_mut(&mut self) -> &mut Ownable {
+        &mut self.erc20.ownable
+    }
+}
+
+#[external]
+#[inherit(Erc20<HookedParams>, Ownable)]
+impl HookedErc20 {}
@@ -2,70 +2,106 @@
 #![cfg_attr(not(feature = "export-abi"), no_main)]
 extern crate alloc;
 
-mod erc20;
-use crate::erc20::{Erc20, Erc20Params};
-use alloc::{string::String, vec::Vec};
-use stylus_sdk::{alloy_primitives::U256, call, msg, prelude::*};
+pub mod abi;
+#[cfg(feature = "client")]
+pub mod bindings;
+pub mod blocklist;
+#[cfg(feature = "capped")]
+mod capped_erc20;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod erc165;
+pub mod erc20;
+#[cfg(feature = "wrapper")]
+mod erc20_wrapper;
+#[cfg(feature = "vault")]
+mod erc4626;
+#[cfg(feature = "nft")]
+mod erc721;
+#[cfg(feature = "faucet")]
+mod faucet;
+#[cfg(feature = "fee")]
+mod fee_erc20;
+pub mod helper;
+#[cfg(feature = "hooks")]
+mod hooked_erc20;
+pub mod math;
+pub mod ownable;
+pub mod pausable;
+pub mod rate_limit;
+pub mod reentrancy_guard;
+#[cfg(feature = "client")]
+pub mod signer;
+pub mod snapshots;
+#[cfg(feature = "client")]
+pub mod transport;
+#[cfg(feature = "vesting")]
+mod vesting;
+pub mod votes;
+#[cfg(feature = "votes")]
+mod votes_erc20;
+// Weth is the program unless another contract's feature selects a different entrypoint
+#[cfg(not(any(
+    feature = "helper",
+    feature = "capped",
+    feature = "fee",
+    feature = "vault",
+    feature = "hooks",
+    feature = "nft",
+    feature = "votes",
+    feature = "vesting",
+    feature = "faucet",
+    feature = "wrapper"
+)))]
+mod weth;
 
 #[cfg(target_arch = "wasm32")]
 #[global_allocator]
 static ALLOC: mini_alloc::MiniAlloc = mini_alloc::MiniAlloc::INIT;
 
-struct WethParams;
+#[cfg(all(
+    feature = "export-abi",
+    not(any(
+        feature = "helper",
+        feature = "capped",
+        feature = "fee",
+        feature = "vault",
+        feature = "hooks",
+        feature = "nft",
+        feature = "votes",
+        feature = "vesting",
+        feature = "faucet",
+        feature = "wrapper"
+    ))
+))]
+pub use crate::weth::main;
 
-/// Immutable definitions
-impl Erc20Params for WethParams {
-    const NAME: &'static str = "Wrapped Ether Example";
-    const SYMBOL: &'static str = "WETH";
-    const DECIMALS: u8 = 18;
-}
+#[cfg(all(feature = "export-abi", feature = "helper"))]
+pub use crate::helper::main;
 
-// The contract
-sol_storage! {
-    #[entrypoint] // Makes Weth the entrypoint
-    struct Weth {
-        #[borrow] // Allows erc20 to access Weth's storage and make calls
-        Erc20<WethParams> erc20;
-    }
-}
+#[cfg(all(feature = "export-abi", feature = "capped"))]
+pub use crate::capped_erc20::main;
 
-// Another contract we'd like to call
-sol_interface! {
-    interface IMath {
-        function sum(uint256[] values) pure returns (string, uint256);
-    }
-}
+#[cfg(all(feature = "export-abi", feature = "fee"))]
+pub use crate::fee_erc20::main;
 
-#[external]
-#[inherit(Erc20<WethParams>)]
-impl Weth {
-    #[payable]
-    pub fn deposit(&mut self) -> Result<(), Vec<u8>> {
-        self.erc20.mint(msg::sender(), msg::value());
-        Ok(())
-    }
+#[cfg(all(feature = "export-abi", feature = "vault"))]
+pub use crate::erc4626::main;
 
-    pub fn withdraw(&mut self, amount: U256) -> Result<(), Vec<u8>> {
-        self.erc20.burn(msg::sender(), amount)?;
+#[cfg(all(feature = "export-abi", feature = "hooks"))]
+pub use crate::hooked_erc20::main;
 
-        // send the user their funds
-        call::transfer_eth(msg::sender(), amount)
-    }
+#[cfg(all(feature = "export-abi", feature = "nft"))]
+pub use crate::erc721::main;
 
-    // sums numbers
-    pub fn sum(values: Vec<U256>) -> Result<(String, U256), Vec<u8>> {
-        Ok(("sum".into(), values.iter().sum()))
-    }
+#[cfg(all(feature = "export-abi", feature = "votes"))]
+pub use crate::votes_erc20::main;
 
-    // // ...
-    // pub fn dec(&mut self) -> Result<u8, Erc20Error> {
-    //     self.erc20.decimals()
-    // }    
+#[cfg(all(feature = "export-abi", feature = "vesting"))]
+pub use crate::vesting::main;
 
-    // calls the sum() method from the interface
-    pub fn sum_with_helper(&self, helper: IMath, values: Vec<U256>) -> Result<U256, Vec<u8>> {
-        let (text, sum) = helper.sum(self, values)?;
-        assert_eq!(&text, "sum");
-        Ok(sum)
-    }
-}
+#[cfg(all(feature = "export-abi", feature = "faucet"))]
+pub use crate::faucet::main;
+
+#[cfg(all(feature = "export-abi", feature = "wrapper"))]
+pub use crate::erc20_wrapper::main;
@@ -0,0 +1,58 @@
+//! Checked arithmetic on token amounts. Each operation reverts with a named
+//! error instead of panicking or wrapping around.
+
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::{U256, U512},
+    alloy_sol_types::{sol, SolError},
+};
+
+sol! {
+    error Overflow();
+    error Underflow();
+    error DivisionByZero();
+}
+
+pub enum MathError {
+    Overflow(Overflow),
+    Underflow(Underflow),
+    DivisionByZero(DivisionByZero),
+}
+
+impl From<MathError> for Vec<u8> {
+    fn from(err: MathError) -> Vec<u8> {
+        match err {
+            MathError::Overflow(e) => e.encode(),
+            MathError::Underflow(e) => e.encode(),
+            MathError::DivisionByZero(e) => e.encode(),
+        }
+    }
+}
+
+pub fn add(a: U256, b: U256) -> Result<U256, MathError> {
+    a.checked_add(b).ok_or(MathError::Overflow(Overflow {}))
+}
+
+pub fn sub(a: U256, b: U256) -> Result<U256, MathError> {
+    a.checked_sub(b).ok_or(MathError::Underflow(Underflow {}))
+}
+
+/// `a * b / denominator` rounded down. The product is taken at 512 bits, so
+/// only a quotient that doesn't fit in 256 bits overflows.
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256, MathError> {
+    if denominator == U256::ZERO {
+        return Err(MathError::DivisionByZero(DivisionByZero {}));
+    }
+    let product: U512 = a.widening_mul(b);
+    let quotient = product / U512::from(denominator);
+    U256::checked_from_limbs_slice(quotient.as_limbs()).ok_or(MathError::Overflow(Overflow {}))
+}
+
+/// `a * b / denominator` rounded up, for amounts that must never be undercounted.
+pub fn mul_div_up(a: U256, b: U256, denominator: U256) -> Result<U256, MathError> {
+    let quotient = mul_div(a, b, denominator)?;
+    if a.mul_mod(b, denominator) == U256::ZERO {
+        return Ok(quotient);
+    }
+    add(quotient, U256::from(1))
+}
@@ -1,7 +1,7 @@
 // #![cfg_attr(not(feature = "export-abi"), no_main, no_std)]
 // extern crate alloc;
 
-use crate::erc20::{Erc20, Erc20Params};
+use crate::erc20::{Erc20, Erc20Params, NoExtension};
 use alloc::{string::String, vec::Vec};
 use stylus_sdk::{alloy_primitives::U256, call, msg, prelude::*};
 
@@ -16,6 +16,7 @@ impl Erc20Params for WethParams {
     const NAME: &'static str = "Wrapped Ether Example";
     const SYMBOL: &'static str = "WETH";
     const DECIMALS: u8 = 18;
+    type Extension = NoExtension;
 }
 
 // The contract
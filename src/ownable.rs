@@ -0,0 +1,104 @@
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::Address,
+    alloy_sol_types::{sol, SolError},
+    evm, msg,
+    prelude::*,
+};
+
+sol_storage! {
+    /// Ownable restricts admin methods to a single owner account.
+    pub struct Ownable {
+        /// The current owner, or zero once renounced
+        address owner;
+        /// Set once the initial owner has been assigned
+        bool initialized;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    error UnauthorizedAccount(address account);
+    error InvalidOwner(address owner);
+    error AlreadyInitialized();
+}
+
+pub enum OwnableError {
+    UnauthorizedAccount(UnauthorizedAccount),
+    InvalidOwner(InvalidOwner),
+    AlreadyInitialized(AlreadyInitialized),
+}
+
+impl From<OwnableError> for Vec<u8> {
+    fn from(err: OwnableError) -> Vec<u8> {
+        match err {
+            OwnableError::UnauthorizedAccount(e) => e.encode(),
+            OwnableError::InvalidOwner(e) => e.encode(),
+            OwnableError::AlreadyInitialized(e) => e.encode(),
+        }
+    }
+}
+
+// These methods aren't exposed to other contracts
+impl Ownable {
+    /// Stylus programs have no constructor, so the deployer calls this once
+    /// (through the parent contract's `init`) to become the initial owner.
+    pub fn initialize(&mut self, owner: Address) -> Result<(), OwnableError> {
+        if self.initialized.get() {
+            return Err(OwnableError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.transfer_ownership_impl(owner);
+        Ok(())
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized.get()
+    }
+
+    pub fn only_owner(&self) -> Result<(), OwnableError> {
+        let account = msg::sender();
+        if account != self.owner.get() {
+            return Err(OwnableError::UnauthorizedAccount(UnauthorizedAccount {
+                account,
+            }));
+        }
+        Ok(())
+    }
+
+    fn transfer_ownership_impl(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        evm::log(OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+    }
+}
+
+// These methods are external to other contracts
+#[external]
+impl Ownable {
+    pub fn owner(&self) -> Result<Address, OwnableError> {
+        Ok(self.owner.get())
+    }
+
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), OwnableError> {
+        self.only_owner()?;
+        if new_owner == Address::ZERO {
+            return Err(OwnableError::InvalidOwner(InvalidOwner {
+                owner: new_owner,
+            }));
+        }
+        self.transfer_ownership_impl(new_owner);
+        Ok(())
+    }
+
+    pub fn renounce_ownership(&mut self) -> Result<(), OwnableError> {
+        self.only_owner()?;
+        self.transfer_ownership_impl(Address::ZERO);
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_sol_types::{sol, SolError},
+    evm, msg,
+    prelude::*,
+};
+
+sol_storage! {
+    /// Pausable is an emergency stop for state-changing token methods.
+    pub struct Pausable {
+        bool paused;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Paused(address account);
+    event Unpaused(address account);
+
+    error EnforcedPause();
+    error ExpectedPause();
+}
+
+pub enum PausableError {
+    EnforcedPause(EnforcedPause),
+    ExpectedPause(ExpectedPause),
+}
+
+impl From<PausableError> for Vec<u8> {
+    fn from(err: PausableError) -> Vec<u8> {
+        match err {
+            PausableError::EnforcedPause(e) => e.encode(),
+            PausableError::ExpectedPause(e) => e.encode(),
+        }
+    }
+}
+
+// Access control is left to the parent contract, which knows who may pause
+impl Pausable {
+    pub fn is_paused(&self) -> bool {
+        self.paused.get()
+    }
+
+    pub fn when_not_paused(&self) -> Result<(), PausableError> {
+        if self.paused.get() {
+            return Err(PausableError::EnforcedPause(EnforcedPause {}));
+        }
+        Ok(())
+    }
+
+    pub fn pause(&mut self) -> Result<(), PausableError> {
+        self.when_not_paused()?;
+        self.paused.set(true);
+        evm::log(Paused {
+            account: msg::sender(),
+        });
+        Ok(())
+    }
+
+    pub fn unpause(&mut self) -> Result<(), PausableError> {
+        if !self.paused.get() {
+            return Err(PausableError::ExpectedPause(ExpectedPause {}));
+        }
+        self.paused.set(false);
+        evm::log(Unpaused {
+            account: msg::sender(),
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::{sol, SolError},
+    block, evm,
+    prelude::*,
+};
+
+sol_storage! {
+    /// RateLimit caps how much an account may send per transfer and per time
+    /// window. Windows are fixed `window_seconds` slices of block time, so an
+    /// account's allowance resets whenever a new one starts. A zero cap is no cap.
+    pub struct RateLimit {
+        uint256 max_per_tx;
+        uint256 max_per_window;
+        uint256 window_seconds;
+        /// Maps accounts to what they sent in each window, by window index
+        mapping(address => mapping(uint256 => uint256)) window_totals;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event RateLimitSet(uint256 max_per_tx, uint256 max_per_window, uint256 window_seconds);
+
+    error ExceededTxLimit(uint256 value, uint256 max_per_tx);
+    error ExceededWindowLimit(address account, uint256 window_total, uint256 max_per_window);
+    error InvalidWindow();
+}
+
+pub enum RateLimitError {
+    ExceededTxLimit(ExceededTxLimit),
+    ExceededWindowLimit(ExceededWindowLimit),
+    InvalidWindow(InvalidWindow),
+}
+
+impl From<RateLimitError> for Vec<u8> {
+    fn from(err: RateLimitError) -> Vec<u8> {
+        match err {
+            RateLimitError::ExceededTxLimit(e) => e.encode(),
+            RateLimitError::ExceededWindowLimit(e) => e.encode(),
+            RateLimitError::InvalidWindow(e) => e.encode(),
+        }
+    }
+}
+
+// Access control is left to the parent contract, which knows who may set limits
+impl RateLimit {
+    /// The caps and window length, in that order.
+    pub fn limits(&self) -> (U256, U256, U256) {
+        (
+            self.max_per_tx.get(),
+            self.max_per_window.get(),
+            self.window_seconds.get(),
+        )
+    }
+
+    pub fn set_limits(
+        &mut self,
+        max_per_tx: U256,
+        max_per_window: U256,
+        window_seconds: U256,
+    ) -> Result<(), RateLimitError> {
+        if max_per_window != U256::ZERO && window_seconds == U256::ZERO {
+            return Err(RateLimitError::InvalidWindow(InvalidWindow {}));
+        }
+        self.max_per_tx.set(max_per_tx);
+        self.max_per_window.set(max_per_window);
+        self.window_seconds.set(window_seconds);
+        evm::log(RateLimitSet {
+            max_per_tx,
+            max_per_window,
+            window_seconds,
+        });
+        Ok(())
+    }
+
+    /// What `account` has sent in the current window.
+    pub fn window_total(&self, account: Address) -> U256 {
+        match self.window_index() {
+            Some(index) => self.window_totals.getter(account).get(index),
+            None => U256::ZERO,
+        }
+    }
+
+    /// Counts `value` against `from`'s limits, reverting if it exceeds either.
+    pub fn check_limit(&mut self, from: Address, value: U256) -> Result<(), RateLimitError> {
+        let max_per_tx = self.max_per_tx.get();
+        if max_per_tx != U256::ZERO && value > max_per_tx {
+            return Err(RateLimitError::ExceededTxLimit(ExceededTxLimit {
+                value,
+                max_per_tx,
+            }));
+        }
+        let max_per_window = self.max_per_window.get();
+        let Some(index) = self.window_index().filter(|_| max_per_window != U256::ZERO) else {
+            return Ok(());
+        };
+        let mut totals = self.window_totals.setter(from);
+        let mut total = totals.setter(index);
+        let window_total = total.get().saturating_add(value);
+        if window_total > max_per_window {
+            return Err(RateLimitError::ExceededWindowLimit(ExceededWindowLimit {
+                account: from,
+                window_total,
+                max_per_window,
+            }));
+        }
+        total.set(window_total);
+        Ok(())
+    }
+
+    // None while no window is set
+    fn window_index(&self) -> Option<U256> {
+        let window_seconds = self.window_seconds.get();
+        (window_seconds != U256::ZERO).then(|| U256::from(block::timestamp()) / window_seconds)
+    }
+}
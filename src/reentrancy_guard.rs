@@ -0,0 +1,50 @@
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_sol_types::{sol, SolError},
+    prelude::*,
+};
+
+sol_storage! {
+    /// ReentrancyGuard rejects calls that re-enter a guarded method while it
+    /// is still running, e.g. from the receiver of an ETH transfer.
+    ///
+    /// This build never reaches it: without the SDK's `reentrant` feature,
+    /// every entrypoint reverts a reentrant call, with no revert data, before
+    /// any method runs. The guard is only for a future `reentrant` build.
+    pub struct ReentrancyGuard {
+        bool locked;
+    }
+}
+
+sol! {
+    error ReentrantCall();
+}
+
+pub enum ReentrancyGuardError {
+    ReentrantCall(ReentrantCall),
+}
+
+impl From<ReentrancyGuardError> for Vec<u8> {
+    fn from(err: ReentrancyGuardError) -> Vec<u8> {
+        match err {
+            ReentrancyGuardError::ReentrantCall(e) => e.encode(),
+        }
+    }
+}
+
+impl ReentrancyGuard {
+    /// Enters the guarded section, reverting if it is already entered.
+    /// Pair every successful call with `exit`; a revert rolls the lock back
+    /// along with the rest of the call's storage writes.
+    pub fn non_reentrant(&mut self) -> Result<(), ReentrancyGuardError> {
+        if self.locked.get() {
+            return Err(ReentrancyGuardError::ReentrantCall(ReentrantCall {}));
+        }
+        self.locked.set(true);
+        Ok(())
+    }
+
+    pub fn exit(&mut self) {
+        self.locked.set(false);
+    }
+}
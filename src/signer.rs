@@ -0,0 +1,219 @@
+//! The signers a [`Client`](crate::client::Client) can send transactions with.
+//!
+//! A private key file, an encrypted keystore or a BIP-39 mnemonic all load
+//! into a [`LocalWallet`]. Keys held in AWS KMS need the `kms` feature.
+
+use async_trait::async_trait;
+use ethers::{
+    signers::{coins_bip39::English, LocalWallet, MnemonicBuilder, Signer, WalletError},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::Eip712},
+        Address, PathOrString, Signature,
+    },
+};
+use std::fmt;
+
+#[cfg(feature = "kms")]
+use ethers::signers::{AwsSigner, AwsSignerError};
+
+/// Any of the supported signers, so the client type doesn't depend on which
+/// one is in use.
+#[derive(Debug)]
+pub enum ClientSigner {
+    Local(LocalWallet),
+    #[cfg(feature = "kms")]
+    Kms(AwsSigner),
+}
+
+impl From<LocalWallet> for ClientSigner {
+    fn from(wallet: LocalWallet) -> Self {
+        Self::Local(wallet)
+    }
+}
+
+#[cfg(feature = "kms")]
+impl From<AwsSigner> for ClientSigner {
+    fn from(signer: AwsSigner) -> Self {
+        Self::Kms(signer)
+    }
+}
+
+#[derive(Debug)]
+pub enum SignerError {
+    Local(WalletError),
+    MnemonicRead(std::io::Error),
+    /// The mnemonic doesn't have one of the word counts BIP-39 allows
+    InvalidMnemonic {
+        words: usize,
+    },
+    // Boxed, as it is many times the size of the other errors
+    #[cfg(feature = "kms")]
+    Kms(Box<AwsSignerError>),
+}
+
+impl SignerError {
+    #[cfg(feature = "kms")]
+    fn kms(err: AwsSignerError) -> Self {
+        Self::Kms(Box::new(err))
+    }
+}
+
+impl fmt::Display for SignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local(err) => write!(f, "wallet error: {err}"),
+            Self::MnemonicRead(err) => write!(f, "could not read mnemonic file: {err}"),
+            Self::InvalidMnemonic { words } => write!(
+                f,
+                "invalid mnemonic: {words} words, expected 12, 15, 18, 21 or 24"
+            ),
+            #[cfg(feature = "kms")]
+            Self::Kms(err) => write!(f, "aws kms error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SignerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Local(err) => Some(err),
+            Self::MnemonicRead(err) => Some(err),
+            Self::InvalidMnemonic { .. } => None,
+            #[cfg(feature = "kms")]
+            Self::Kms(err) => Some(err),
+        }
+    }
+}
+
+#[async_trait]
+impl Signer for ClientSigner {
+    type Error = SignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, SignerError> {
+        match self {
+            Self::Local(wallet) => wallet
+                .sign_message(message)
+                .await
+                .map_err(SignerError::Local),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => signer.sign_message(message).await.map_err(SignerError::kms),
+        }
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, SignerError> {
+        match self {
+            Self::Local(wallet) => wallet
+                .sign_transaction(tx)
+                .await
+                .map_err(SignerError::Local),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => signer.sign_transaction(tx).await.map_err(SignerError::kms),
+        }
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, SignerError> {
+        match self {
+            Self::Local(wallet) => wallet
+                .sign_typed_data(payload)
+                .await
+                .map_err(SignerError::Local),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => signer
+                .sign_typed_data(payload)
+                .await
+                .map_err(SignerError::kms),
+        }
+    }
+
+    fn address(&self) -> Address {
+        match self {
+            Self::Local(wallet) => wallet.address(),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => signer.address(),
+        }
+    }
+
+    fn chain_id(&self) -> u64 {
+        match self {
+            Self::Local(wallet) => wallet.chain_id(),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => signer.chain_id(),
+        }
+    }
+
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        match self {
+            Self::Local(wallet) => Self::Local(wallet.with_chain_id(chain_id)),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => Self::Kms(signer.with_chain_id(chain_id)),
+        }
+    }
+}
+
+/// Decrypts a JSON keystore file, as written by geth or `cast wallet import`.
+///
+/// ```
+/// use ethers::signers::Signer;
+/// use ethers::types::Address;
+/// use stylus_hello_world::signer::read_keystore;
+///
+/// let wallet = read_keystore("tests/fixtures/keystore.json", "stylus").unwrap();
+/// let expected: Address = "0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E".parse().unwrap();
+/// assert_eq!(wallet.address(), expected);
+///
+/// assert!(read_keystore("tests/fixtures/keystore.json", "wrong").is_err());
+/// ```
+pub fn read_keystore(path: &str, password: &str) -> Result<LocalWallet, SignerError> {
+    LocalWallet::decrypt_keystore(path, password).map_err(SignerError::Local)
+}
+
+/// Derives the wallet at `m/44'/60'/0'/0/{index}`, the path MetaMask and
+/// most other wallets use, from the BIP-39 mnemonic in the file at `path`.
+/// The words may be split across lines and padded with any whitespace.
+///
+/// ```
+/// use ethers::signers::Signer;
+/// use ethers::types::Address;
+/// use stylus_hello_world::signer::read_mnemonic;
+///
+/// // The well-known development mnemonic, "test test ... test junk"
+/// let wallet = read_mnemonic("tests/fixtures/mnemonic.txt", 0).unwrap();
+/// let expected: Address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".parse().unwrap();
+/// assert_eq!(wallet.address(), expected);
+///
+/// let second = read_mnemonic("tests/fixtures/mnemonic.txt", 1).unwrap();
+/// let expected: Address = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".parse().unwrap();
+/// assert_eq!(second.address(), expected);
+///
+/// // A keystore is no mnemonic
+/// assert!(read_mnemonic("tests/fixtures/keystore.json", 0).is_err());
+/// ```
+pub fn read_mnemonic(path: &str, index: u32) -> Result<LocalWallet, SignerError> {
+    let contents = std::fs::read_to_string(path).map_err(SignerError::MnemonicRead)?;
+    let words: Vec<&str> = contents.split_whitespace().collect();
+    if ![12, 15, 18, 21, 24].contains(&words.len()) {
+        return Err(SignerError::InvalidMnemonic { words: words.len() });
+    }
+    MnemonicBuilder::<English>::default()
+        .phrase(PathOrString::String(words.join(" ")))
+        .index(index)
+        .and_then(|builder| builder.build())
+        .map_err(SignerError::Local)
+}
+
+/// Connects to the AWS KMS key `key_id`, in the region and with the
+/// credentials the environment configures, e.g. `AWS_REGION`.
+#[cfg(feature = "kms")]
+pub async fn kms_signer(key_id: &str) -> Result<AwsSigner, SignerError> {
+    let kms = rusoto_kms::KmsClient::new(rusoto_core::Region::default());
+    // connect sets the actual chain id
+    AwsSigner::new(kms, key_id, 1)
+        .await
+        .map_err(SignerError::kms)
+}
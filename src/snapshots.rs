@@ -0,0 +1,123 @@
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::{sol, SolError},
+    evm,
+    prelude::*,
+    storage::StorageVec,
+};
+
+sol_storage! {
+    /// The value an account (or the total supply) held when a snapshot was taken.
+    pub struct Checkpoint {
+        uint256 id;
+        uint256 value;
+    }
+
+    /// Snapshots records historical balances and total supply for
+    /// token-weighted voting. Values are captured lazily: the first write to
+    /// an account after `snapshot()` stores its old balance under the current
+    /// snapshot id, so idle accounts cost nothing.
+    pub struct Snapshots {
+        /// The id of the most recent snapshot, zero before the first one
+        uint256 current_id;
+        Checkpoint[] total_supply_checkpoints;
+        mapping(address => Checkpoint[]) account_checkpoints;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Snapshot(uint256 id);
+
+    error InvalidSnapshotId(uint256 id);
+}
+
+pub enum SnapshotsError {
+    InvalidSnapshotId(InvalidSnapshotId),
+}
+
+impl From<SnapshotsError> for Vec<u8> {
+    fn from(err: SnapshotsError) -> Vec<u8> {
+        match err {
+            SnapshotsError::InvalidSnapshotId(e) => e.encode(),
+        }
+    }
+}
+
+// Access control is left to the parent contract, which knows who may snapshot
+impl Snapshots {
+    pub fn snapshot(&mut self) -> U256 {
+        let id = self.current_id.get() + U256::from(1);
+        self.current_id.set(id);
+        evm::log(Snapshot { id });
+        id
+    }
+
+    /// Must be called before `account`'s balance changes from `balance`.
+    pub fn update_account(&mut self, account: Address, balance: U256) {
+        let id = self.current_id.get();
+        capture(&mut self.account_checkpoints.setter(account), id, balance);
+    }
+
+    /// Must be called before the total supply changes from `supply`.
+    pub fn update_total_supply(&mut self, supply: U256) {
+        let id = self.current_id.get();
+        capture(&mut self.total_supply_checkpoints, id, supply);
+    }
+
+    pub fn balance_of_at(
+        &self,
+        account: Address,
+        id: U256,
+        balance: U256,
+    ) -> Result<U256, SnapshotsError> {
+        self.validate(id)?;
+        Ok(value_at(&self.account_checkpoints.getter(account), id).unwrap_or(balance))
+    }
+
+    pub fn total_supply_at(&self, id: U256, supply: U256) -> Result<U256, SnapshotsError> {
+        self.validate(id)?;
+        Ok(value_at(&self.total_supply_checkpoints, id).unwrap_or(supply))
+    }
+
+    fn validate(&self, id: U256) -> Result<(), SnapshotsError> {
+        if id == U256::ZERO || id > self.current_id.get() {
+            return Err(SnapshotsError::InvalidSnapshotId(InvalidSnapshotId { id }));
+        }
+        Ok(())
+    }
+}
+
+// Stores `value` under snapshot `id` unless that snapshot was already captured
+fn capture(checkpoints: &mut StorageVec<Checkpoint>, id: U256, value: U256) {
+    if id == U256::ZERO {
+        return;
+    }
+    let last_id = match checkpoints.len() {
+        0 => U256::ZERO,
+        len => checkpoints.getter(len - 1).unwrap().id.get(),
+    };
+    if last_id < id {
+        let mut checkpoint = checkpoints.grow();
+        checkpoint.id.set(id);
+        checkpoint.value.set(value);
+    }
+}
+
+// Binary-searches for the first checkpoint taken at or after snapshot `id`,
+// which holds the value as of `id`. `None` means nothing changed since.
+fn value_at(checkpoints: &StorageVec<Checkpoint>, id: U256) -> Option<U256> {
+    let (mut low, mut high) = (0, checkpoints.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if checkpoints.getter(mid).unwrap().id.get() < id {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    checkpoints
+        .getter(low)
+        .map(|checkpoint| checkpoint.value.get())
+}
@@ -0,0 +1,134 @@
+//! JSON-RPC transports picked by the scheme of the endpoint url, so clients
+//! can stream subscriptions over WebSocket or IPC as well as poll over HTTP.
+
+use crate::client::ClientError;
+use async_trait::async_trait;
+use ethers::providers::{Http, Ipc, JsonRpcClient, Provider, ProviderError, PubsubClient, Ws};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
+use std::path::PathBuf;
+use url::Url;
+
+/// Where a node listens, as given by an rpc url.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    Http(Url),
+    Ws(Url),
+    /// The node's IPC socket
+    Ipc(PathBuf),
+}
+
+impl Endpoint {
+    /// Reads `http(s)://` and `ws(s)://` urls, and `file://` urls or bare
+    /// paths to an IPC socket.
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use stylus_hello_world::client::ClientError;
+    /// use stylus_hello_world::transport::Endpoint;
+    ///
+    /// let http = Endpoint::parse("https://stylus-testnet.arbitrum.io/rpc").unwrap();
+    /// assert!(matches!(http, Endpoint::Http(url) if url.host_str() == Some("stylus-testnet.arbitrum.io")));
+    /// assert!(matches!(Endpoint::parse("ws://localhost:8548"), Ok(Endpoint::Ws(_))));
+    /// assert!(matches!(Endpoint::parse("wss://localhost:8548"), Ok(Endpoint::Ws(_))));
+    ///
+    /// let socket = PathBuf::from("/tmp/nitro.ipc");
+    /// assert_eq!(Endpoint::parse("file:///tmp/nitro.ipc").unwrap(), Endpoint::Ipc(socket.clone()));
+    /// assert_eq!(Endpoint::parse("/tmp/nitro.ipc").unwrap(), Endpoint::Ipc(socket));
+    ///
+    /// assert!(matches!(Endpoint::parse("ftp://localhost"), Err(ClientError::InvalidRpcUrl(_))));
+    /// assert!(matches!(Endpoint::parse("http://"), Err(ClientError::InvalidRpcUrl(_))));
+    /// ```
+    pub fn parse(rpc_url: &str) -> Result<Self, ClientError> {
+        let invalid = || ClientError::InvalidRpcUrl(rpc_url.to_string());
+        let Some((scheme, rest)) = rpc_url.split_once("://") else {
+            return Ok(Self::Ipc(rpc_url.into()));
+        };
+        match scheme {
+            "http" | "https" => Url::parse(rpc_url).map(Self::Http).map_err(|_| invalid()),
+            "ws" | "wss" => Url::parse(rpc_url).map(Self::Ws).map_err(|_| invalid()),
+            "file" if !rest.is_empty() => Ok(Self::Ipc(rest.into())),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Any of the transports an [`Endpoint`] can select.
+#[derive(Clone, Debug)]
+pub enum Transport {
+    Http(Http),
+    Ws(Ws),
+    Ipc(Ipc),
+}
+
+impl Transport {
+    /// Opens a connection to `endpoint`. Only WebSocket and IPC connect up
+    /// front, HTTP sends nothing until the first request.
+    pub async fn connect(endpoint: &Endpoint) -> Result<Self, ClientError> {
+        Ok(match endpoint {
+            Endpoint::Http(url) => Self::Http(Http::new(url.clone())),
+            Endpoint::Ws(url) => Self::Ws(
+                Ws::connect(url.as_str())
+                    .await
+                    .map_err(|err| ClientError::Rpc(err.into()))?,
+            ),
+            Endpoint::Ipc(path) => Self::Ipc(
+                Ipc::connect(path)
+                    .await
+                    .map_err(|err| ClientError::Rpc(err.into()))?,
+            ),
+        })
+    }
+
+    /// Whether the node can push notifications, which HTTP can't.
+    pub fn supports_subscriptions(&self) -> bool {
+        !matches!(self, Self::Http(_))
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for Transport {
+    type Error = ProviderError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, ProviderError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        match self {
+            Self::Http(http) => http.request(method, params).await.map_err(Into::into),
+            Self::Ws(ws) => ws.request(method, params).await.map_err(Into::into),
+            Self::Ipc(ipc) => ipc.request(method, params).await.map_err(Into::into),
+        }
+    }
+}
+
+impl PubsubClient for Transport {
+    // Ws and Ipc share their stream type
+    type NotificationStream = <Ws as PubsubClient>::NotificationStream;
+
+    fn subscribe<T: Into<ethers::types::U256>>(
+        &self,
+        id: T,
+    ) -> Result<Self::NotificationStream, ProviderError> {
+        match self {
+            Self::Http(_) => Err(ProviderError::UnsupportedRPC),
+            Self::Ws(ws) => ws.subscribe(id).map_err(Into::into),
+            Self::Ipc(ipc) => ipc.subscribe(id).map_err(Into::into),
+        }
+    }
+
+    fn unsubscribe<T: Into<ethers::types::U256>>(&self, id: T) -> Result<(), ProviderError> {
+        match self {
+            Self::Http(_) => Err(ProviderError::UnsupportedRPC),
+            Self::Ws(ws) => ws.unsubscribe(id).map_err(Into::into),
+            Self::Ipc(ipc) => ipc.unsubscribe(id).map_err(Into::into),
+        }
+    }
+}
+
+/// A provider over the transport `rpc_url`'s scheme selects, see [`Endpoint::parse`].
+pub async fn connect(rpc_url: &str) -> Result<Provider<Transport>, ClientError> {
+    let endpoint = Endpoint::parse(rpc_url)?;
+    Ok(Provider::new(Transport::connect(&endpoint).await?))
+}
@@ -0,0 +1,170 @@
+//! A vesting wallet: holds an Erc20 token for one beneficiary and lets it
+//! out linearly from `start` to `start + duration`. Nothing vests before
+//! the cliff; at the cliff everything vested since `start` becomes
+//! releasable at once. A zero duration makes it a plain timelock.
+//!
+//! Whatever the contract holds is vested on the one schedule, however late
+//! it was sent. Anyone may call `release`, which only pays the beneficiary.
+//!
+//! Build with `--features vesting` to make `Vesting` the program's
+//! entrypoint in place of `Weth`.
+
+use crate::abi;
+use crate::math;
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256, U64},
+    alloy_sol_types::{sol, SolError},
+    block, contract, evm,
+    prelude::*,
+};
+
+sol_storage! {
+    #[entrypoint]
+    struct Vesting {
+        /// The vested token and who receives it, set by `init`
+        address token;
+        address beneficiary;
+        /// The schedule, in seconds since the epoch and seconds
+        uint64 start;
+        uint64 cliff;
+        uint64 duration;
+        /// How much has been paid to the beneficiary so far
+        uint256 released;
+    }
+}
+
+sol! {
+    event Released(address indexed token, address indexed beneficiary, uint256 amount);
+
+    error AlreadyInitialized();
+    error InvalidToken(address token);
+    error InvalidBeneficiary(address beneficiary);
+    error InvalidSchedule(uint64 start, uint64 cliff, uint64 duration);
+    error NothingToRelease();
+}
+
+sol_interface! {
+    interface IErc20 {
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+impl Vesting {
+    // The schedule's end, which `init` checked fits in a u64
+    fn end(&self) -> u64 {
+        self.start.get().to::<u64>() + self.duration.get().to::<u64>()
+    }
+
+    // Everything the schedule covers: what's still held plus what's been paid
+    fn total_allocation(&self) -> Result<U256, Vec<u8>> {
+        let held = IErc20::new(self.token.get()).balance_of(self, contract::address())?;
+        Ok(math::add(held, self.released.get())?)
+    }
+
+    fn vested_at(&self, total: U256, timestamp: u64) -> Result<U256, Vec<u8>> {
+        let start = self.start.get().to::<u64>();
+        if timestamp < start + self.cliff.get().to::<u64>() {
+            return Ok(U256::ZERO);
+        }
+        if timestamp >= self.end() {
+            return Ok(total);
+        }
+        let elapsed = U256::from(timestamp - start);
+        Ok(math::mul_div(
+            total,
+            elapsed,
+            U256::from(self.duration.get()),
+        )?)
+    }
+}
+
+#[external]
+impl Vesting {
+    /// Vests this contract's holdings of `token` for `beneficiary` from
+    /// `start`, over `duration` seconds, with nothing releasable for the
+    /// first `cliff` of them. Can only be called once.
+    pub fn init(
+        &mut self,
+        token: Address,
+        beneficiary: Address,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+    ) -> Result<(), Vec<u8>> {
+        if self.token.get() != Address::ZERO {
+            return Err(AlreadyInitialized {}.encode());
+        }
+        if !abi::has_code(token) {
+            return Err(InvalidToken { token }.encode());
+        }
+        if beneficiary == Address::ZERO {
+            return Err(InvalidBeneficiary { beneficiary }.encode());
+        }
+        // The cliff must fall within the schedule, and the schedule end within a u64
+        if cliff > duration || start.checked_add(duration).is_none() {
+            return Err(InvalidSchedule {
+                start,
+                cliff,
+                duration,
+            }
+            .encode());
+        }
+        self.token.set(token);
+        self.beneficiary.set(beneficiary);
+        self.start.set(U64::from(start));
+        self.cliff.set(U64::from(cliff));
+        self.duration.set(U64::from(duration));
+        Ok(())
+    }
+
+    pub fn token(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.token.get())
+    }
+
+    pub fn beneficiary(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.beneficiary.get())
+    }
+
+    /// Start, cliff and duration, in that order.
+    pub fn schedule(&self) -> Result<(u64, u64, u64), Vec<u8>> {
+        Ok((
+            self.start.get().to(),
+            self.cliff.get().to(),
+            self.duration.get().to(),
+        ))
+    }
+
+    pub fn released(&self) -> Result<U256, Vec<u8>> {
+        Ok(self.released.get())
+    }
+
+    /// How much of the allocation has vested by `timestamp`, released or not.
+    pub fn vested_amount(&self, timestamp: u64) -> Result<U256, Vec<u8>> {
+        self.vested_at(self.total_allocation()?, timestamp)
+    }
+
+    /// What `release` would pay the beneficiary now.
+    pub fn releasable(&self) -> Result<U256, Vec<u8>> {
+        let vested = self.vested_amount(block::timestamp())?;
+        Ok(vested.saturating_sub(self.released.get()))
+    }
+
+    /// Pays the beneficiary everything vested but not yet released.
+    pub fn release(&mut self) -> Result<U256, Vec<u8>> {
+        let amount = self.releasable()?;
+        if amount == U256::ZERO {
+            return Err(NothingToRelease {}.encode());
+        }
+        // Counted as released first, so a reentrant call finds nothing left
+        self.released.set(math::add(self.released.get(), amount)?);
+        let (token, beneficiary) = (self.token.get(), self.beneficiary.get());
+        abi::safe_transfer(token, beneficiary, amount)?;
+        evm::log(Released {
+            token,
+            beneficiary,
+            amount,
+        });
+        Ok(amount)
+    }
+}
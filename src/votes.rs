@@ -0,0 +1,155 @@
+use crate::math::{self, MathError};
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256, U64},
+    alloy_sol_types::{sol, SolError},
+    block, evm,
+    prelude::*,
+    storage::StorageVec,
+};
+
+sol_storage! {
+    /// A delegate's voting power from `block` on.
+    pub struct VotesCheckpoint {
+        uint64 block;
+        uint256 votes;
+    }
+
+    /// Votes tracks delegated voting power for token governance. Holders
+    /// delegate their whole balance, to themselves or anyone else, and each
+    /// delegate's power is checkpointed by block so it can be looked up
+    /// historically. Undelegated balances count for no one.
+    ///
+    /// Blocks are those of `block::number()`, which on Arbitrum is an
+    /// estimate of the L1 block number, as `block.number` is in Solidity.
+    pub struct Votes {
+        mapping(address => address) delegates;
+        mapping(address => VotesCheckpoint[]) checkpoints;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event DelegateChanged(address indexed delegator, address indexed from_delegate, address indexed to_delegate);
+    event DelegateVotesChanged(address indexed delegate, uint256 previous_votes, uint256 new_votes);
+
+    error FutureLookup(uint256 block_number, uint256 current_block);
+}
+
+pub enum VotesError {
+    FutureLookup(FutureLookup),
+}
+
+impl From<VotesError> for Vec<u8> {
+    fn from(err: VotesError) -> Vec<u8> {
+        match err {
+            VotesError::FutureLookup(e) => e.encode(),
+        }
+    }
+}
+
+// Access control and balances are left to the parent token
+impl Votes {
+    pub fn delegates(&self, account: Address) -> Address {
+        self.delegates.get(account)
+    }
+
+    /// Makes `delegatee` the delegate of `delegator`, moving the power of
+    /// its `balance` over from the previous delegate.
+    pub fn delegate(
+        &mut self,
+        delegator: Address,
+        delegatee: Address,
+        balance: U256,
+    ) -> Result<(), MathError> {
+        let from_delegate = self.delegates.get(delegator);
+        self.delegates.insert(delegator, delegatee);
+        evm::log(DelegateChanged {
+            delegator,
+            from_delegate,
+            to_delegate: delegatee,
+        });
+        self.move_voting_power(from_delegate, delegatee, balance)
+    }
+
+    /// Must be called when `amount` of balance moves between holders with
+    /// delegates `from` and `to`, zero for none.
+    pub fn move_voting_power(
+        &mut self,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), MathError> {
+        if from == to || amount == U256::ZERO {
+            return Ok(());
+        }
+        if from != Address::ZERO {
+            let votes = math::sub(self.get_votes(from), amount)?;
+            self.write_checkpoint(from, votes);
+        }
+        if to != Address::ZERO {
+            let votes = math::add(self.get_votes(to), amount)?;
+            self.write_checkpoint(to, votes);
+        }
+        Ok(())
+    }
+
+    pub fn get_votes(&self, account: Address) -> U256 {
+        let checkpoints = self.checkpoints.getter(account);
+        match checkpoints.len() {
+            0 => U256::ZERO,
+            len => checkpoints.getter(len - 1).unwrap().votes.get(),
+        }
+    }
+
+    /// `account`'s voting power at the end of `block_number`, which must
+    /// already be over.
+    pub fn get_past_votes(&self, account: Address, block_number: U256) -> Result<U256, VotesError> {
+        let current_block = U256::from(block::number());
+        if block_number >= current_block {
+            return Err(VotesError::FutureLookup(FutureLookup {
+                block_number,
+                current_block,
+            }));
+        }
+        Ok(votes_at(&self.checkpoints.getter(account), block_number))
+    }
+
+    // Several changes within one block share its checkpoint
+    fn write_checkpoint(&mut self, delegate: Address, new_votes: U256) {
+        let previous_votes = self.get_votes(delegate);
+        let now = U64::from(block::number());
+        let mut checkpoints = self.checkpoints.setter(delegate);
+        let len = checkpoints.len();
+        if len > 0 && checkpoints.getter(len - 1).unwrap().block.get() == now {
+            checkpoints.setter(len - 1).unwrap().votes.set(new_votes);
+        } else {
+            let mut checkpoint = checkpoints.grow();
+            checkpoint.block.set(now);
+            checkpoint.votes.set(new_votes);
+        }
+        evm::log(DelegateVotesChanged {
+            delegate,
+            previous_votes,
+            new_votes,
+        });
+    }
+}
+
+// Binary-searches for the last checkpoint at or before `block_number`. Zero
+// if the first checkpoint came later.
+fn votes_at(checkpoints: &StorageVec<VotesCheckpoint>, block_number: U256) -> U256 {
+    let (mut low, mut high) = (0, checkpoints.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if U256::from(checkpoints.getter(mid).unwrap().block.get()) > block_number {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    match low {
+        0 => U256::ZERO,
+        n => checkpoints.getter(n - 1).unwrap().votes.get(),
+    }
+}
@@ -0,0 +1,92 @@
+//! An Erc20 with delegated, checkpointed voting power for on-chain
+//! governance. Its transfer hook moves voting power along with the balances
+//! of delegating holders; the `Votes` storage is its Erc20 extension, so no
+//! other token carries it.
+//!
+//! Build with `--features votes` to make `VotesErc20` the program's
+//! entrypoint in place of `Weth`.
+
+use crate::erc20::{Erc20, Erc20Error, Erc20Params};
+use crate::ownable::Ownable;
+use crate::votes::Votes;
+use alloc::vec::Vec;
+use core::borrow::{Borrow, BorrowMut};
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block, msg,
+    prelude::*,
+};
+
+struct VotesParams;
+
+/// Metadata reported until `init` sets it
+impl Erc20Params for VotesParams {
+    const NAME: &'static str = "Votes Token Example";
+    const SYMBOL: &'static str = "VOTE";
+    const DECIMALS: u8 = 18;
+    type Extension = Votes;
+
+    fn after_token_transfer(
+        token: &mut Erc20<Self>,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<(), Erc20Error> {
+        let votes = &mut token.extension;
+        let (from, to) = (votes.delegates(from), votes.delegates(to));
+        Ok(votes.move_voting_power(from, to, value)?)
+    }
+}
+
+sol_storage! {
+    #[entrypoint]
+    struct VotesErc20 {
+        #[borrow]
+        Erc20<VotesParams> erc20;
+    }
+}
+
+// Lets the router reach components nested inside erc20
+impl Borrow<Ownable> for VotesErc20 {
+    fn borrow(&self) -> &Ownable {
+        &self.erc20.ownable
+    }
+}
+
+impl BorrowMut<Ownable> for VotesErc20 {
+    fn borrow_mut(&mut self) -> &mut Ownable {
+        &mut self.erc20.ownable
+    }
+}
+
+#[external]
+#[inherit(Erc20<VotesParams>, Ownable)]
+impl VotesErc20 {
+    /// ERC-6372: the block number checkpoints are taken at.
+    pub fn clock(&self) -> Result<u64, Vec<u8>> {
+        Ok(block::number())
+    }
+
+    /// Moves all of the caller's voting power, now and as its balance
+    /// changes, to `delegatee`. The zero address withdraws it.
+    pub fn delegate(&mut self, delegatee: Address) -> Result<(), Vec<u8>> {
+        let delegator = msg::sender();
+        let balance = self.erc20.balances.get(delegator);
+        let votes = &mut self.erc20.extension;
+        Ok(votes.delegate(delegator, delegatee, balance)?)
+    }
+
+    pub fn delegates(&self, account: Address) -> Result<Address, Vec<u8>> {
+        Ok(self.erc20.extension.delegates(account))
+    }
+
+    pub fn get_votes(&self, account: Address) -> Result<U256, Vec<u8>> {
+        Ok(self.erc20.extension.get_votes(account))
+    }
+
+    /// `account`'s voting power at the end of `block_number`, a block before
+    /// the current `clock()`.
+    pub fn get_past_votes(&self, account: Address, block_number: U256) -> Result<U256, Vec<u8>> {
+        Ok(self.erc20.extension.get_past_votes(account, block_number)?)
+    }
+}
@@ -0,0 +1,240 @@
+use crate::abi::encode_call;
+use crate::erc165::interface_id;
+use crate::erc20::{Erc20, Erc20Params, NoExtension};
+use crate::helper::{check_sum_input, Helper};
+use crate::ownable::Ownable;
+use crate::reentrancy_guard::ReentrancyGuard;
+use alloc::{string::String, vec, vec::Vec};
+use core::borrow::{Borrow, BorrowMut};
+use stylus_sdk::{
+    abi::Router,
+    alloy_primitives::{Address, FixedBytes, U256},
+    alloy_sol_types::{sol, sol_data, SolError, SolType},
+    call::{self, RawCall},
+    contract, evm, function_selector, msg,
+    prelude::*,
+    storage::{StorageType, TopLevelStorage},
+    ArbResult,
+};
+
+/// The id of Weth's own methods, `0x69c5a27c`: deposit(), withdraw(uint256),
+/// depositTo(address) and withdrawTo(address,uint256).
+const IWETH_ID: FixedBytes<4> = interface_id([
+    function_selector!("deposit"),
+    function_selector!("withdraw", U256),
+    function_selector!("depositTo", Address),
+    function_selector!("withdrawTo", Address, U256),
+]);
+
+struct WethParams;
+
+/// Metadata reported until `init` sets it
+impl Erc20Params for WethParams {
+    const NAME: &'static str = "Wrapped Ether Example";
+    const SYMBOL: &'static str = "WETH";
+    const DECIMALS: u8 = 18;
+    type Extension = NoExtension;
+    // Every WETH is backed by deposited ETH, so none may be flash minted
+    fn max_flash_loan(_token: &Erc20<Self>) -> U256 {
+        U256::ZERO
+    }
+}
+
+// The contract
+sol_storage! {
+    struct Weth {
+        #[borrow] // Allows erc20 to access Weth's storage and make calls
+        Erc20<WethParams> erc20;
+        /// Guards every method that sends ETH
+        ReentrancyGuard guard;
+    }
+}
+
+sol! {
+    // Matches WETH9 so existing dashboards and subgraphs index Weth unchanged
+    event Deposit(address indexed dst, uint256 wad);
+    event Withdrawal(address indexed src, uint256 wad);
+    event ExcessEthSwept(address indexed to, uint256 amount);
+
+    error MintDisabled();
+    error NoExcessEth(uint256 balance, uint256 total_supply);
+    // `reverted` is false when the helper answered with data that didn't decode
+    error HelperCallFailed(address helper, bool reverted);
+}
+
+// Lets Weth's methods take `&mut self` when called through the router below
+unsafe impl TopLevelStorage for Weth {}
+
+// Makes Weth the entrypoint. The SDK's generated entrypoint rejects calldata
+// shorter than a selector, so this one routes by hand to let bare ETH
+// transfers wrap like `deposit`. Unknown selectors still revert.
+#[entrypoint]
+fn weth_entrypoint(input: Vec<u8>) -> ArbResult {
+    let mut storage = unsafe { <Weth as StorageType>::new(U256::ZERO, 0) };
+    if input.is_empty() {
+        storage.deposit_impl(msg::sender(), msg::value())?;
+        return Ok(vec![]);
+    }
+    if input.len() < 4 {
+        return Err(vec![]);
+    }
+    let selector = u32::from_be_bytes(input[..4].try_into().unwrap());
+    <Weth as Router<_>>::route(&mut storage, selector, &input[4..]).unwrap_or(Err(vec![]))
+}
+
+#[cfg(feature = "export-abi")]
+pub fn main() {
+    stylus_sdk::abi::export::print_abi::<Weth>();
+}
+
+// Lets the router reach components nested inside erc20
+impl Borrow<Ownable> for Weth {
+    fn borrow(&self) -> &Ownable {
+        &self.erc20.ownable
+    }
+}
+
+impl BorrowMut<Ownable> for Weth {
+    fn borrow_mut(&mut self) -> &mut Ownable {
+        &mut self.erc20.ownable
+    }
+}
+
+// The method of crate::helper::Helper that sum_with_helper calls
+const HELPER_SUM: &str = "sum(uint256[])";
+
+// These methods aren't exposed to other contracts
+impl Weth {
+    fn deposit_impl(&mut self, account: Address, value: U256) -> Result<(), Vec<u8>> {
+        self.erc20.mint_impl(account, value)?;
+        evm::log(Deposit {
+            dst: account,
+            wad: value,
+        });
+        Ok(())
+    }
+
+    // Burns `from`'s WETH and sends the ETH to `to`. Follows
+    // checks-effects-interactions: the burn checks and debits the balance
+    // before any ETH leaves, so a reentrant call would already see it spent.
+    // Today the SDK reverts such a call before it gets here; the guard is for
+    // a `reentrant` build.
+    fn withdraw_impl(&mut self, from: Address, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.guard.non_reentrant()?;
+        self.erc20.burn_impl(from, amount)?;
+        evm::log(Withdrawal {
+            src: from,
+            wad: amount,
+        });
+
+        // a failed transfer reverts the whole call, burn included
+        call::transfer_eth(to, amount)?;
+        self.guard.exit();
+        Ok(())
+    }
+}
+
+#[external]
+#[inherit(Erc20<WethParams>, Ownable)]
+impl Weth {
+    #[payable]
+    pub fn deposit(&mut self) -> Result<(), Vec<u8>> {
+        self.deposit_impl(msg::sender(), msg::value())
+    }
+
+    pub fn withdraw(&mut self, amount: U256) -> Result<(), Vec<u8>> {
+        // send the user their funds
+        self.withdraw_impl(msg::sender(), msg::sender(), amount)
+    }
+
+    // unwraps the caller's whole balance, returning how much; zero is a no-op
+    pub fn withdraw_all(&mut self) -> Result<U256, Vec<u8>> {
+        let account = msg::sender();
+        let amount = self.erc20.balance_of(account)?;
+        if amount != U256::ZERO {
+            self.withdraw_impl(account, account, amount)?;
+        }
+        Ok(amount)
+    }
+
+    // true while the ETH held covers every WETH in circulation. ETH forced in
+    // without a deposit, e.g. by selfdestruct, only adds to the margin.
+    pub fn is_fully_collateralized(&self) -> Result<bool, Vec<u8>> {
+        Ok(contract::balance() >= self.erc20.total_supply.get())
+    }
+
+    // the ETH held, the WETH supply and the surplus of the first over the
+    // second, zero while undercollateralized, for monitoring in one call
+    pub fn reserves(&self) -> Result<(U256, U256, U256), Vec<u8>> {
+        let (balance, total_supply) = (contract::balance(), self.erc20.total_supply.get());
+        Ok((balance, total_supply, balance.saturating_sub(total_supply)))
+    }
+
+    // sends `to` the ETH held beyond what backs the WETH supply, returning how
+    // much. Only the owner may call this, and only while there is a surplus.
+    pub fn sweep_excess_eth(&mut self, to: Address) -> Result<U256, Vec<u8>> {
+        self.erc20.ownable.only_owner()?;
+        self.guard.non_reentrant()?;
+        let (balance, total_supply) = (contract::balance(), self.erc20.total_supply.get());
+        if balance <= total_supply {
+            return Err(NoExcessEth {
+                balance,
+                total_supply,
+            }
+            .encode());
+        }
+        let amount = balance - total_supply;
+        call::transfer_eth(to, amount)?;
+        evm::log(ExcessEthSwept { to, amount });
+        self.guard.exit();
+        Ok(amount)
+    }
+
+    // wraps the sent ETH on behalf of another account
+    #[payable]
+    pub fn deposit_to(&mut self, account: Address) -> Result<(), Vec<u8>> {
+        self.deposit_impl(account, msg::value())
+    }
+
+    // unwraps the caller's WETH and sends the ETH to another account
+    pub fn withdraw_to(&mut self, account: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.withdraw_impl(msg::sender(), account, amount)
+    }
+
+    // overrides Erc20's supportsInterface to add the IWeth id
+    pub fn supports_interface(interface_id: FixedBytes<4>) -> Result<bool, Vec<u8>> {
+        Ok(interface_id == IWETH_ID || Erc20::<WethParams>::supports_interface(interface_id)?)
+    }
+
+    // overrides Erc20's mint: WETH is only ever minted against deposited ETH
+    pub fn mint(&mut self, _to: Address, _value: U256) -> Result<(), Vec<u8>> {
+        Err(MintDisabled {}.encode())
+    }
+
+    // sums at most MAX_SUM_VALUES numbers, reverting with SumOverflow if the total overflows
+    pub fn sum(values: Vec<U256>) -> Result<(String, U256), Vec<u8>> {
+        Helper::sum(values)
+    }
+
+    // // ...
+    // pub fn dec(&mut self) -> Result<u8, Erc20Error> {
+    //     self.erc20.decimals()
+    // }
+
+    // calls the helper's sum() method, with the same limit as sum()
+    pub fn sum_with_helper(&self, helper: Address, values: Vec<U256>) -> Result<U256, Vec<u8>> {
+        check_sum_input(&values)?;
+        let failed = |reverted| HelperCallFailed { helper, reverted }.encode();
+        let calldata =
+            encode_call::<(sol_data::Array<sol_data::Uint<256>>,)>(HELPER_SUM, &(values,));
+        let output = RawCall::new_static()
+            .call(helper, &calldata)
+            .map_err(|_| failed(true))?;
+        let (text, sum) = <(sol_data::String, sol_data::Uint<256>)>::decode_params(&output, true)
+            .map_err(|_| failed(false))?;
+        if text != "sum" && text != "empty" {
+            return Err(failed(false));
+        }
+        Ok(sum)
+    }
+}
@@ -0,0 +1,243 @@
+//! `ensure_allowance` and `safe_send` against a mocked node, checking which
+//! requests they send.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use async_trait::async_trait;
+use common::{ensure_allowance, safe_send, send_or_simulate, FeeArgs, TxWait};
+use ethers::{
+    abi::{encode, Token},
+    providers::{JsonRpcClient, JsonRpcError, MockError, MockProvider, MockResponse, Provider},
+    types::{Address, Bytes, Transaction, TransactionReceipt, H256, U256},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use stylus_hello_world::bindings::Erc20;
+
+// A mock that also keeps the method of every request, in order
+#[derive(Debug, Default)]
+struct Recording {
+    mock: MockProvider,
+    methods: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl JsonRpcClient for Recording {
+    type Error = MockError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, MockError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.methods.lock().unwrap().push(method.to_string());
+        self.mock.request(method, params).await
+    }
+}
+
+fn token(owner: Address) -> (Erc20<Provider<Arc<Recording>>>, Arc<Recording>) {
+    let recording = Arc::new(Recording::default());
+    let provider = Provider::new(recording.clone())
+        .with_sender(owner)
+        .interval(Duration::from_millis(1));
+    let token = Erc20::new(Address::repeat_byte(0x70), Arc::new(provider));
+    (token, recording)
+}
+
+// Fee caps spare the mock the fee estimation requests
+fn fees() -> FeeArgs {
+    FeeArgs {
+        max_fee_per_gas: Some(U256::from(2_000_000_000u64)),
+        max_priority_fee_per_gas: Some(U256::zero()),
+        max_gas_price: None,
+    }
+}
+
+fn wait() -> TxWait {
+    TxWait {
+        confirmations: 1,
+        timeout: Duration::from_secs(10),
+    }
+}
+
+// Queues the replies to sending and mining the transaction `hash`. The mock
+// pops replies last in, first out, so they are pushed in reverse.
+fn mined(mock: &MockProvider, hash: H256) {
+    let receipt = TransactionReceipt {
+        transaction_hash: hash,
+        block_number: Some(1.into()),
+        status: Some(1.into()),
+        ..Default::default()
+    };
+    let tx = Transaction {
+        hash,
+        block_number: Some(1.into()),
+        ..Default::default()
+    };
+    mock.push(receipt).unwrap();
+    mock.push(tx).unwrap();
+    mock.push(hash).unwrap();
+    mock.push(U256::from(50_000)).unwrap();
+}
+
+fn allowance(mock: &MockProvider, value: u64) {
+    let word = encode(&[Token::Uint(value.into())]);
+    mock.push::<Bytes, _>(Bytes::from(word)).unwrap();
+}
+
+// Queues the eth_call reply of a simulation returning `value`
+fn simulated(mock: &MockProvider, value: Token) {
+    mock.push::<Bytes, _>(Bytes::from(encode(&[value])))
+        .unwrap();
+}
+
+const SEND: [&str; 4] = [
+    "eth_estimateGas",
+    "eth_sendTransaction",
+    "eth_getTransactionByHash",
+    "eth_getTransactionReceipt",
+];
+
+#[tokio::test]
+async fn a_short_allowance_is_approved_before_the_transfer() {
+    let owner = Address::repeat_byte(0x01);
+    let (token, recording) = token(owner);
+    let (approve_hash, transfer_hash) = (H256::repeat_byte(0xaa), H256::repeat_byte(0xbb));
+    mined(&recording.mock, transfer_hash);
+    simulated(&recording.mock, Token::Bool(true));
+    mined(&recording.mock, approve_hash);
+    simulated(&recording.mock, Token::Bool(true));
+    allowance(&recording.mock, 5);
+
+    let approval = ensure_allowance(
+        &token,
+        owner,
+        U256::from(10),
+        Some(U256::from(10)),
+        &fees(),
+        false,
+        wait(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(approval.unwrap().transaction_hash, approve_hash);
+    let call = fees()
+        .apply(token.transfer_from(owner, Address::repeat_byte(0x02), U256::from(10)))
+        .unwrap();
+    let transfer = send_or_simulate(call, false, wait()).await.unwrap();
+    assert_eq!(transfer.unwrap().transaction_hash, transfer_hash);
+
+    let methods = recording.methods.lock().unwrap();
+    // The approval is simulated before it is sent, like the transfer
+    assert_eq!(methods[..2], ["eth_call", "eth_call"]);
+    assert_eq!(methods[2..6], SEND);
+    assert_eq!(methods[6], "eth_call");
+    assert_eq!(methods[7..], SEND);
+}
+
+#[tokio::test]
+async fn enough_allowance_sends_no_approval() {
+    let owner = Address::repeat_byte(0x01);
+    let (token, recording) = token(owner);
+    allowance(&recording.mock, 10);
+
+    let approval = ensure_allowance(
+        &token,
+        owner,
+        U256::from(10),
+        Some(U256::from(10)),
+        &fees(),
+        false,
+        wait(),
+    )
+    .await
+    .unwrap();
+    assert!(approval.is_none());
+    assert_eq!(*recording.methods.lock().unwrap(), ["eth_call"]);
+}
+
+#[tokio::test]
+async fn a_short_allowance_fails_without_auto_approve() {
+    let owner = Address::repeat_byte(0x01);
+    let (token, recording) = token(owner);
+    allowance(&recording.mock, 5);
+    let err = ensure_allowance(&token, owner, U256::from(10), None, &fees(), false, wait())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("allows"), "{err}");
+
+    // Nor is too small an approval sent
+    allowance(&recording.mock, 5);
+    let err = ensure_allowance(
+        &token,
+        owner,
+        U256::from(10),
+        Some(U256::from(9)),
+        &fees(),
+        false,
+        wait(),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("short of 10"), "{err}");
+    assert_eq!(*recording.methods.lock().unwrap(), ["eth_call", "eth_call"]);
+}
+
+#[tokio::test]
+async fn a_dry_run_only_simulates_the_approval() {
+    let owner = Address::repeat_byte(0x01);
+    let (token, recording) = token(owner);
+    simulated(&recording.mock, Token::Bool(true));
+    recording.mock.push(U256::from(50_000)).unwrap();
+    allowance(&recording.mock, 5);
+
+    let approval = ensure_allowance(
+        &token,
+        owner,
+        U256::from(10),
+        Some(U256::from(10)),
+        &fees(),
+        true,
+        wait(),
+    )
+    .await
+    .unwrap();
+    assert!(approval.is_none());
+    assert_eq!(
+        *recording.methods.lock().unwrap(),
+        ["eth_call", "eth_estimateGas", "eth_call"]
+    );
+}
+
+#[tokio::test]
+async fn a_predicted_revert_is_never_broadcast() {
+    let owner = Address::repeat_byte(0x01);
+    let (token, recording) = token(owner);
+    let mut data =
+        ethers::utils::keccak256("InsufficientBalance(address,uint256,uint256)")[..4].to_vec();
+    data.extend(encode(&[
+        Token::Address(owner),
+        Token::Uint(5.into()),
+        Token::Uint(10.into()),
+    ]));
+    recording
+        .mock
+        .push_response(MockResponse::Error(JsonRpcError {
+            code: 3,
+            message: "execution reverted".into(),
+            data: Some(Bytes::from(data).to_string().into()),
+        }));
+
+    let call = fees()
+        .apply(token.transfer(Address::repeat_byte(0x02), U256::from(10)))
+        .unwrap();
+    let err = safe_send(call, wait()).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("call reverted: InsufficientBalance(from: {owner:?}, have: 5, want: 10)")
+    );
+    assert_eq!(*recording.methods.lock().unwrap(), ["eth_call"]);
+}
@@ -0,0 +1,25 @@
+//! `Amount`, converting between whole tokens and base units.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::Amount;
+use ethers::types::U256;
+
+#[test]
+fn whole_tokens_round_trip_through_base_units() {
+    let amount = Amount::from_tokens("1.25", 18).unwrap();
+    let base_units = U256::from(1_250_000_000_000_000_000u64);
+    assert_eq!(amount.base_units(), base_units);
+    assert_eq!(amount.tokens(), "1.25");
+    assert_eq!(amount, Amount::from_base_units(base_units, 18));
+    assert_eq!(Amount::from_tokens(&amount.tokens(), 18).unwrap(), amount);
+    assert_eq!(amount.to_string(), "1.25 (1250000000000000000 base units)");
+
+    // The same digits mean another amount at other decimals
+    assert_eq!(
+        Amount::from_tokens("1.25", 6).unwrap().base_units(),
+        U256::from(1_250_000)
+    );
+    assert!(Amount::from_tokens("1.25", 1).is_err());
+}
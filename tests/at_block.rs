@@ -0,0 +1,49 @@
+//! `--at-block` parsing, and the block reaching the node with the call.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::{parse_block_tag, pin_to_block};
+use ethers::{
+    abi::{encode, Token},
+    providers::Provider,
+    types::{Address, BlockId, BlockNumber, Bytes},
+    utils::serialize,
+};
+use std::sync::Arc;
+use stylus_hello_world::bindings::Erc20;
+
+#[test]
+fn block_tags_parse() {
+    assert_eq!(parse_block_tag("latest").unwrap(), BlockNumber::Latest);
+    assert_eq!(parse_block_tag("pending").unwrap(), BlockNumber::Pending);
+    assert_eq!(
+        parse_block_tag("1234").unwrap(),
+        BlockNumber::Number(1234.into())
+    );
+    for input in ["", "earliest", "0x10", "-1", "12a", "99999999999999999999"] {
+        let err = parse_block_tag(input).unwrap_err();
+        assert!(
+            err.to_string().contains("is not a block number"),
+            "{input}: {err}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn the_block_is_sent_with_the_call() {
+    let (provider, mock) = Provider::mocked();
+    let token = Erc20::new(Address::repeat_byte(0x70), Arc::new(provider));
+    for (block, sent) in [
+        (Some(BlockNumber::Number(7.into())), BlockId::from(7u64)),
+        (Some(BlockNumber::Pending), BlockNumber::Pending.into()),
+        (None, BlockNumber::Latest.into()),
+    ] {
+        let word = encode(&[Token::Uint(18.into())]);
+        mock.push::<Bytes, _>(Bytes::from(word)).unwrap();
+        let call = pin_to_block(token.decimals(), block);
+        assert_eq!(call.call().await.unwrap(), 18);
+        mock.assert_request("eth_call", [serialize(&call.tx), serialize(&sent)])
+            .unwrap();
+    }
+}
@@ -0,0 +1,47 @@
+//! `assert_balance_delta` against a mocked token.
+
+mod support;
+
+use ethers::{
+    abi::{encode, Token},
+    providers::{MockProvider, Provider},
+    types::{Address, Bytes, I256, U256},
+};
+use std::sync::Arc;
+use stylus_hello_world::bindings::Erc20;
+use support::assert_balance_delta;
+
+// A token whose balanceOf answers `before`, then `after`
+fn token(before: u64, after: u64) -> (Erc20<Provider<MockProvider>>, MockProvider) {
+    let (provider, mock) = Provider::mocked();
+    for balance in [after, before] {
+        let reply = Bytes::from(encode(&[Token::Uint(U256::from(balance))]));
+        mock.push::<Bytes, _>(reply).unwrap();
+    }
+    (
+        Erc20::new(Address::repeat_byte(1), Arc::new(provider)),
+        mock,
+    )
+}
+
+#[tokio::test]
+async fn increases_and_decreases_are_signed() {
+    let account = Address::repeat_byte(2);
+    let (up, _mock) = token(100, 130);
+    let output = assert_balance_delta(&up, account, 30, || async { "ran" }).await;
+    assert_eq!(output, "ran");
+
+    let (down, _mock) = token(100, 60);
+    assert_balance_delta(&down, account, -40, || async {}).await;
+
+    let (big, _mock) = token(u64::MAX, 0);
+    let expected = -I256::from(u64::MAX);
+    assert_balance_delta(&big, account, expected, || async {}).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "moved by -40")]
+async fn a_wrong_delta_fails_with_the_actual_one() {
+    let (token, _mock) = token(100, 60);
+    assert_balance_delta(&token, Address::repeat_byte(2), 40, || async {}).await;
+}
@@ -0,0 +1,45 @@
+//! `BalanceWatch`, which picks out the balance readings worth printing.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::{BalanceChange, BalanceWatch, Crossing};
+use ethers::types::U256;
+
+fn change(previous: Option<u64>, balance: u64, crossed: Option<Crossing>) -> Option<BalanceChange> {
+    Some(BalanceChange {
+        previous: previous.map(U256::from),
+        balance: U256::from(balance),
+        crossed,
+    })
+}
+
+#[test]
+fn only_readings_that_differ_are_reported() {
+    let mut watch = BalanceWatch::new(None);
+    // An empty balance is still a first reading
+    assert_eq!(watch.observe(U256::zero()), change(None, 0, None));
+    assert_eq!(watch.observe(U256::zero()), None);
+    assert_eq!(watch.observe(U256::from(5)), change(Some(0), 5, None));
+    assert_eq!(watch.observe(U256::from(5)), None);
+    assert_eq!(watch.observe(U256::from(2)), change(Some(5), 2, None));
+}
+
+#[test]
+fn crossing_the_threshold_is_flagged_each_way() {
+    let mut watch = BalanceWatch::new(Some(U256::from(10)));
+    // Starting above it isn't a crossing
+    assert_eq!(watch.observe(U256::from(12)), change(None, 12, None));
+    assert_eq!(watch.observe(U256::from(10)), change(Some(12), 10, None));
+    assert_eq!(
+        watch.observe(U256::from(9)),
+        change(Some(10), 9, Some(Crossing::Down))
+    );
+    assert_eq!(watch.observe(U256::from(3)), change(Some(9), 3, None));
+    // Reaching it exactly counts
+    assert_eq!(
+        watch.observe(U256::from(10)),
+        change(Some(3), 10, Some(Crossing::Up))
+    );
+    assert_eq!(watch.observe(U256::from(50)), change(Some(10), 50, None));
+}
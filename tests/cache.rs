@@ -0,0 +1,82 @@
+//! `CachedReader` against a mocked node.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::CachedReader;
+use ethers::{
+    abi::{encode, Token},
+    providers::{MockProvider, Provider},
+    types::{Address, Bytes, U256},
+};
+use std::sync::Arc;
+use std::time::Duration;
+use stylus_hello_world::bindings::{erc_20::TransferFilter, Erc20};
+
+fn reader(ttl: Duration) -> (CachedReader<Provider<MockProvider>>, MockProvider) {
+    let (provider, mock) = Provider::mocked();
+    let token = Erc20::new(Address::repeat_byte(0x70), Arc::new(provider));
+    (CachedReader::new(token, ttl), mock)
+}
+
+// Queues the reply to one uint256 eth_call
+fn uint(mock: &MockProvider, value: u64) {
+    let word = encode(&[Token::Uint(value.into())]);
+    mock.push::<Bytes, _>(Bytes::from(word)).unwrap();
+}
+
+#[tokio::test]
+async fn entries_are_refetched_once_the_ttl_expires() {
+    let account = Address::repeat_byte(0x01);
+    let (reader, mock) = reader(Duration::from_millis(50));
+    uint(&mock, 5);
+    assert_eq!(reader.get_balance(account).await.unwrap(), U256::from(5));
+
+    // Within the ttl the node isn't asked again, so the next reply waits
+    uint(&mock, 7);
+    assert_eq!(reader.get_balance(account).await.unwrap(), U256::from(5));
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(reader.get_balance(account).await.unwrap(), U256::from(7));
+
+    uint(&mock, 18);
+    assert_eq!(reader.get_decimals().await.unwrap(), 18);
+    assert_eq!(reader.get_decimals().await.unwrap(), 18);
+}
+
+#[tokio::test]
+async fn a_transfer_invalidates_the_entries_it_touches() {
+    let (from, to, bystander) = (
+        Address::repeat_byte(0x01),
+        Address::repeat_byte(0x02),
+        Address::repeat_byte(0x03),
+    );
+    let spender = Address::repeat_byte(0x04);
+    let (reader, mock) = reader(Duration::from_secs(3600));
+    for value in [30, 20, 10, 100] {
+        uint(&mock, value);
+    }
+    assert_eq!(reader.get_balance(from).await.unwrap(), U256::from(100));
+    assert_eq!(reader.get_balance(to).await.unwrap(), U256::from(10));
+    assert_eq!(reader.get_balance(bystander).await.unwrap(), U256::from(20));
+    assert_eq!(
+        reader.get_allowance(from, spender).await.unwrap(),
+        U256::from(30)
+    );
+
+    reader.invalidate(&TransferFilter {
+        from,
+        to,
+        value: U256::from(25),
+    });
+    for value in [5, 35, 75] {
+        uint(&mock, value);
+    }
+    assert_eq!(reader.get_balance(from).await.unwrap(), U256::from(75));
+    assert_eq!(reader.get_balance(to).await.unwrap(), U256::from(35));
+    assert_eq!(
+        reader.get_allowance(from, spender).await.unwrap(),
+        U256::from(5)
+    );
+    // Still cached, as the transfer didn't involve it
+    assert_eq!(reader.get_balance(bystander).await.unwrap(), U256::from(20));
+}
@@ -0,0 +1,82 @@
+//! Loading `--config` and `--address-book` files, with the examples' own
+//! `Config` and `AddressBook`.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::{AddressBook, Config, Profile};
+use ethers::types::Address;
+use std::path::Path;
+
+fn address(input: &str) -> Option<Address> {
+    Some(input.parse().unwrap())
+}
+
+#[test]
+fn the_sample_config_loads() {
+    let config = Config::read(Path::new("examples/config.example.toml")).unwrap();
+    assert_eq!(
+        config.profile(None).unwrap(),
+        Profile {
+            priv_key_path: Some("/opt/7d3f.pri".into()),
+            ..Profile::default()
+        }
+    );
+
+    // A profile keeps the defaults it doesn't override
+    assert_eq!(
+        config.profile(Some("stylus-testnet")).unwrap(),
+        Profile {
+            rpc_url: Some("https://stylus-testnet.arbitrum.io/rpc".into()),
+            program_address: address("0xC4CA13280b8EafD7A033670E620B1AF74950E147"),
+            helper_address: address("0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E"),
+            priv_key_path: Some("/opt/7d3f.pri".into()),
+        }
+    );
+    let devnode = config.profile(Some("devnode")).unwrap();
+    assert_eq!(devnode.rpc_url.as_deref(), Some("http://localhost:8547"));
+    assert_eq!(devnode.program_address, None);
+
+    let err = config.profile(Some("mainnet")).unwrap_err();
+    assert!(err.to_string().contains("\"devnode\", \"stylus-testnet\""));
+}
+
+#[test]
+fn profile_values_override_the_defaults() {
+    let config = Config::from_toml(
+        r#"
+        rpc_url = "http://default"
+        [networks.other]
+        rpc_url = "http://other"
+        "#,
+    )
+    .unwrap();
+    let rpc_url = |name| config.profile(name).unwrap().rpc_url.unwrap();
+    assert_eq!(rpc_url(None), "http://default");
+    assert_eq!(rpc_url(Some("other")), "http://other");
+}
+
+#[test]
+fn miscased_addresses_are_rejected() {
+    let config =
+        Config::from_toml(r#"program_address = "0x3f1eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E""#);
+    assert!(config.unwrap_err().to_string().contains("checksum"));
+}
+
+#[test]
+fn address_book_names_resolve_before_literal_addresses() {
+    let book = AddressBook::read(Path::new("examples/address_book.example.toml")).unwrap();
+    assert_eq!(
+        book.resolve("weth").ok(),
+        address("0xC4CA13280b8EafD7A033670E620B1AF74950E147")
+    );
+
+    // Anything else must be an address itself
+    let raw = "0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E";
+    assert_eq!(book.resolve(raw).ok(), address(raw));
+    let err = book.resolve("usdc").unwrap_err();
+    assert!(err.to_string().ends_with("not a name in the address book"));
+
+    let err = AddressBook::from_toml(r#"usdc = "0x3f1eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E""#);
+    assert!(err.unwrap_err().to_string().contains("\"usdc\""));
+}
@@ -0,0 +1,59 @@
+//! `confirm` against a mocked node that never mines the transaction.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use async_trait::async_trait;
+use common::{confirm, TxWait};
+use ethers::{
+    providers::{JsonRpcClient, MockError, PendingTransaction, Provider},
+    types::{Transaction, H256},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
+use std::time::Duration;
+
+// Knows every transaction, but never puts one in a block
+#[derive(Debug)]
+struct NeverMined;
+
+#[async_trait]
+impl JsonRpcClient for NeverMined {
+    type Error = MockError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, MockError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let reply = match method {
+            "eth_getTransactionByHash" => {
+                let [hash]: [H256; 1] = serde_json::from_value(serde_json::to_value(params)?)?;
+                serde_json::to_value(Transaction {
+                    hash,
+                    ..Default::default()
+                })?
+            }
+            _ => serde_json::Value::Null,
+        };
+        Ok(serde_json::from_value(reply)?)
+    }
+}
+
+#[tokio::test]
+async fn an_unmined_transaction_times_out_with_its_hash() {
+    let provider = Provider::new(NeverMined).interval(Duration::from_millis(50));
+    let hash = H256::repeat_byte(0xab);
+    let wait = TxWait {
+        confirmations: 1,
+        timeout: Duration::from_secs(2),
+    };
+
+    let err = confirm(PendingTransaction::new(hash, &provider), wait)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("transaction {hash:?} not mined within 2 seconds")
+    );
+}
@@ -0,0 +1,46 @@
+//! The `decode_calldata` command's output.
+
+use ethers::{abi::AbiEncode, types::U256};
+use std::process::Command;
+use stylus_hello_world::bindings::erc_20::TransferCall;
+
+const TO: &str = "0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E";
+
+fn decode(calldata: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_decode_calldata"))
+        .arg(calldata)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn a_transfer_decodes_to_its_arguments() {
+    let call = TransferCall {
+        to: TO.parse().unwrap(),
+        value: U256::from(1_500),
+    };
+    let printed = decode(&call.encode_hex());
+    let expected = format!(
+        "Erc20.transfer(address,uint256)\n  to: {}\n  value: 1500\n",
+        TO.to_lowercase()
+    );
+    assert_eq!(printed, expected);
+}
+
+#[test]
+fn an_unknown_selector_is_printed_raw() {
+    assert_eq!(decode("0xdeadbeef0000"), "unknown selector 0xdeadbeef\n");
+}
+
+#[test]
+fn calldata_shorter_than_a_selector_is_rejected() {
+    let output = Command::new(env!("CARGO_BIN_EXE_decode_calldata"))
+        .arg("0xa905")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("too short for a selector"), "{stderr}");
+}
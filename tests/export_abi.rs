@@ -0,0 +1,33 @@
+//! The `export_abi` command's files.
+
+use ethers::abi::Abi;
+use std::process::Command;
+use stylus_hello_world::bindings::{exported_abis, ERC20_ABI, HELPER_ABI, WETH_ABI};
+
+#[test]
+fn exported_files_parse_back_to_the_bindings_abis() {
+    let out = std::env::temp_dir().join(format!("export_abi_{}", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_export_abi"))
+        .arg("--out")
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let printed = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(printed.lines().count(), exported_abis().len());
+
+    let read = |file: &str| -> Abi {
+        let json = std::fs::read_to_string(out.join(file)).unwrap();
+        serde_json::from_str(&json).unwrap()
+    };
+    let (erc20, weth, helper) = (read("Erc20.json"), read("Weth.json"), read("Helper.json"));
+    assert_eq!(erc20, *ERC20_ABI);
+    assert_eq!(weth, *WETH_ABI);
+    assert_eq!(helper, *HELPER_ABI);
+
+    // Spot checks that the files are full ABIs, not just well-formed JSON
+    assert_eq!(erc20.function("transfer").unwrap().inputs.len(), 2);
+    assert!(weth.function("deposit").is_ok() && weth.event("Deposit").is_ok());
+    assert!(helper.error("SumOverflow").is_ok());
+    std::fs::remove_dir_all(&out).unwrap();
+}
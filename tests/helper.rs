@@ -0,0 +1,36 @@
+//! Unit tests of `Helper::sum`, the entrypoint `sumWithHelper` cross-calls.
+
+use alloy_primitives::U256;
+use alloy_sol_types::SolError;
+use stylus_hello_world::helper::{Helper, InputTooLarge, SumOverflow, MAX_SUM_VALUES};
+
+#[test]
+fn an_empty_input_is_tagged_empty() {
+    let result = Helper::sum(vec![]);
+    assert_eq!(result, Ok(("empty".into(), U256::ZERO)));
+}
+
+#[test]
+fn values_are_added_and_tagged_sum() {
+    let values = [1, 2, 3].map(U256::from).to_vec();
+    assert_eq!(Helper::sum(values), Ok(("sum".into(), U256::from(6))));
+}
+
+#[test]
+fn an_overflowing_sum_reverts() {
+    let result = Helper::sum(vec![U256::MAX, U256::from(1)]);
+    assert_eq!(result, Err(SumOverflow {}.encode()));
+}
+
+#[test]
+fn at_most_max_sum_values_are_added() {
+    let (_, sum) = Helper::sum(vec![U256::from(1); MAX_SUM_VALUES]).unwrap();
+    assert_eq!(sum, U256::from(MAX_SUM_VALUES));
+
+    let result = Helper::sum(vec![U256::from(1); MAX_SUM_VALUES + 1]);
+    let too_large = InputTooLarge {
+        length: U256::from(MAX_SUM_VALUES + 1),
+        max: U256::from(MAX_SUM_VALUES),
+    };
+    assert_eq!(result, Err(too_large.encode()));
+}
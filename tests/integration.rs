@@ -0,0 +1,2866 @@
+//! End-to-end tests against a local Arbitrum Nitro devnode.
+//!
+//! They deploy fresh programs from deployment tx data written by cargo stylus,
+//! so build that first:
+//!
+//! ```text
+//! cargo stylus deploy --dry-run --output-tx-data weth.tx
+//! cargo stylus deploy --features capped --dry-run --output-tx-data capped.tx
+//! cargo stylus deploy --features fee --dry-run --output-tx-data fee.tx
+//! cargo stylus deploy --features vault --dry-run --output-tx-data vault.tx
+//! cargo stylus deploy --features hooks --dry-run --output-tx-data hooks.tx
+//! cargo stylus deploy --features nft --dry-run --output-tx-data nft.tx
+//! cargo stylus deploy --features votes --dry-run --output-tx-data votes.tx
+//! cargo stylus deploy --features vesting --dry-run --output-tx-data vesting.tx
+//! cargo stylus deploy --features faucet --dry-run --output-tx-data faucet.tx
+//! cargo stylus deploy --features wrapper --dry-run --output-tx-data wrapper.tx
+//! cargo stylus deploy --features helper --dry-run --output-tx-data helper.tx
+//! ```
+//!
+//! then run them one at a time, since they share the funded devnode account:
+//!
+//! ```text
+//! WETH_TX_DATA=weth.tx CAPPED_TX_DATA=capped.tx FEE_TX_DATA=fee.tx VAULT_TX_DATA=vault.tx \
+//! HOOKS_TX_DATA=hooks.tx NFT_TX_DATA=nft.tx VOTES_TX_DATA=votes.tx \
+//! VESTING_TX_DATA=vesting.tx FAUCET_TX_DATA=faucet.tx WRAPPER_TX_DATA=wrapper.tx \
+//! HELPER_TX_DATA=helper.tx \
+//! cargo test --features client --test integration -- --ignored --test-threads=1
+//! ```
+//!
+//! `DEVNODE_RPC_URL` and `DEVNODE_PRIV_KEY` default to the nitro devnode's
+//! endpoint and prefunded key, and can point CI at a container instead.
+//!
+//! `gas_used_stays_within_the_baseline` compares Weth's gas costs against
+//! `tests/fixtures/gas_baseline.json`. After a change that is meant to move
+//! them, or to record them in the first place, refresh the file and commit it:
+//!
+//! ```text
+//! UPDATE_GAS_BASELINE=1 WETH_TX_DATA=weth.tx \
+//! cargo test --features client --test integration gas_used -- --ignored
+//! ```
+//!
+//! The switch is an environment variable rather than an `--update-baseline`
+//! flag because the test harness refuses arguments it doesn't know, and
+//! replacing it with `harness = false` would mean replacing it for the whole
+//! suite.
+
+mod support;
+
+use ethers::{
+    abi::{encode, Detokenize, Token},
+    contract::{parse_log, ContractCall},
+    core::rand::thread_rng,
+    providers::Middleware,
+    signers::{LocalWallet, Signer},
+    types::{
+        transaction::eip712::EIP712Domain, Address, BlockNumber, TransactionReceipt,
+        TransactionRequest, H256, I256, U256,
+    },
+    utils::{keccak256, parse_ether},
+};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use stylus_hello_world::abi::{selector, sign_typed};
+use stylus_hello_world::bindings::{
+    erc_20,
+    faucet::{Faucet, FaucetErrors},
+    nft::{Erc721, Erc721Errors},
+    vault::{Erc4626, Erc4626Errors},
+    vesting::{Vesting, VestingErrors},
+    weth,
+    wrapper::{WithdrawnFilter, Wrapper, WrapperErrors},
+    AfterTokenTransferFilter, BeforeTokenTransferFilter, CappedErc20, CappedErc20Errors, Erc20,
+    Erc20Errors, FeeErc20, FeeRecipientUpdatedFilter, FeeUpdatedFilter, FutureLookup, Helper,
+    InvalidFee, VotesErc20, Weth, WethErrors,
+};
+use stylus_hello_world::client::{connect, deploy_program, read_tx_data, Client, RetryPolicy};
+use support::assert_balance_delta;
+
+const DEVNODE_RPC_URL: &str = "http://localhost:8547";
+const DEVNODE_PRIV_KEY: &str = "b6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9520659";
+
+fn env_or(key: &str, default: &str) -> String {
+    std::env::var(key).unwrap_or_else(|_| default.to_string())
+}
+
+async fn devnode() -> Arc<Client> {
+    let rpc_url = env_or("DEVNODE_RPC_URL", DEVNODE_RPC_URL);
+    let wallet = LocalWallet::from_str(&env_or("DEVNODE_PRIV_KEY", DEVNODE_PRIV_KEY))
+        .expect("DEVNODE_PRIV_KEY is not a private key");
+    connect(&rpc_url, wallet, RetryPolicy::default())
+        .await
+        .expect("could not reach the devnode")
+}
+
+// A fresh account holding some devnode ETH for gas
+async fn funded_account(client: &Arc<Client>) -> Arc<Client> {
+    let rpc_url = env_or("DEVNODE_RPC_URL", DEVNODE_RPC_URL);
+    let account = connect(
+        &rpc_url,
+        LocalWallet::new(&mut thread_rng()),
+        RetryPolicy::default(),
+    )
+    .await
+    .expect("could not reach the devnode");
+    let funding = TransactionRequest::new()
+        .to(account.signer().address())
+        .value(parse_ether("0.1").unwrap());
+    let receipt = client.send_transaction(funding, None).await.unwrap().await;
+    receipt.unwrap().expect("funding transfer was dropped");
+    account
+}
+
+async fn deploy(client: &Arc<Client>, tx_data_var: &str) -> Address {
+    let path = std::env::var(tx_data_var).unwrap_or_else(|_| panic!("{tx_data_var} is not set"));
+    let tx_data = read_tx_data(&path).expect("could not read tx data");
+    deploy_program(client.clone(), tx_data)
+        .await
+        .expect("could not deploy")
+}
+
+async fn send<D: Detokenize>(call: ContractCall<Client, D>) -> TransactionReceipt {
+    let receipt = call.send().await.expect("could not send").await.unwrap();
+    let receipt = receipt.expect("transaction was dropped");
+    assert_eq!(receipt.status, Some(1.into()), "transaction reverted");
+    receipt
+}
+
+// A CappedErc20 is the plain mintable Erc20 among the programs
+async fn deploy_token(client: &Arc<Client>, decimals: u8, cap: U256) -> Address {
+    let address = deploy(client, "CAPPED_TX_DATA").await;
+    let capped = CappedErc20::new(address, client.clone());
+    send(capped.init("Capped".into(), "CAP".into(), decimals, cap)).await;
+    address
+}
+
+// A vault over a fresh asset token, of which the caller holds `supply`
+async fn deploy_vault(client: &Arc<Client>, supply: U256) -> (Erc20<Client>, Erc4626<Client>) {
+    let asset = deploy_token(client, 18, supply).await;
+    send(CappedErc20::new(asset, client.clone()).mint(client.signer().address(), supply)).await;
+    let address = deploy(client, "VAULT_TX_DATA").await;
+    let vault = Erc4626::new(address, client.clone());
+    send(vault.init("Vault".into(), "VLT".into(), asset)).await;
+    let asset = Erc20::new(asset, client.clone());
+    send(asset.approve(address, U256::MAX)).await;
+    (asset, vault)
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn weth_deposit_transfer_withdraw() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+
+    let token = Erc20::new(weth.address(), client.clone());
+    let delta = |amount: U256| I256::try_from(amount).unwrap();
+
+    let wad = parse_ether("1").unwrap();
+    assert_balance_delta(&token, me, delta(wad), || send(weth.deposit().value(wad))).await;
+    assert_eq!(weth.total_supply().call().await.unwrap(), wad);
+
+    let sent = wad / 4;
+    assert_balance_delta(&token, other, delta(sent), || {
+        assert_balance_delta(&token, me, -delta(sent), || {
+            send(weth.transfer(other, sent))
+        })
+    })
+    .await;
+
+    assert_balance_delta(&token, me, -delta(wad - sent), || {
+        send(weth.withdraw(wad - sent))
+    })
+    .await;
+    assert_eq!(weth.total_supply().call().await.unwrap(), sent);
+
+    // Withdrawing more than the balance reverts
+    assert!(weth.withdraw(U256::one()).call().await.is_err());
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn weth_withdraw_all() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+
+    let wad = parse_ether("1").unwrap();
+    send(weth.deposit().value(wad)).await;
+    assert_eq!(weth.withdraw_all().call().await.unwrap(), wad);
+    send(weth.withdraw_all()).await;
+    assert_eq!(weth.balance_of(me).call().await.unwrap(), U256::zero());
+    assert_eq!(weth.total_supply().call().await.unwrap(), U256::zero());
+
+    // With nothing left it succeeds and unwraps nothing
+    assert_eq!(weth.withdraw_all().call().await.unwrap(), U256::zero());
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn plain_eth_transfers_wrap() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+
+    let wad = parse_ether("1").unwrap();
+    let plain = TransactionRequest::new().to(weth.address()).value(wad);
+    let receipt = client.send_transaction(plain, None).await.unwrap().await;
+    let receipt = receipt.unwrap().expect("transfer was dropped");
+    assert_eq!(receipt.status, Some(1.into()));
+    // Logged after the mint's Transfer, like deposit()
+    let deposit = parse_log::<weth::DepositFilter>(receipt.logs[1].clone()).unwrap();
+    assert_eq!((deposit.dst, deposit.wad), (me, wad));
+    assert_eq!(weth.balance_of(me).call().await.unwrap(), wad);
+
+    // Calldata too short for a selector reverts, even when it starts one
+    let deposit = keccak256("deposit()");
+    for len in 1..4 {
+        let short = TransactionRequest::new()
+            .to(weth.address())
+            .value(wad)
+            .data(deposit[..len].to_vec());
+        assert!(client.call(&short.into(), None).await.is_err());
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn weth_logs_index_the_credited_and_debited_accounts() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+    let holder = funded_account(&client).await;
+    let recipient = Address::random();
+    let wad = parse_ether("0.01").unwrap();
+    let topic = |account: Address| H256::from(account);
+
+    // Paid for by me, credited to the holder
+    let receipt = send(weth.deposit_to(holder.signer().address()).value(wad)).await;
+    let log = receipt.logs[1].clone();
+    assert_eq!(log.topics[1], topic(holder.signer().address()));
+    let deposit = parse_log::<weth::DepositFilter>(log).unwrap();
+    assert_eq!((deposit.dst, deposit.wad), (holder.signer().address(), wad));
+    assert_ne!(deposit.dst, me);
+
+    // Debited from the holder, paid out to the recipient
+    let holder_weth = Weth::new(weth.address(), holder.clone());
+    let receipt = send(holder_weth.withdraw_to(recipient, wad / 2)).await;
+    let log = receipt.logs[1].clone();
+    assert_eq!(log.topics[1], topic(holder.signer().address()));
+    let withdrawal = parse_log::<weth::WithdrawalFilter>(log).unwrap();
+    assert_eq!(
+        (withdrawal.src, withdrawal.wad),
+        (holder.signer().address(), wad / 2)
+    );
+    assert_eq!(client.get_balance(recipient, None).await.unwrap(), wad / 2);
+
+    // The plain variants index the caller
+    let receipt = send(weth.deposit().value(wad)).await;
+    let deposit = parse_log::<weth::DepositFilter>(receipt.logs[1].clone()).unwrap();
+    assert_eq!(deposit.dst, me);
+    let receipt = send(weth.withdraw(wad)).await;
+    let withdrawal = parse_log::<weth::WithdrawalFilter>(receipt.logs[1].clone()).unwrap();
+    assert_eq!(withdrawal.src, me);
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn sum_takes_at_most_1024_values() {
+    let client = devnode().await;
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    let too_large = |err: ethers::contract::ContractError<Client>| {
+        matches!(
+            err.decode_contract_revert::<WethErrors>(),
+            Some(WethErrors::InputTooLarge(_))
+        )
+    };
+
+    let (_, total) = weth.sum(vec![U256::one(); 1024]).call().await.unwrap();
+    assert_eq!(total, U256::from(1024));
+    assert!(too_large(
+        weth.sum(vec![U256::one(); 1025]).call().await.unwrap_err()
+    ));
+
+    // Weth checks the limit before it calls the helper
+    let helper = deploy(&client, "HELPER_TX_DATA").await;
+    let via_helper = |len| weth.sum_with_helper(helper, vec![U256::one(); len]);
+    assert_eq!(via_helper(1024).call().await.unwrap(), U256::from(1024));
+    assert!(too_large(via_helper(1025).call().await.unwrap_err()));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn sum_with_helper_reports_how_the_helper_failed() {
+    let client = devnode().await;
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    let values = vec![U256::from(1), U256::from(2), U256::from(3)];
+    let failure = |err: ethers::contract::ContractError<Client>| match err
+        .decode_contract_revert::<WethErrors>()
+    {
+        Some(WethErrors::HelperCallFailed(failed)) => (failed.helper, failed.reverted),
+        other => panic!("expected HelperCallFailed, got {other:?}"),
+    };
+
+    // Calling an account without code succeeds with no output, which doesn't decode
+    let no_code = Address::random();
+    let err = weth.sum_with_helper(no_code, values.clone()).call().await;
+    assert_eq!(failure(err.unwrap_err()), (no_code, false));
+
+    let reverting = deploy_evm(&client, &REVERTS).await;
+    let err = weth.sum_with_helper(reverting, values.clone()).call().await;
+    assert_eq!(failure(err.unwrap_err()), (reverting, true));
+
+    let helper = deploy(&client, "HELPER_TX_DATA").await;
+    let sum = weth.sum_with_helper(helper, values).call().await.unwrap();
+    assert_eq!(sum, U256::from(6));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn sum_with_helper_matches_the_helper() {
+    let client = devnode().await;
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    let helper = Helper::new(deploy(&client, "HELPER_TX_DATA").await, client.clone());
+
+    for values in [vec![], vec![U256::from(7)], vec![U256::from(40); 3]] {
+        let (_, direct) = helper.sum(values.clone()).call().await.unwrap();
+        let via_weth = weth
+            .sum_with_helper(helper.address(), values)
+            .call()
+            .await
+            .unwrap();
+        assert_eq!(via_weth, direct);
+    }
+    let (text, sum) = helper.sum(vec![U256::from(40); 3]).call().await.unwrap();
+    assert_eq!((text.as_str(), sum), ("sum", U256::from(120)));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn token_metadata_matches_the_getters() {
+    let client = devnode().await;
+    let address = deploy_token(&client, 6, U256::from(1_000)).await;
+    let token = Erc20::new(address, client.clone());
+    send(token.mint(client.signer().address(), U256::from(42))).await;
+
+    let (name, symbol, decimals, total_supply) = token.token_metadata().call().await.unwrap();
+    assert_eq!(name, token.name().call().await.unwrap());
+    assert_eq!(symbol, token.symbol().call().await.unwrap());
+    assert_eq!(decimals, token.decimals().call().await.unwrap());
+    assert_eq!(total_supply, token.total_supply().call().await.unwrap());
+    assert_eq!(
+        (name.as_str(), symbol.as_str(), decimals, total_supply),
+        ("Capped", "CAP", 6, U256::from(42))
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn erc20_mint_transfer_and_cap() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let cap = U256::from(1_000_000);
+    let address = deploy_token(&client, 6, cap).await;
+    let capped = CappedErc20::new(address, client.clone());
+    let token = Erc20::new(address, client.clone());
+    assert_eq!(token.decimals().call().await.unwrap(), 6);
+
+    send(capped.mint(me, cap)).await;
+    assert_eq!(token.balance_of(me).call().await.unwrap(), cap);
+
+    assert_balance_delta(&token, other, 10, || {
+        assert_balance_delta(&token, me, -10, || {
+            send(token.transfer(other, U256::from(10)))
+        })
+    })
+    .await;
+    assert_eq!(token.total_supply().call().await.unwrap(), cap);
+
+    let err = capped.mint(me, U256::one()).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<CappedErc20Errors>(),
+        Some(CappedErc20Errors::ExceededCap(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn a_zero_cap_is_refused() {
+    let client = devnode().await;
+    let capped = CappedErc20::new(deploy(&client, "CAPPED_TX_DATA").await, client.clone());
+    let err = capped
+        .init("Capped".into(), "CAP".into(), 18, U256::zero())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<CappedErc20Errors>(),
+        Some(CappedErc20Errors::ZeroCap(_))
+    ));
+
+    // Nothing was initialized, so a real cap can still be set
+    send(capped.init("Capped".into(), "CAP".into(), 18, U256::one())).await;
+    assert_eq!(capped.cap().call().await.unwrap(), U256::one());
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn blocklist_freezes_senders_and_recipients() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    let blocked = |err: ethers::contract::ContractError<Client>| {
+        matches!(
+            err.decode_contract_revert::<Erc20Errors>(),
+            Some(Erc20Errors::BlockedAccount(_))
+        )
+    };
+
+    // A blocked sender can't transfer but keeps its balance
+    send(token.block(me)).await;
+    assert!(token.is_blocked(me).call().await.unwrap());
+    assert!(blocked(
+        token.transfer(other, U256::one()).call().await.unwrap_err()
+    ));
+    assert_eq!(token.balance_of(me).call().await.unwrap(), U256::from(100));
+
+    // Nor can anyone send to a blocked recipient
+    send(token.unblock(me)).await;
+    send(token.block(other)).await;
+    assert!(blocked(
+        token.transfer(other, U256::one()).call().await.unwrap_err()
+    ));
+
+    send(token.unblock(other)).await;
+    send(token.transfer(other, U256::one())).await;
+    assert_eq!(token.balance_of(other).call().await.unwrap(), U256::one());
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn allowances_increase_and_decrease_to_zero() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let spender = Address::random();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    let token = Erc20::new(address, client.clone());
+    // Each change logs an Approval carrying the new total
+    let approved = |receipt: TransactionReceipt| {
+        let approval = parse_log::<erc_20::ApprovalFilter>(receipt.logs[0].clone()).unwrap();
+        assert_eq!((approval.owner, approval.spender), (me, spender));
+        approval.value
+    };
+
+    let receipt = send(token.increase_allowance(spender, U256::from(10))).await;
+    assert_eq!(approved(receipt), U256::from(10));
+    let receipt = send(token.increase_allowance(spender, U256::from(5))).await;
+    assert_eq!(approved(receipt), U256::from(15));
+    let receipt = send(token.decrease_allowance(spender, U256::from(15))).await;
+    assert_eq!(approved(receipt), U256::zero());
+    assert_eq!(
+        token.allowance(me, spender).call().await.unwrap(),
+        U256::zero()
+    );
+
+    let err = token
+        .decrease_allowance(spender, U256::one())
+        .call()
+        .await
+        .unwrap_err();
+    match err.decode_contract_revert::<Erc20Errors>() {
+        Some(Erc20Errors::InsufficientAllowance(e)) => {
+            assert_eq!((e.have, e.want), (U256::zero(), U256::one()))
+        }
+        other => panic!("expected InsufficientAllowance, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn zero_address_spenders_and_recipients_are_refused() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    let revert =
+        |err: ethers::contract::ContractError<Client>| err.decode_contract_revert::<Erc20Errors>();
+
+    let err = token
+        .approve(Address::zero(), U256::one())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::InvalidSpender(_))));
+    let err = token
+        .increase_allowance(Address::zero(), U256::one())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::InvalidSpender(_))));
+
+    let err = token
+        .transfer(Address::zero(), U256::one())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::InvalidReceiver(_))));
+    assert_eq!(token.balance_of(me).call().await.unwrap(), U256::from(100));
+
+    // Approving yourself is pointless but allowed
+    send(token.approve(me, U256::from(5))).await;
+    assert_eq!(token.allowance(me, me).call().await.unwrap(), U256::from(5));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn holder_count_tracks_nonzero_balances() {
+    let client = devnode().await;
+    let other = funded_account(&client).await;
+    let (me, them) = (client.signer().address(), other.signer().address());
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    assert_eq!(token.holder_count().call().await.unwrap(), U256::one());
+
+    // A first receipt adds a holder, a partial send or a self-transfer doesn't
+    send(token.transfer(them, U256::from(40))).await;
+    assert_eq!(token.holder_count().call().await.unwrap(), U256::from(2));
+    send(token.transfer(them, U256::from(10))).await;
+    send(token.transfer(me, U256::from(50))).await;
+    assert_eq!(token.holder_count().call().await.unwrap(), U256::from(2));
+
+    // Sending everything away, or burning it, removes one
+    send(token.transfer(them, U256::from(50))).await;
+    assert_eq!(token.holder_count().call().await.unwrap(), U256::one());
+    send(Erc20::new(address, other).burn(U256::from(100))).await;
+    assert_eq!(token.holder_count().call().await.unwrap(), U256::zero());
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn transfer_batch_pays_every_recipient() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    let recipients = vec![Address::random(), Address::random(), Address::random()];
+    let amounts = vec![U256::from(10), U256::from(20), U256::from(30)];
+
+    let receipt = send(token.transfer_batch(recipients.clone(), amounts.clone())).await;
+    let balances = token.balances_of(recipients.clone()).call().await.unwrap();
+    assert_eq!(balances, amounts);
+    assert_eq!(token.balance_of(me).call().await.unwrap(), U256::from(40));
+
+    // One Transfer per recipient, in order
+    let transfers: Vec<_> = receipt
+        .logs
+        .into_iter()
+        .map(|log| parse_log::<erc_20::TransferFilter>(log).unwrap())
+        .map(|transfer| (transfer.from, transfer.to, transfer.value))
+        .collect();
+    let expected: Vec<_> = recipients
+        .into_iter()
+        .zip(amounts)
+        .map(|(to, value)| (me, to, value))
+        .collect();
+    assert_eq!(transfers, expected);
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn transfer_batch_is_refused_whole_when_the_sum_is_short() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    let (a, b) = (Address::random(), Address::random());
+    let revert =
+        |err: ethers::contract::ContractError<Client>| err.decode_contract_revert::<Erc20Errors>();
+
+    // Each transfer alone is covered, the two together aren't
+    let call = token.transfer_batch(vec![a, b], vec![U256::from(60), U256::from(50)]);
+    let err = call.call().await.unwrap_err();
+    assert!(
+        matches!(revert(err), Some(Erc20Errors::InsufficientBalance(e)) if e.want == U256::from(110))
+    );
+    let err = call.send().await.unwrap_err();
+    assert!(matches!(
+        revert(err),
+        Some(Erc20Errors::InsufficientBalance(_))
+    ));
+    let balances = token.balances_of(vec![me, a, b]).call().await.unwrap();
+    assert_eq!(balances, [U256::from(100), U256::zero(), U256::zero()]);
+
+    let err = token
+        .transfer_batch(vec![a, b], vec![U256::MAX, U256::one()])
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::Overflow(_))));
+
+    // Only a strict batch refuses to pay anyone twice
+    let err = token
+        .transfer_batch_with_recipients_and_amounts(vec![a, a], vec![U256::one(); 2], true)
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::DuplicateRecipient(e)) if e.recipient == a));
+    send(token.transfer_batch_with_recipients_and_amounts(vec![a, a], vec![U256::one(); 2], false))
+        .await;
+    assert_eq!(token.balance_of(a).call().await.unwrap(), U256::from(2));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn max_allowance_is_never_spent() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    let spender = funded_account(&client).await;
+    let spender_address = spender.signer().address();
+    let as_spender = Erc20::new(address, spender);
+
+    send(token.approve(spender_address, U256::MAX)).await;
+    for _ in 0..2 {
+        send(as_spender.transfer_from(me, other, U256::from(10))).await;
+        assert_eq!(
+            token.allowance(me, spender_address).call().await.unwrap(),
+            U256::MAX
+        );
+    }
+    assert_eq!(
+        token.balance_of(other).call().await.unwrap(),
+        U256::from(20)
+    );
+
+    // A finite allowance is spent down as usual
+    send(token.approve(spender_address, U256::from(30))).await;
+    send(as_spender.transfer_from(me, other, U256::from(10))).await;
+    assert_eq!(
+        token.allowance(me, spender_address).call().await.unwrap(),
+        U256::from(20)
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn transfers_and_approvals_index_both_accounts() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    let token = Erc20::new(address, client.clone());
+    // The event's signature hash and its two indexed addresses
+    let topics = |receipt: &TransactionReceipt, event: &str, first: Address, second: Address| {
+        let log = &receipt.logs[0];
+        assert_eq!(log.address, address);
+        assert_eq!(
+            log.topics,
+            [
+                H256(keccak256(event)),
+                H256::from(first),
+                H256::from(second)
+            ]
+        );
+        U256::from_big_endian(&log.data)
+    };
+    let transfer = "Transfer(address,address,uint256)";
+
+    // Mints come from the zero address
+    let receipt = send(token.mint(me, U256::from(100))).await;
+    assert_eq!(
+        topics(&receipt, transfer, Address::zero(), me),
+        U256::from(100)
+    );
+    let minted = parse_log::<erc_20::TransferFilter>(receipt.logs[0].clone()).unwrap();
+    assert_eq!((minted.from, minted.to), (Address::zero(), me));
+
+    let receipt = send(token.transfer(other, U256::from(30))).await;
+    assert_eq!(topics(&receipt, transfer, me, other), U256::from(30));
+
+    let receipt = send(token.approve(other, U256::from(5))).await;
+    let approval = "Approval(address,address,uint256)";
+    assert_eq!(topics(&receipt, approval, me, other), U256::from(5));
+
+    // Burns go to the zero address
+    let receipt = send(token.burn(U256::from(20))).await;
+    assert_eq!(
+        topics(&receipt, transfer, me, Address::zero()),
+        U256::from(20)
+    );
+    let burned = parse_log::<erc_20::TransferFilter>(receipt.logs[0].clone()).unwrap();
+    assert_eq!((burned.from, burned.to), (me, Address::zero()));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn transfer_from_yourself_needs_no_allowance() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    assert_eq!(token.allowance(me, me).call().await.unwrap(), U256::zero());
+
+    // Just a transfer: only the Transfer is logged, and no allowance is touched
+    let receipt = assert_balance_delta(&token, me, -30, || {
+        send(token.transfer_from(me, other, U256::from(30)))
+    })
+    .await;
+    assert_eq!(receipt.logs.len(), 1);
+    assert!(parse_log::<erc_20::TransferFilter>(receipt.logs[0].clone()).is_ok());
+    assert_eq!(token.allowance(me, me).call().await.unwrap(), U256::zero());
+
+    // The balance still limits it
+    let err = token
+        .transfer_from(me, other, U256::from(71))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::InsufficientBalance(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn spending_an_allowance_handles_max_exact_and_short() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    let spender = funded_account(&client).await;
+    let spender_address = spender.signer().address();
+    let as_spender = Erc20::new(address, spender);
+
+    // MAX: nothing is spent, so only the Transfer is logged
+    send(token.approve(spender_address, U256::MAX)).await;
+    let receipt = send(as_spender.transfer_from(me, other, U256::from(10))).await;
+    assert_eq!(receipt.logs.len(), 1);
+    assert!(parse_log::<erc_20::TransferFilter>(receipt.logs[0].clone()).is_ok());
+
+    // Exact: the allowance is spent to zero, and the Approval logs what's left
+    send(token.approve(spender_address, U256::from(10))).await;
+    let receipt = send(as_spender.burn_from(me, U256::from(10))).await;
+    let approval: erc_20::ApprovalFilter = parse_log(receipt.logs[0].clone()).unwrap();
+    assert_eq!(
+        (approval.owner, approval.spender, approval.value),
+        (me, spender_address, U256::zero())
+    );
+    assert_eq!(
+        token.allowance(me, spender_address).call().await.unwrap(),
+        U256::zero()
+    );
+
+    // Short: both paths revert with what the spender had and wanted
+    send(token.approve(spender_address, U256::from(5))).await;
+    for err in [
+        as_spender
+            .transfer_from(me, other, U256::from(6))
+            .call()
+            .await
+            .unwrap_err(),
+        as_spender
+            .burn_from(me, U256::from(6))
+            .call()
+            .await
+            .unwrap_err(),
+    ] {
+        let Some(Erc20Errors::InsufficientAllowance(err)) =
+            err.decode_contract_revert::<Erc20Errors>()
+        else {
+            panic!("expected InsufficientAllowance");
+        };
+        assert_eq!((err.have, err.want), (U256::from(5), U256::from(6)));
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn fee_on_transfer_splits_exactly() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let (other, collector) = (Address::random(), Address::random());
+    let address = deploy(&client, "FEE_TX_DATA").await;
+    let fee_token = FeeErc20::new(address, client.clone());
+    let token = Erc20::new(address, client.clone());
+    send(token.init("Fee".into(), "FEE".into(), 18)).await;
+    send(token.mint(me, U256::from(1_000_000))).await;
+    send(fee_token.set_fee_recipient(collector)).await;
+    let balances = || async {
+        (
+            token.balance_of(other).call().await.unwrap().as_u64(),
+            token.balance_of(collector).call().await.unwrap().as_u64(),
+        )
+    };
+
+    // No fee by default
+    send(token.transfer(other, U256::from(1000))).await;
+    assert_eq!(balances().await, (1000, 0));
+
+    send(fee_token.set_fee_bps(U256::from(250))).await;
+    send(token.transfer(other, U256::from(1000))).await;
+    assert_eq!(balances().await, (1975, 25));
+
+    // 2.5% of 39 is 0.975, rounded down to no fee at all
+    send(token.transfer(other, U256::from(39))).await;
+    assert_eq!(balances().await, (2014, 25));
+    // and 2.5% of 10001 is 250.025
+    send(token.transfer(other, U256::from(10001))).await;
+    assert_eq!(balances().await, (11765, 275));
+
+    // transferFrom spends the whole value of the allowance
+    let spender = funded_account(&client).await;
+    let spender_address = spender.signer().address();
+    send(token.approve(spender_address, U256::from(1000))).await;
+    send(Erc20::new(address, spender).transfer_from(me, other, U256::from(1000))).await;
+    assert_eq!(balances().await, (12740, 300));
+    assert_eq!(
+        token.allowance(me, spender_address).call().await.unwrap(),
+        U256::zero()
+    );
+
+    let err = fee_token
+        .set_fee_bps(U256::from(10_001))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(err.decode_revert::<InvalidFee>().is_some());
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn every_transfer_method_pays_the_fee() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    // `other` accepts ERC-1363 transfers, so `transferAndCall` can pay it too
+    let other = deploy_evm(&client, &returns_selector(ON_TRANSFER_RECEIVED)).await;
+    let collector = Address::random();
+    let address = deploy(&client, "FEE_TX_DATA").await;
+    let fee_token = FeeErc20::new(address, client.clone());
+    let token = Erc20::new(address, client.clone());
+    send(token.init("Fee".into(), "FEE".into(), 18)).await;
+    send(token.mint(me, U256::from(1_000_000))).await;
+    send(fee_token.set_fee_bps(U256::from(100))).await;
+    let balances = || async {
+        let accounts = vec![other, collector];
+        let balances = token.balances_of(accounts).call().await.unwrap();
+        balances.iter().map(U256::as_u64).collect::<Vec<_>>()
+    };
+
+    // Until there is a recipient the 1% fee is burned, out of what `other` got
+    let receipt = send(token.transfer(other, U256::from(1000))).await;
+    let transfers: Vec<_> = receipt
+        .logs
+        .into_iter()
+        .filter_map(|log| parse_log::<erc_20::TransferFilter>(log).ok())
+        .map(|t| (t.from, t.to, t.value.as_u64()))
+        .collect();
+    assert_eq!(transfers, [(me, other, 990), (me, Address::zero(), 10)]);
+    assert_eq!(balances().await, [990, 0]);
+    assert_eq!(
+        token.total_supply().call().await.unwrap(),
+        U256::from(999_990)
+    );
+
+    // What the recipient itself receives pays no fee
+    send(fee_token.set_fee_recipient(collector)).await;
+    let recipients = vec![other, collector];
+    send(token.transfer_batch(recipients, vec![U256::from(1000); 2])).await;
+    assert_eq!(balances().await, [1980, 1010]);
+    send(token.transfer_and_call(other, U256::from(1000))).await;
+    assert_eq!(balances().await, [2970, 1020]);
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn the_fee_is_split_off_the_transfer() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let (other, collector) = (Address::random(), Address::random());
+    let address = deploy(&client, "FEE_TX_DATA").await;
+    let fee_token = FeeErc20::new(address, client.clone());
+    let token = Erc20::new(address, client.clone());
+    send(token.init("Fee".into(), "FEE".into(), 18)).await;
+    send(token.mint(me, U256::from(1_000_000))).await;
+    let transfers = |receipt: TransactionReceipt| -> Vec<_> {
+        receipt
+            .logs
+            .into_iter()
+            .filter_map(|log| parse_log::<erc_20::TransferFilter>(log).ok())
+            .map(|t| (t.from, t.to, t.value.as_u64()))
+            .collect()
+    };
+
+    let receipt = send(fee_token.set_fee_bps(U256::from(100))).await;
+    let updated = parse_log::<FeeUpdatedFilter>(receipt.logs[0].clone()).unwrap();
+    assert_eq!(updated.fee_bps, U256::from(100));
+    let receipt = send(fee_token.set_fee_recipient(collector)).await;
+    let updated = parse_log::<FeeRecipientUpdatedFilter>(receipt.logs[0].clone()).unwrap();
+    assert_eq!(updated.fee_recipient, collector);
+
+    // `from` pays both parts, and a blocked recipient still collects
+    send(token.block(collector)).await;
+    let receipt = send(token.transfer(other, U256::from(1000))).await;
+    assert_eq!(transfers(receipt), [(me, other, 990), (me, collector, 10)]);
+    assert_eq!(
+        token.balance_of(collector).call().await.unwrap(),
+        U256::from(10)
+    );
+    send(token.unblock(collector)).await;
+
+    // Receiving counts against no one's rate limit but the sender's
+    let holder = funded_account(&client).await;
+    let holder_address = holder.signer().address();
+    send(token.transfer(holder_address, U256::from(1000))).await;
+    send(token.set_rate_limit(U256::from(1000), U256::from(1000), U256::from(3600))).await;
+    send(Erc20::new(address, holder).transfer(me, U256::from(990))).await;
+    let receipt = send(token.transfer(holder_address, U256::from(500))).await;
+    assert_eq!(
+        transfers(receipt),
+        [(me, holder_address, 495), (me, collector, 5)]
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn burn_from_spends_allowance() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    let spender = funded_account(&client).await;
+    let spender_address = spender.signer().address();
+    let as_spender = Erc20::new(address, spender);
+
+    send(token.approve(spender_address, U256::from(30))).await;
+    send(as_spender.burn_from(me, U256::from(10))).await;
+    assert_eq!(
+        token.allowance(me, spender_address).call().await.unwrap(),
+        U256::from(20)
+    );
+    assert_eq!(token.balance_of(me).call().await.unwrap(), U256::from(90));
+    assert_eq!(token.total_supply().call().await.unwrap(), U256::from(90));
+
+    // Burning past the allowance reverts
+    let err = as_spender
+        .burn_from(me, U256::from(21))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::InsufficientAllowance(_))
+    ));
+
+    // A MAX allowance is never spent down, but the balance still limits the burn
+    send(token.approve(spender_address, U256::MAX)).await;
+    send(as_spender.burn_from(me, U256::from(40))).await;
+    assert_eq!(
+        token.allowance(me, spender_address).call().await.unwrap(),
+        U256::MAX
+    );
+    let err = as_spender
+        .burn_from(me, U256::from(51))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::InsufficientBalance(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn balances_of_reads_many_accounts() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let (funded, empty) = (Address::random(), Address::random());
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    send(token.transfer(funded, U256::from(30))).await;
+
+    let balances = token
+        .balances_of(vec![funded, empty, me, funded])
+        .call()
+        .await
+        .unwrap();
+    let expected = [30, 0, 70, 30].map(U256::from);
+    assert_eq!(balances, expected);
+    assert!(token.balances_of(vec![]).call().await.unwrap().is_empty());
+
+    let err = token
+        .balances_of(vec![empty; 501])
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::TooManyAccounts(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn token_uri_is_owner_set_and_bounded() {
+    let client = devnode().await;
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    let token = Erc20::new(address, client.clone());
+    assert_eq!(token.token_uri().call().await.unwrap(), "");
+
+    let uri = "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+    let receipt = send(token.set_token_uri(uri.into())).await;
+    let event: erc_20::TokenUriUpdatedFilter = parse_log(receipt.logs[0].clone()).unwrap();
+    assert_eq!(event.uri, uri);
+    assert_eq!(token.token_uri().call().await.unwrap(), uri);
+
+    let err = token
+        .set_token_uri("x".repeat(257))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::TokenUriTooLong(_))
+    ));
+
+    let other = Erc20::new(address, funded_account(&client).await);
+    let err = other
+        .set_token_uri("https://example.com/token.json".into())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::UnauthorizedAccount(_))
+    ));
+    assert_eq!(token.token_uri().call().await.unwrap(), uri);
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn name_and_symbol_can_be_rebranded_by_the_owner() {
+    let client = devnode().await;
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    let token = Erc20::new(address, client.clone());
+
+    let receipt = send(token.set_name("Rebranded".into())).await;
+    let event: erc_20::MetadataUpdatedFilter = parse_log(receipt.logs[0].clone()).unwrap();
+    assert_eq!(
+        (event.name.as_str(), event.symbol.as_str()),
+        ("Rebranded", "CAP")
+    );
+    let receipt = send(token.set_symbol("RBD".into())).await;
+    let event: erc_20::MetadataUpdatedFilter = parse_log(receipt.logs[0].clone()).unwrap();
+    assert_eq!(
+        (event.name.as_str(), event.symbol.as_str()),
+        ("Rebranded", "RBD")
+    );
+    assert_eq!(token.name().call().await.unwrap(), "Rebranded");
+    assert_eq!(token.symbol().call().await.unwrap(), "RBD");
+    assert_eq!(token.decimals().call().await.unwrap(), 18);
+
+    let err = token.set_symbol("X".repeat(17)).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::MetadataTooLong(_))
+    ));
+
+    let other = Erc20::new(address, funded_account(&client).await);
+    let err = other.set_name("Hijacked".into()).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::UnauthorizedAccount(_))
+    ));
+    assert_eq!(token.name().call().await.unwrap(), "Rebranded");
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn vault_exchange_rate_across_deposits() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let (asset, vault) = deploy_vault(&client, U256::from(10_000)).await;
+    let shares = Erc20::new(vault.address(), client.clone());
+    assert_eq!(shares.decimals().call().await.unwrap(), 21);
+    // The rate counts 1000 virtual shares and 1 virtual asset
+    let expected_shares = |assets: u64, supply: u64, total: u64| {
+        U256::from(assets) * U256::from(supply + 1000) / U256::from(total + 1)
+    };
+
+    send(vault.deposit(U256::from(1000), me)).await;
+    assert_eq!(
+        shares.balance_of(me).call().await.unwrap(),
+        expected_shares(1000, 0, 0)
+    );
+
+    // A donation raises the value of every share
+    send(asset.transfer(vault.address(), U256::from(500))).await;
+    assert_eq!(vault.total_assets().call().await.unwrap(), U256::from(1500));
+    let supply = shares.total_supply().call().await.unwrap().as_u64();
+    let preview = vault.preview_deposit(U256::from(300)).call().await.unwrap();
+    assert_eq!(preview, expected_shares(300, supply, 1500));
+    let other = Address::random();
+    send(vault.deposit(U256::from(300), other)).await;
+    assert_eq!(shares.balance_of(other).call().await.unwrap(), preview);
+
+    // Minting takes the assets rounded up, depositing gives shares rounded down
+    let minted = U256::from(12_345);
+    let cost = vault.preview_mint(minted).call().await.unwrap();
+    assert!(vault.convert_to_shares(cost).call().await.unwrap() >= minted);
+    assert!(vault.convert_to_shares(cost - 1).call().await.unwrap() < minted);
+    let cost = I256::try_from(cost).unwrap();
+    assert_balance_delta(&asset, me, -cost, || send(vault.mint(minted, me))).await;
+
+    // Withdrawing burns the shares rounded up, redeeming pays assets rounded down
+    let burned = vault
+        .preview_withdraw(U256::from(100))
+        .call()
+        .await
+        .unwrap();
+    assert!(vault.convert_to_assets(burned).call().await.unwrap() >= U256::from(100));
+    let held = shares.balance_of(me).call().await.unwrap();
+    send(vault.withdraw(U256::from(100), me, me)).await;
+    assert_eq!(held - shares.balance_of(me).call().await.unwrap(), burned);
+
+    let held = shares.balance_of(me).call().await.unwrap();
+    let paid = vault.preview_redeem(held).call().await.unwrap();
+    let paid = I256::try_from(paid).unwrap();
+    assert_balance_delta(&asset, me, paid, || send(vault.redeem(held, me, me))).await;
+    assert_eq!(vault.max_redeem(me).call().await.unwrap(), U256::zero());
+    // The other depositor's shares are still fully backed
+    let other_shares = shares.balance_of(other).call().await.unwrap();
+    let other_assets = vault.convert_to_assets(other_shares).call().await.unwrap();
+    assert!(other_assets <= vault.total_assets().call().await.unwrap());
+    assert!(other_assets >= U256::from(299));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn vault_resists_first_deposit_inflation() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let wad = parse_ether("1").unwrap();
+    let (asset, vault) = deploy_vault(&client, wad * 3).await;
+    let victim = funded_account(&client).await;
+    let victim_address = victim.signer().address();
+    send(asset.transfer(victim_address, wad)).await;
+    send(Erc20::new(asset.address(), victim.clone()).approve(vault.address(), U256::MAX)).await;
+
+    // The attacker deposits 1 wei, then donates to inflate the share price
+    send(vault.deposit(U256::one(), me)).await;
+    send(asset.transfer(vault.address(), wad)).await;
+
+    let as_victim = Erc4626::new(vault.address(), victim);
+    send(as_victim.deposit(wad, victim_address)).await;
+    let shares = Erc20::new(vault.address(), client.clone());
+    let victim_shares = shares.balance_of(victim_address).call().await.unwrap();
+    assert!(victim_shares > U256::zero());
+    // The donation mostly goes to the virtual shares, not the attacker
+    let redeemable = vault.max_withdraw(victim_address).call().await.unwrap();
+    assert!(redeemable >= wad * 99 / 100);
+    assert!(vault.max_withdraw(me).call().await.unwrap() < wad);
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn vault_withdraw_for_another_spends_share_allowance() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let (_, vault) = deploy_vault(&client, U256::from(1000)).await;
+    send(vault.deposit(U256::from(1000), me)).await;
+    let spender = funded_account(&client).await;
+    let spender_address = spender.signer().address();
+    let as_spender = Erc4626::new(vault.address(), spender);
+
+    let err = as_spender
+        .redeem(U256::from(1000), spender_address, me)
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::InsufficientAllowance(_))
+    ));
+
+    let shares = Erc20::new(vault.address(), client.clone());
+    send(shares.approve(spender_address, U256::from(1000))).await;
+    send(as_spender.redeem(U256::from(1000), spender_address, me)).await;
+    assert_eq!(
+        shares.allowance(me, spender_address).call().await.unwrap(),
+        U256::zero()
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn vault_refuses_to_rescue_its_asset() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let (asset, vault) = deploy_vault(&client, U256::from(1000)).await;
+    send(vault.deposit(U256::from(1000), me)).await;
+
+    let err = vault
+        .rescue_tokens(asset.address(), me, U256::from(1000))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc4626Errors>(),
+        Some(Erc4626Errors::CannotRescueAsset(_))
+    ));
+    assert_eq!(vault.total_assets().call().await.unwrap(), U256::from(1000));
+
+    // Other tokens sent to the vault by mistake can still be rescued
+    let stray = deploy_token(&client, 18, U256::from(10)).await;
+    send(CappedErc20::new(stray, client.clone()).mint(vault.address(), U256::from(10))).await;
+    send(vault.rescue_tokens(stray, me, U256::from(10))).await;
+    let stray = Erc20::new(stray, client.clone());
+    assert_eq!(stray.balance_of(me).call().await.unwrap(), U256::from(10));
+}
+
+// The hook and Transfer events of a receipt in order: the balances of `from`
+// and `to` each hook saw, and the value of the Transfer
+fn hook_trace(receipt: &TransactionReceipt) -> Vec<(&'static str, u64, u64)> {
+    receipt
+        .logs
+        .iter()
+        .filter_map(|log| {
+            if let Ok(e) = parse_log::<BeforeTokenTransferFilter>(log.clone()) {
+                return Some(("before", e.from_balance.as_u64(), e.to_balance.as_u64()));
+            }
+            if let Ok(e) = parse_log::<AfterTokenTransferFilter>(log.clone()) {
+                return Some(("after", e.from_balance.as_u64(), e.to_balance.as_u64()));
+            }
+            let transfer = parse_log::<erc_20::TransferFilter>(log.clone());
+            transfer.ok().map(|e| ("transfer", e.value.as_u64(), 0))
+        })
+        .collect()
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn transfer_hooks_run_around_balance_updates() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let token = Erc20::new(deploy(&client, "HOOKS_TX_DATA").await, client.clone());
+    send(token.init("Hooked".into(), "HOOK".into(), 18)).await;
+
+    // The zero address side of a mint or burn always has a zero balance
+    let receipt = send(token.mint(me, U256::from(100))).await;
+    assert_eq!(
+        hook_trace(&receipt),
+        [("before", 0, 0), ("transfer", 100, 0), ("after", 0, 100)]
+    );
+
+    let receipt = send(token.transfer(other, U256::from(30))).await;
+    assert_eq!(
+        hook_trace(&receipt),
+        [("before", 100, 0), ("transfer", 30, 0), ("after", 70, 30)]
+    );
+
+    send(token.approve(me, U256::from(5))).await;
+    let receipt = send(token.transfer_from(me, other, U256::from(5))).await;
+    assert_eq!(
+        hook_trace(&receipt),
+        [("before", 70, 30), ("transfer", 5, 0), ("after", 65, 35)]
+    );
+
+    let receipt = send(token.burn(U256::from(10))).await;
+    assert_eq!(
+        hook_trace(&receipt),
+        [("before", 65, 0), ("transfer", 10, 0), ("after", 55, 0)]
+    );
+}
+
+// Sleeps until a new `window_seconds` window of block time has begun
+async fn next_window(client: &Arc<Client>, window_seconds: u64) {
+    let latest = client
+        .get_block(BlockNumber::Latest)
+        .await
+        .unwrap()
+        .unwrap();
+    let elapsed = latest.timestamp.as_u64() % window_seconds;
+    tokio::time::sleep(Duration::from_secs(window_seconds - elapsed + 1)).await;
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn rate_limit_caps_transfers_per_tx_and_window() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let address = deploy_token(&client, 18, U256::from(1000)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(1000))).await;
+    let token = Erc20::new(address, client.clone());
+    let window_seconds = 20;
+    send(token.set_rate_limit(U256::from(100), U256::from(250), window_seconds.into())).await;
+
+    let err = token
+        .transfer(other, U256::from(101))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::ExceededTxLimit(_))
+    ));
+
+    // Start at the beginning of a window so all transfers below share it
+    next_window(&client, window_seconds).await;
+    for value in [100, 100, 50] {
+        send(token.transfer(other, U256::from(value))).await;
+    }
+    assert_eq!(
+        token.window_total(me).call().await.unwrap(),
+        U256::from(250)
+    );
+    let err = token.transfer(other, U256::one()).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::ExceededWindowLimit(_))
+    ));
+    // Others have caps of their own, and mints don't count against any
+    send(CappedErc20::new(address, client.clone()).mint(other, U256::from(100))).await;
+
+    next_window(&client, window_seconds).await;
+    send(token.transfer(other, U256::from(100))).await;
+    assert_eq!(
+        token.balance_of(other).call().await.unwrap(),
+        U256::from(450)
+    );
+
+    // Zero caps lift the limits
+    send(token.set_rate_limit(U256::zero(), U256::zero(), U256::zero())).await;
+    send(token.transfer(other, U256::from(300))).await;
+}
+
+// Sends `to` ETH without calling it, as a selfdestruct does, so WETH holds it
+// without a deposit
+async fn force_send_eth(client: &Arc<Client>, to: Address, value: U256) {
+    // Init code that selfdestructs to `to`: PUSH20 <to> SELFDESTRUCT
+    let mut init_code = vec![0x73];
+    init_code.extend(to.as_bytes());
+    init_code.push(0xff);
+    let force_send = TransactionRequest::new().data(init_code).value(value);
+    let receipt = client
+        .send_transaction(force_send, None)
+        .await
+        .unwrap()
+        .await;
+    receipt.unwrap().expect("force send was dropped");
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn weth_stays_fully_collateralized() {
+    let client = devnode().await;
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+    let backing = || async {
+        let balance = client.get_balance(weth.address(), None).await.unwrap();
+        let supply = weth.total_supply().call().await.unwrap();
+        assert!(weth.is_fully_collateralized().call().await.unwrap());
+        (balance, supply)
+    };
+    assert_eq!(backing().await, (U256::zero(), U256::zero()));
+
+    let wad = parse_ether("1").unwrap();
+    send(weth.deposit().value(wad)).await;
+    assert_eq!(backing().await, (wad, wad));
+    send(weth.withdraw(wad / 4)).await;
+    assert_eq!(backing().await, (wad * 3 / 4, wad * 3 / 4));
+
+    force_send_eth(&client, weth.address(), wad).await;
+    assert_eq!(backing().await, (wad * 7 / 4, wad * 3 / 4));
+
+    send(weth.withdraw_all()).await;
+    assert_eq!(backing().await, (wad, U256::zero()));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn sweep_takes_only_eth_beyond_the_supply() {
+    let client = devnode().await;
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+    let (recipient, wad) = (Address::random(), parse_ether("1").unwrap());
+    let no_excess = |err: ethers::contract::ContractError<Client>| {
+        matches!(
+            err.decode_contract_revert::<WethErrors>(),
+            Some(WethErrors::NoExcessEth(_))
+        )
+    };
+
+    // Fully backed deposits leave nothing to sweep
+    send(weth.deposit().value(wad)).await;
+    let reserves = weth.reserves().call().await.unwrap();
+    assert_eq!(reserves, (wad, wad, U256::zero()));
+    assert!(no_excess(
+        weth.sweep_excess_eth(recipient).call().await.unwrap_err()
+    ));
+
+    force_send_eth(&client, weth.address(), wad / 2).await;
+    let reserves = weth.reserves().call().await.unwrap();
+    assert_eq!(reserves, (wad + wad / 2, wad, wad / 2));
+    let swept = weth.sweep_excess_eth(recipient).call().await.unwrap();
+    assert_eq!(swept, wad / 2);
+    send(weth.sweep_excess_eth(recipient)).await;
+    assert_eq!(client.get_balance(recipient, None).await.unwrap(), wad / 2);
+
+    // The deposit's backing is untouched and still withdrawable
+    assert_eq!(client.get_balance(weth.address(), None).await.unwrap(), wad);
+    assert!(weth.is_fully_collateralized().call().await.unwrap());
+    assert!(no_excess(
+        weth.sweep_excess_eth(recipient).call().await.unwrap_err()
+    ));
+    send(weth.withdraw(wad)).await;
+
+    // Only the owner sweeps
+    force_send_eth(&client, weth.address(), wad / 2).await;
+    let other = Weth::new(weth.address(), funded_account(&client).await);
+    let err = other.sweep_excess_eth(recipient).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WethErrors>(),
+        Some(WethErrors::UnauthorizedAccount(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn rescue_tokens_returns_foreign_tokens_only() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let rescuer = Address::random();
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let stuck = Erc20::new(address, client.clone());
+
+    // Sent straight to Weth instead of through any of its methods
+    send(stuck.transfer(weth.address(), U256::from(40))).await;
+    send(weth.rescue_tokens(address, rescuer, U256::from(30))).await;
+    assert_eq!(
+        stuck.balance_of(rescuer).call().await.unwrap(),
+        U256::from(30)
+    );
+    assert_eq!(
+        stuck.balance_of(weth.address()).call().await.unwrap(),
+        U256::from(10)
+    );
+
+    // The token's own revert, here for an insufficient balance, fails the rescue
+    let err = weth
+        .rescue_tokens(address, rescuer, U256::from(11))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WethErrors>(),
+        Some(WethErrors::RescueFailed(_))
+    ));
+
+    let wad = parse_ether("1").unwrap();
+    send(weth.deposit().value(wad)).await;
+    send(weth.transfer(weth.address(), wad)).await;
+    let err = weth
+        .rescue_tokens(weth.address(), rescuer, wad)
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WethErrors>(),
+        Some(WethErrors::CannotRescueOwnToken(_))
+    ));
+
+    let err = weth
+        .rescue_tokens(Address::random(), rescuer, U256::one())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WethErrors>(),
+        Some(WethErrors::RescueFailed(_))
+    ));
+
+    let stranger = Weth::new(weth.address(), funded_account(&client).await);
+    let err = stranger
+        .rescue_tokens(address, rescuer, U256::from(10))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WethErrors>(),
+        Some(WethErrors::UnauthorizedAccount(_))
+    ));
+}
+
+// Deploys an EVM contract whose code is `runtime`, answering every call the same
+async fn deploy_evm(client: &Arc<Client>, runtime: &[u8]) -> Address {
+    deploy_evm_with_setup(client, &[], runtime).await
+}
+
+// Like `deploy_evm`, but first runs `setup` in the deployment transaction
+async fn deploy_evm_with_setup(client: &Arc<Client>, setup: &[u8], runtime: &[u8]) -> Address {
+    // Copies the runtime that follows this 11-byte header into memory and returns it
+    let header = [
+        0x60,
+        runtime.len() as u8,
+        0x80,
+        0x60,
+        (setup.len() + 11) as u8,
+        0x60,
+        0x00,
+        0x39,
+        0x60,
+        0x00,
+        0xf3,
+    ];
+    let deployment = TransactionRequest::new().data([setup, &header[..], runtime].concat());
+    let receipt = client
+        .send_transaction(deployment, None)
+        .await
+        .unwrap()
+        .await;
+    let receipt = receipt.unwrap().expect("deployment was dropped");
+    receipt.contract_address.expect("no contract was created")
+}
+
+// STOP: succeeds returning nothing, like tokens predating the ERC-20 bool
+const RETURNS_NOTHING: [u8; 1] = [0x00];
+// MSTORE a zero word and RETURN it: an explicit `false`
+const RETURNS_FALSE: [u8; 10] = [0x60, 0x00, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+
+// PUSH1 0 DUP1 REVERT: rejects every call
+const REVERTS: [u8; 4] = [0x60, 0x00, 0x80, 0xfd];
+
+// The selector of the ERC-1363 hook on recipients of `transferAndCall`
+const ON_TRANSFER_RECEIVED: [u8; 4] = selector("onTransferReceived(address,address,uint256,bytes)");
+
+// Returns `selector` as a left-aligned word: a hook implementation that accepts
+fn returns_selector(selector: [u8; 4]) -> Vec<u8> {
+    let push = [&[0x63][..], &selector, &[0x60, 0xe0, 0x1b]].concat();
+    [push, vec![0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]].concat()
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn transfer_and_call_reverts_with_the_receiver() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(me, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+
+    let receiver = deploy_evm(&client, &returns_selector(ON_TRANSFER_RECEIVED)).await;
+    let call =
+        token.transfer_and_call_with_to_and_value(receiver, U256::from(10), vec![1, 2].into());
+    let receipt = send(call).await;
+    let logs: Vec<_> = receipt
+        .logs
+        .into_iter()
+        .filter_map(|log| parse_log::<erc_20::TransferAndCallFilter>(log).ok())
+        .collect();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].data.to_vec(), [1, 2]);
+    send(token.transfer_and_call(receiver, U256::from(5))).await;
+    assert_eq!(
+        token.balance_of(receiver).call().await.unwrap(),
+        U256::from(15)
+    );
+
+    // Reverting, returning nothing or the wrong value and having no code all
+    // refuse, and the transfer with them
+    let refusing = [
+        deploy_evm(&client, &REVERTS).await,
+        deploy_evm(&client, &RETURNS_NOTHING).await,
+        deploy_evm(&client, &RETURNS_FALSE).await,
+        Address::random(),
+    ];
+    for receiver in refusing {
+        let err = token
+            .transfer_and_call(receiver, U256::from(10))
+            .call()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.decode_contract_revert::<Erc20Errors>(),
+            Some(Erc20Errors::CallbackFailed(_))
+        ));
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn transfer_from_and_call_spends_the_allowance() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let owner = funded_account(&client).await;
+    let holder = owner.signer().address();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(holder, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    let receiver = deploy_evm(&client, &returns_selector(ON_TRANSFER_RECEIVED)).await;
+
+    let call = token.transfer_from_and_call(holder, receiver, U256::from(10));
+    let err = call.call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::InsufficientAllowance(_))
+    ));
+
+    let as_owner = Erc20::new(address, owner.clone());
+    send(as_owner.approve(me, U256::from(30))).await;
+    send(token.transfer_from_and_call_with_from_and_to_and_data(
+        holder,
+        receiver,
+        U256::from(10),
+        vec![7].into(),
+    ))
+    .await;
+    send(token.transfer_from_and_call(holder, receiver, U256::from(10))).await;
+    assert_eq!(
+        token.balance_of(receiver).call().await.unwrap(),
+        U256::from(20)
+    );
+    assert_eq!(
+        token.allowance(holder, me).call().await.unwrap(),
+        U256::from(10)
+    );
+
+    // The receiver still has to accept
+    let refusing = deploy_evm(&client, &RETURNS_FALSE).await;
+    let err = token
+        .transfer_from_and_call(holder, refusing, U256::from(10))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::CallbackFailed(_))
+    ));
+}
+
+// A receiver that, whenever it is called, calls weth.withdraw(amount) back and
+// stores the outcome: slot 0 holds 1 if that call reverted and 2 if it went
+// through, slot 1 the length of what it returned
+fn rewithdraws(weth: Address, amount: u8) -> Vec<u8> {
+    [
+        &[0x63][..],
+        &keccak256("withdraw(uint256)")[..4],
+        &[
+            0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52, 0x60, amount, 0x60, 0x04, 0x52,
+        ],
+        &[
+            0x60, 0x00, 0x60, 0x00, 0x60, 0x24, 0x60, 0x00, 0x60, 0x00, 0x73,
+        ],
+        weth.as_bytes(),
+        &[0x5a, 0xf1, 0x60, 0x01, 0x01, 0x60, 0x00, 0x55],
+        &[0x3d, 0x60, 0x01, 0x55, 0x00],
+    ]
+    .concat()
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn withdraw_cannot_be_reentered_from_the_eth_callback() {
+    let client = devnode().await;
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+    let receiver = deploy_evm(&client, &rewithdraws(weth.address(), 10)).await;
+    send(weth.deposit().value(20)).await;
+    // The receiver holds WETH of its own, so only reentry can fail its withdraw
+    send(weth.transfer(receiver, U256::from(10))).await;
+
+    send(weth.withdraw_to(receiver, U256::from(10))).await;
+    let slot = |index: u64| client.get_storage_at(receiver, H256::from_low_u64_be(index), None);
+    assert_eq!(slot(0).await.unwrap(), H256::from_low_u64_be(1));
+    // ReentrantCall would return its selector: the SDK rejected the call
+    // before `withdraw`, and the guard inside it, ever ran
+    assert_eq!(slot(1).await.unwrap(), H256::zero());
+    assert_eq!(
+        weth.balance_of(receiver).call().await.unwrap(),
+        U256::from(10)
+    );
+    assert_eq!(
+        client.get_balance(receiver, None).await.unwrap(),
+        U256::from(10)
+    );
+}
+
+// An ERC-3156 borrower accepting every loan without calling the lender back,
+// which the SDK would revert: it returns keccak256("ERC3156FlashBorrower.onFlashLoan")
+fn flash_borrower() -> Vec<u8> {
+    [
+        &[0x7f][..],
+        &keccak256("ERC3156FlashBorrower.onFlashLoan"),
+        &[0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3],
+    ]
+    .concat()
+}
+
+// Deployment setup calling token.approve(token, type(uint256).max), so the
+// contract can repay flash loans. The result is checked by the caller.
+fn approves_itself(token: Address) -> Vec<u8> {
+    [
+        &[0x63][..],
+        &keccak256("approve(address,uint256)")[..4],
+        &[0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52, 0x73],
+        token.as_bytes(),
+        &[0x60, 0x04, 0x52, 0x60, 0x00, 0x19, 0x60, 0x24, 0x52],
+        &[
+            0x60, 0x00, 0x60, 0x00, 0x60, 0x44, 0x60, 0x00, 0x60, 0x00, 0x73,
+        ],
+        token.as_bytes(),
+        &[0x5a, 0xf1, 0x50],
+    ]
+    .concat()
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn flash_loans_are_repaid_with_the_fee_or_revert() {
+    let client = devnode().await;
+    let address = deploy_token(&client, 18, U256::from(10_000)).await;
+    let token = Erc20::new(address, client.clone());
+    let revert =
+        |err: ethers::contract::ContractError<Client>| err.decode_contract_revert::<Erc20Errors>();
+    let fee_recipient = Address::random();
+    send(token.set_flash_fee(U256::from(100), fee_recipient)).await;
+    let amount = U256::from(1_000);
+    assert_eq!(
+        token.flash_fee(address, amount).call().await.unwrap(),
+        U256::from(10)
+    );
+
+    // The borrower approved the token when it was deployed, and pays the 1%
+    // fee out of what it held before
+    let borrower =
+        deploy_evm_with_setup(&client, &approves_itself(address), &flash_borrower()).await;
+    assert_eq!(
+        token.allowance(borrower, address).call().await.unwrap(),
+        U256::MAX
+    );
+    send(CappedErc20::new(address, client.clone()).mint(borrower, U256::from(10))).await;
+    let receipt = send(token.flash_loan(borrower, address, amount, vec![].into())).await;
+    let transfers: Vec<erc_20::TransferFilter> = receipt
+        .logs
+        .into_iter()
+        .filter_map(|log| parse_log(log).ok())
+        .collect();
+    assert_eq!(transfers.len(), 3, "mint, burn and fee: {transfers:?}");
+    let balances = token
+        .balances_of(vec![borrower, fee_recipient])
+        .call()
+        .await
+        .unwrap();
+    assert_eq!(balances, [U256::zero(), U256::from(10)]);
+    assert_eq!(token.total_supply().call().await.unwrap(), U256::from(10));
+
+    // Now it can't cover the fee, and another borrower approves nothing
+    let err = token
+        .flash_loan(borrower, address, amount, vec![].into())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        revert(err),
+        Some(Erc20Errors::InsufficientBalance(_))
+    ));
+    let keeper = deploy_evm(&client, &flash_borrower()).await;
+    let err = token
+        .flash_loan(keeper, address, amount, vec![].into())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        revert(err),
+        Some(Erc20Errors::InsufficientAllowance(_))
+    ));
+
+    let refusing = deploy_evm(&client, &RETURNS_NOTHING).await;
+    let err = token
+        .flash_loan(refusing, address, amount, vec![].into())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::CallbackFailed(_))));
+
+    let other = Address::random();
+    assert_eq!(
+        token.max_flash_loan(other).call().await.unwrap(),
+        U256::zero()
+    );
+    let err = token
+        .flash_loan(borrower, other, amount, vec![].into())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        revert(err),
+        Some(Erc20Errors::FlashLoanTooLarge(_))
+    ));
+
+    // Loans are minted, so they must fit under the cap of 10000
+    let room = token.max_flash_loan(address).call().await.unwrap();
+    assert_eq!(room, U256::from(9_990));
+    send(CappedErc20::new(address, client.clone()).mint(borrower, U256::from(100))).await;
+    let err = token
+        .flash_loan(borrower, address, U256::from(9_891), vec![].into())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        revert(err),
+        Some(Erc20Errors::FlashLoanTooLarge(e)) if e.max == U256::from(9_890)
+    ));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn backed_tokens_offer_no_flash_loans() {
+    let client = devnode().await;
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+    let borrower =
+        deploy_evm_with_setup(&client, &approves_itself(weth.address()), &flash_borrower()).await;
+    assert_eq!(
+        weth.max_flash_loan(weth.address()).call().await.unwrap(),
+        U256::zero()
+    );
+    let err = weth
+        .flash_loan(borrower, weth.address(), U256::from(1), vec![].into())
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WethErrors>(),
+        Some(WethErrors::FlashLoanTooLarge(_))
+    ));
+
+    // The wrapper's and the vault's tokens are backed by their underlying
+    let (_, wrapper) = deploy_wrapper(&client, U256::from(100)).await;
+    let (_, vault) = deploy_vault(&client, U256::from(100)).await;
+    for address in [wrapper.address(), vault.address()] {
+        let token = Erc20::new(address, client.clone());
+        assert_eq!(
+            token.max_flash_loan(address).call().await.unwrap(),
+            U256::zero()
+        );
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn approve_and_call_needs_the_spender_to_accept() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let token = Erc20::new(
+        deploy_token(&client, 18, U256::from(100)).await,
+        client.clone(),
+    );
+    let erc1363_id = [0xb0, 0x20, 0x2a, 0x11];
+    assert!(token.supports_interface(erc1363_id).call().await.unwrap());
+
+    let selector = keccak256("onApprovalReceived(address,uint256,bytes)");
+    let accepting = deploy_evm(
+        &client,
+        &returns_selector(selector[..4].try_into().unwrap()),
+    )
+    .await;
+    send(token.approve_and_call_with_spender_and_value(accepting, U256::from(7), vec![1].into()))
+        .await;
+    assert_eq!(
+        token.allowance(me, accepting).call().await.unwrap(),
+        U256::from(7)
+    );
+
+    // Reverting, returning the wrong value and having no code all refuse
+    let refusing = [
+        deploy_evm(&client, &REVERTS).await,
+        deploy_evm(&client, &RETURNS_FALSE).await,
+        Address::random(),
+    ];
+    for spender in refusing {
+        let err = token
+            .approve_and_call(spender, U256::from(7))
+            .call()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.decode_contract_revert::<Erc20Errors>(),
+            Some(Erc20Errors::CallbackFailed(_))
+        ));
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn rescue_tolerates_tokens_returning_nothing_but_not_false() {
+    let client = devnode().await;
+    let rescuer = Address::random();
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+
+    let no_return = deploy_evm(&client, &RETURNS_NOTHING).await;
+    let receipt = send(weth.rescue_tokens(no_return, rescuer, U256::from(5))).await;
+    let rescued = receipt
+        .logs
+        .into_iter()
+        .find_map(|log| parse_log::<erc_20::TokensRescuedFilter>(log).ok())
+        .expect("no TokensRescued event");
+    assert_eq!(rescued.token, no_return);
+
+    let returns_false = deploy_evm(&client, &RETURNS_FALSE).await;
+    let err = weth
+        .rescue_tokens(returns_false, rescuer, U256::from(5))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WethErrors>(),
+        Some(WethErrors::RescueFailed(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn pausing_freezes_transfers_until_unpaused() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let address = deploy_token(&client, 18, U256::from(1_000)).await;
+    let token = Erc20::new(address, client.clone());
+    send(token.mint(me, U256::from(100))).await;
+    let revert =
+        |err: ethers::contract::ContractError<Client>| err.decode_contract_revert::<Erc20Errors>();
+
+    let receipt = send(token.pause()).await;
+    let paused = parse_log::<erc_20::PausedFilter>(receipt.logs[0].clone()).unwrap();
+    assert_eq!(paused.account, me);
+    assert!(token.paused().call().await.unwrap());
+    let err = token
+        .transfer(other, U256::from(10))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::EnforcedPause(_))));
+    let err = token.mint(me, U256::from(10)).call().await.unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::EnforcedPause(_))));
+    // Reads still work while paused
+    assert_eq!(token.balance_of(me).call().await.unwrap(), U256::from(100));
+
+    let receipt = send(token.unpause()).await;
+    assert!(parse_log::<erc_20::UnpausedFilter>(receipt.logs[0].clone()).is_ok());
+    assert!(!token.paused().call().await.unwrap());
+    send(token.transfer(other, U256::from(10))).await;
+    assert_eq!(
+        token.balance_of(other).call().await.unwrap(),
+        U256::from(10)
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn snapshots_ignore_later_transfers() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let other = Address::random();
+    let address = deploy_token(&client, 18, U256::from(1_000)).await;
+    let token = Erc20::new(address, client.clone());
+    send(token.mint(me, U256::from(100))).await;
+
+    let receipt = send(token.snapshot()).await;
+    let id = parse_log::<erc_20::SnapshotFilter>(receipt.logs[0].clone())
+        .unwrap()
+        .id;
+    send(token.transfer(other, U256::from(30))).await;
+    send(token.mint(other, U256::from(50))).await;
+    send(token.burn(U256::from(20))).await;
+
+    assert_eq!(token.balance_of(me).call().await.unwrap(), U256::from(50));
+    assert_eq!(
+        token.balance_of(other).call().await.unwrap(),
+        U256::from(80)
+    );
+    assert_eq!(token.total_supply().call().await.unwrap(), U256::from(130));
+    assert_eq!(
+        token.balance_of_at(me, id).call().await.unwrap(),
+        U256::from(100)
+    );
+    assert_eq!(
+        token.balance_of_at(other, id).call().await.unwrap(),
+        U256::zero()
+    );
+    assert_eq!(
+        token.total_supply_at(id).call().await.unwrap(),
+        U256::from(100)
+    );
+
+    // Ids not yet taken can't be read
+    let err = token.total_supply_at(id + 1).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::InvalidSnapshotId(_))
+    ));
+}
+
+// The hash of an EIP-712 struct, given its type and its fields encoded in order
+fn struct_hash(type_signature: &str, fields: &[Token]) -> [u8; 32] {
+    let type_hash = Token::FixedBytes(keccak256(type_signature).to_vec());
+    keccak256(encode(&[&[type_hash], fields].concat()))
+}
+
+// The EIP-712 hash of an EIP-2612 permit
+fn permit_hash(
+    owner: Address,
+    spender: Address,
+    value: U256,
+    nonce: U256,
+    deadline: U256,
+) -> [u8; 32] {
+    struct_hash(
+        "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+        &[
+            Token::Address(owner),
+            Token::Address(spender),
+            Token::Uint(value),
+            Token::Uint(nonce),
+            Token::Uint(deadline),
+        ],
+    )
+}
+
+// The EIP-712 domain a CappedErc20 at `token` signs to, as a frontend would
+// build it from `name()`, checked against the ones the token reports
+async fn capped_domain(client: &Arc<Client>, token: &Erc20<Client>) -> EIP712Domain {
+    let name = token.name().call().await.unwrap();
+    let chain_id = client.signer().chain_id();
+    let domain = EIP712Domain {
+        name: Some(name.clone()),
+        version: Some("1".into()),
+        chain_id: Some(chain_id.into()),
+        verifying_contract: Some(token.address()),
+        salt: None,
+    };
+    let separator = token.domain_separator().call().await.unwrap();
+    assert_eq!(domain.separator(), separator);
+    let fields = token.eip_712_domain().call().await.unwrap();
+    assert_eq!(
+        fields,
+        (
+            [0x0f],
+            name,
+            "1".into(),
+            chain_id.into(),
+            token.address(),
+            [0; 32],
+            vec![]
+        )
+    );
+    domain
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn permit_approves_once_per_nonce() {
+    let client = devnode().await;
+    // The owner only signs, so it needs no ETH for gas
+    let owner = LocalWallet::new(&mut thread_rng());
+    let spender = Address::random();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    let token = Erc20::new(address, client.clone());
+    let domain = capped_domain(&client, &token).await;
+    let now = client
+        .get_block(BlockNumber::Latest)
+        .await
+        .unwrap()
+        .unwrap()
+        .timestamp;
+    let value = U256::from(10);
+    let permit = |signer: &LocalWallet, nonce: u64, deadline: U256| {
+        let permit = permit_hash(owner.address(), spender, value, U256::from(nonce), deadline);
+        let (v, r, s) = sign_typed(signer, &domain, permit);
+        token.permit(owner.address(), spender, value, deadline, v, r, s)
+    };
+    let revert =
+        |err: ethers::contract::ContractError<Client>| err.decode_contract_revert::<Erc20Errors>();
+
+    assert_eq!(
+        token.nonces(owner.address()).call().await.unwrap(),
+        U256::zero()
+    );
+    let deadline = now + 3600;
+    let receipt = send(permit(&owner, 0, deadline)).await;
+    let approval = parse_log::<erc_20::ApprovalFilter>(receipt.logs[0].clone()).unwrap();
+    assert_eq!(
+        (approval.owner, approval.spender, approval.value),
+        (owner.address(), spender, value)
+    );
+    assert_eq!(
+        token
+            .allowance(owner.address(), spender)
+            .call()
+            .await
+            .unwrap(),
+        value
+    );
+    assert_eq!(
+        token.nonces(owner.address()).call().await.unwrap(),
+        U256::one()
+    );
+
+    // The nonce moved on, so replaying the permit recovers someone else
+    let err = permit(&owner, 0, deadline).call().await.unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::InvalidSigner(_))));
+
+    let err = permit(&owner, 1, now - 1).call().await.unwrap_err();
+    assert!(matches!(
+        revert(err),
+        Some(Erc20Errors::ExpiredSignature(_))
+    ));
+
+    let stranger = LocalWallet::new(&mut thread_rng());
+    let err = permit(&stranger, 1, deadline).call().await.unwrap_err();
+    assert!(matches!(revert(err), Some(Erc20Errors::InvalidSigner(_))));
+
+    // Failed permits leave the nonce where it was
+    assert_eq!(
+        token.nonces(owner.address()).call().await.unwrap(),
+        U256::one()
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn permits_sign_over_the_current_name() {
+    let client = devnode().await;
+    let owner = LocalWallet::new(&mut thread_rng());
+    let spender = Address::random();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    let token = Erc20::new(address, client.clone());
+    let deadline = U256::MAX;
+    let permit = |domain: &EIP712Domain, value: u64, nonce: u64| {
+        let permit = permit_hash(
+            owner.address(),
+            spender,
+            U256::from(value),
+            U256::from(nonce),
+            deadline,
+        );
+        let (v, r, s) = sign_typed(&owner, domain, permit);
+        token.permit(
+            owner.address(),
+            spender,
+            U256::from(value),
+            deadline,
+            v,
+            r,
+            s,
+        )
+    };
+
+    // `init` named the token "Capped", and that is the name permits sign over
+    assert_eq!(token.name().call().await.unwrap(), "Capped");
+    let domain = capped_domain(&client, &token).await;
+    send(permit(&domain, 10, 0)).await;
+    assert_eq!(
+        token
+            .allowance(owner.address(), spender)
+            .call()
+            .await
+            .unwrap(),
+        U256::from(10)
+    );
+
+    // A rename moves the domain, voiding permits signed under the old name
+    let receipt = send(token.set_name("Renamed".into())).await;
+    assert!(receipt
+        .logs
+        .iter()
+        .any(|log| parse_log::<erc_20::Eip712DomainChangedFilter>(log.clone()).is_ok()));
+    let err = permit(&domain, 20, 1).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::InvalidSigner(_))
+    ));
+    let domain = capped_domain(&client, &token).await;
+    send(permit(&domain, 20, 1)).await;
+    assert_eq!(
+        token
+            .allowance(owner.address(), spender)
+            .call()
+            .await
+            .unwrap(),
+        U256::from(20)
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn transfer_with_authorization_spends_each_nonce_once() {
+    let client = devnode().await;
+    // The authorizer only signs, so it needs no ETH for gas
+    let authorizer = LocalWallet::new(&mut thread_rng());
+    let from = authorizer.address();
+    let to = Address::random();
+    let address = deploy_token(&client, 18, U256::from(100)).await;
+    send(CappedErc20::new(address, client.clone()).mint(from, U256::from(100))).await;
+    let token = Erc20::new(address, client.clone());
+    let domain = capped_domain(&client, &token).await;
+    let now = client
+        .get_block(BlockNumber::Latest)
+        .await
+        .unwrap()
+        .unwrap()
+        .timestamp;
+    let hour = U256::from(3600);
+    let authorize = |valid_after: U256, valid_before: U256, nonce: [u8; 32]| {
+        let authorization = struct_hash(
+            "TransferWithAuthorization(address from,address to,uint256 value,uint256 validAfter,uint256 validBefore,bytes32 nonce)",
+            &[
+                Token::Address(from),
+                Token::Address(to),
+                Token::Uint(U256::from(10)),
+                Token::Uint(valid_after),
+                Token::Uint(valid_before),
+                Token::FixedBytes(nonce.to_vec()),
+            ],
+        );
+        let (v, r, s) = sign_typed(&authorizer, &domain, authorization);
+        token.transfer_with_authorization(
+            from,
+            to,
+            U256::from(10),
+            valid_after,
+            valid_before,
+            nonce,
+            v,
+            r,
+            s,
+        )
+    };
+    let revert =
+        |err: ethers::contract::ContractError<Client>| err.decode_contract_revert::<Erc20Errors>();
+
+    let nonce = [1; 32];
+    send(authorize(U256::zero(), now + hour, nonce)).await;
+    assert_eq!(token.balance_of(to).call().await.unwrap(), U256::from(10));
+    assert!(token.authorization_state(from, nonce).call().await.unwrap());
+
+    let err = authorize(U256::zero(), now + hour, nonce).call().await;
+    assert!(matches!(
+        revert(err.unwrap_err()),
+        Some(Erc20Errors::AuthorizationAlreadyUsed(_))
+    ));
+    let err = authorize(now + hour, now + hour * 2, [2; 32]).call().await;
+    assert!(matches!(
+        revert(err.unwrap_err()),
+        Some(Erc20Errors::AuthorizationNotYetValid(_))
+    ));
+    let err = authorize(U256::zero(), now, [3; 32]).call().await;
+    assert!(matches!(
+        revert(err.unwrap_err()),
+        Some(Erc20Errors::AuthorizationExpired(_))
+    ));
+
+    // A signature over different terms recovers someone else
+    let cancel = struct_hash(
+        "CancelAuthorization(address authorizer,bytes32 nonce)",
+        &[Token::Address(from), Token::FixedBytes([4; 32].to_vec())],
+    );
+    let (v, r, s) = sign_typed(&authorizer, &domain, cancel);
+    let err = token
+        .transfer_with_authorization(
+            from,
+            to,
+            U256::from(10),
+            U256::zero(),
+            now + hour,
+            [4; 32],
+            v,
+            r,
+            s,
+        )
+        .call()
+        .await;
+    assert!(matches!(
+        revert(err.unwrap_err()),
+        Some(Erc20Errors::InvalidSigner(_))
+    ));
+
+    // Canceling an unused nonce voids the authorization signed with it
+    send(token.cancel_authorization(from, [4; 32], v, r, s)).await;
+    assert!(token
+        .authorization_state(from, [4; 32])
+        .call()
+        .await
+        .unwrap());
+    let err = authorize(U256::zero(), now + hour, [4; 32]).call().await;
+    assert!(matches!(
+        revert(err.unwrap_err()),
+        Some(Erc20Errors::AuthorizationAlreadyUsed(_))
+    ));
+    assert_eq!(token.balance_of(from).call().await.unwrap(), U256::from(90));
+}
+
+// An Erc721 whose owner, the caller, holds tokens 1 and 2
+async fn deploy_nft(client: &Arc<Client>) -> Erc721<Client> {
+    let nft = Erc721::new(deploy(client, "NFT_TX_DATA").await, client.clone());
+    send(nft.init("Example NFT".into(), "ENFT".into())).await;
+    for id in [1, 2] {
+        send(nft.mint(client.signer().address(), U256::from(id))).await;
+    }
+    nft
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn erc721_transfer_and_approvals() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let nft = deploy_nft(&client).await;
+    let revert =
+        |err: ethers::contract::ContractError<Client>| err.decode_contract_revert::<Erc721Errors>();
+    let err = nft.mint(me, U256::from(1)).call().await.unwrap_err();
+    assert!(matches!(
+        revert(err),
+        Some(Erc721Errors::TokenAlreadyMinted(_))
+    ));
+
+    let other = funded_account(&client).await;
+    let them = other.signer().address();
+    send(nft.transfer_from(me, them, U256::from(1))).await;
+    assert_eq!(nft.owner_of(U256::from(1)).call().await.unwrap(), them);
+    assert_eq!(nft.balance_of(me).call().await.unwrap(), U256::from(1));
+    assert_eq!(nft.balance_of(them).call().await.unwrap(), U256::from(1));
+
+    // Neither a stranger nor the previous holder can move a token
+    let theirs = Erc721::new(nft.address(), other.clone());
+    let err = theirs.transfer_from(me, them, U256::from(2)).call().await;
+    assert!(matches!(
+        revert(err.unwrap_err()),
+        Some(Erc721Errors::NotOwnerOrApproved(_))
+    ));
+    let err = nft.transfer_from(them, me, U256::from(1)).call().await;
+    assert!(matches!(
+        revert(err.unwrap_err()),
+        Some(Erc721Errors::NotOwnerOrApproved(_))
+    ));
+
+    // An approval covers one transfer of one token
+    send(nft.approve(them, U256::from(2))).await;
+    assert_eq!(nft.get_approved(U256::from(2)).call().await.unwrap(), them);
+    send(theirs.transfer_from(me, them, U256::from(2))).await;
+    assert_eq!(
+        nft.get_approved(U256::from(2)).call().await.unwrap(),
+        Address::zero()
+    );
+    let err = nft.owner_of(U256::from(3)).call().await.unwrap_err();
+    assert!(matches!(
+        revert(err),
+        Some(Erc721Errors::NonexistentToken(_))
+    ));
+
+    // An operator moves, and burns, any of the holder's tokens
+    send(theirs.set_approval_for_all(me, true)).await;
+    assert!(nft.is_approved_for_all(them, me).call().await.unwrap());
+    send(nft.transfer_from(them, me, U256::from(1))).await;
+    send(nft.burn(U256::from(2))).await;
+    assert_eq!(nft.balance_of(them).call().await.unwrap(), U256::zero());
+    assert_eq!(nft.balance_of(me).call().await.unwrap(), U256::from(1));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn erc721_safe_transfer_checks_the_receiver() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let nft = deploy_nft(&client).await;
+
+    // Another Erc721 has no onERC721Received to accept the token with
+    let receiver = deploy(&client, "NFT_TX_DATA").await;
+    let err = nft
+        .safe_transfer_from(me, receiver, U256::from(1))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc721Errors>(),
+        Some(Erc721Errors::InvalidReceiver(_))
+    ));
+    assert_eq!(nft.owner_of(U256::from(1)).call().await.unwrap(), me);
+
+    // Accounts without code always accept
+    let other = Address::random();
+    send(nft.safe_transfer_from_with_from_and_to_and_data(
+        me,
+        other,
+        U256::from(1),
+        vec![1, 2].into(),
+    ))
+    .await;
+    assert_eq!(nft.owner_of(U256::from(1)).call().await.unwrap(), other);
+}
+
+// Waits until the votes token's clock has passed `block`, so checkpoints
+// written from now on are taken after it
+async fn clock_past(votes: &VotesErc20<Client>, block: u64) {
+    for _ in 0..120 {
+        if votes.clock().call().await.unwrap() > block {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    panic!("the clock did not move past {block}");
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn votes_follow_delegation_and_are_kept_per_block() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let (other, delegatee) = (Address::random(), Address::random());
+    let address = deploy(&client, "VOTES_TX_DATA").await;
+    let (token, votes) = (
+        Erc20::new(address, client.clone()),
+        VotesErc20::new(address, client.clone()),
+    );
+    send(token.init("Votes".into(), "VOTE".into(), 18)).await;
+    send(token.mint(me, U256::from(100))).await;
+
+    // Balances count once delegated, self-delegation included
+    assert_eq!(votes.get_votes(me).call().await.unwrap(), U256::zero());
+    send(votes.delegate(me)).await;
+    assert_eq!(votes.delegates(me).call().await.unwrap(), me);
+    assert_eq!(votes.get_votes(me).call().await.unwrap(), U256::from(100));
+    send(token.transfer(other, U256::from(30))).await;
+    assert_eq!(votes.get_votes(me).call().await.unwrap(), U256::from(70));
+    let delegated = votes.clock().call().await.unwrap();
+
+    // Re-delegating moves all the power to the new delegate
+    clock_past(&votes, delegated).await;
+    send(votes.delegate(delegatee)).await;
+    assert_eq!(votes.get_votes(me).call().await.unwrap(), U256::zero());
+    assert_eq!(
+        votes.get_votes(delegatee).call().await.unwrap(),
+        U256::from(70)
+    );
+    let redelegated = votes.clock().call().await.unwrap();
+
+    clock_past(&votes, redelegated).await;
+    let past = |account, block: u64| votes.get_past_votes(account, block.into());
+    assert_eq!(past(me, delegated).call().await.unwrap(), U256::from(70));
+    assert_eq!(
+        past(delegatee, delegated).call().await.unwrap(),
+        U256::zero()
+    );
+    assert_eq!(past(me, redelegated).call().await.unwrap(), U256::zero());
+    assert_eq!(
+        past(delegatee, redelegated).call().await.unwrap(),
+        U256::from(70)
+    );
+
+    let now = votes.clock().call().await.unwrap();
+    let err = past(me, now + 1).call().await.unwrap_err();
+    assert!(err.decode_revert::<FutureLookup>().is_some());
+}
+
+// A vesting wallet holding `amount` of a fresh token, on the given schedule
+async fn deploy_vesting(
+    client: &Arc<Client>,
+    beneficiary: Address,
+    amount: U256,
+    (start, cliff, duration): (u64, u64, u64),
+) -> (Erc20<Client>, Vesting<Client>) {
+    let token = deploy_token(client, 18, amount).await;
+    let vesting = Vesting::new(deploy(client, "VESTING_TX_DATA").await, client.clone());
+    send(vesting.init(token, beneficiary, start, cliff, duration)).await;
+    send(CappedErc20::new(token, client.clone()).mint(vesting.address(), amount)).await;
+    (Erc20::new(token, client.clone()), vesting)
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn vesting_releases_linearly_after_the_cliff() {
+    let client = devnode().await;
+    let beneficiary = Address::random();
+    let now = client
+        .get_block(BlockNumber::Latest)
+        .await
+        .unwrap()
+        .unwrap()
+        .timestamp
+        .as_u64();
+    let total = U256::from(1000);
+    let nothing_left = |err: ethers::contract::ContractError<Client>| {
+        matches!(
+            err.decode_contract_revert::<VestingErrors>(),
+            Some(VestingErrors::NothingToRelease(_))
+        )
+    };
+
+    // Started 400s ago, so 40% in and past its 200s cliff
+    let start = now - 400;
+    let (token, vesting) = deploy_vesting(&client, beneficiary, total, (start, 200, 1000)).await;
+    let vested = |at: u64| vesting.vested_amount(start + at);
+    assert_eq!(vested(199).call().await.unwrap(), U256::zero());
+    assert_eq!(vested(200).call().await.unwrap(), U256::from(200));
+    assert_eq!(vested(500).call().await.unwrap(), U256::from(500));
+    assert_eq!(vested(1000).call().await.unwrap(), total);
+    assert_eq!(vested(5000).call().await.unwrap(), total);
+
+    let releasable = vesting.releasable().call().await.unwrap();
+    assert!(releasable >= U256::from(400) && releasable < U256::from(500));
+    send(vesting.release()).await;
+    let released = vesting.released().call().await.unwrap();
+    assert!(released >= releasable && released < U256::from(500));
+    assert_eq!(
+        token.balance_of(beneficiary).call().await.unwrap(),
+        released
+    );
+    // Paying out doesn't change what has vested
+    assert_eq!(vested(500).call().await.unwrap(), U256::from(500));
+
+    // Before the cliff nothing is releasable, though some time has passed
+    let (_, early) = deploy_vesting(&client, beneficiary, total, (now - 100, 200, 1000)).await;
+    assert_eq!(early.releasable().call().await.unwrap(), U256::zero());
+    assert!(nothing_left(early.release().call().await.unwrap_err()));
+
+    // Once over, everything is releasable and a second release finds nothing
+    let (token, done) = deploy_vesting(&client, beneficiary, total, (now - 2000, 200, 1000)).await;
+    assert_eq!(done.releasable().call().await.unwrap(), total);
+    send(done.release()).await;
+    assert_eq!(token.balance_of(beneficiary).call().await.unwrap(), total);
+    assert_eq!(done.releasable().call().await.unwrap(), U256::zero());
+    assert!(nothing_left(done.release().call().await.unwrap_err()));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn vesting_init_rejects_bad_schedules() {
+    let client = devnode().await;
+    let token = deploy_token(&client, 18, U256::from(1)).await;
+    let vesting = Vesting::new(deploy(&client, "VESTING_TX_DATA").await, client.clone());
+    let me = client.signer().address();
+    let reverted = |err: ethers::contract::ContractError<Client>| {
+        err.decode_contract_revert::<VestingErrors>().unwrap()
+    };
+
+    let err = vesting.init(token, me, 0, 11, 10).call().await.unwrap_err();
+    assert!(matches!(reverted(err), VestingErrors::InvalidSchedule(_)));
+    let err = vesting
+        .init(token, me, u64::MAX, 0, 1)
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(reverted(err), VestingErrors::InvalidSchedule(_)));
+    let err = vesting
+        .init(Address::random(), me, 0, 0, 10)
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(reverted(err), VestingErrors::InvalidToken(_)));
+
+    send(vesting.init(token, me, 0, 0, 10)).await;
+    let err = vesting.init(token, me, 0, 0, 10).call().await.unwrap_err();
+    assert!(matches!(
+        reverted(err),
+        VestingErrors::AlreadyInitialized(_)
+    ));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn faucet_drips_once_per_cooldown_until_empty() {
+    let client = devnode().await;
+    let address = deploy_token(&client, 18, U256::from(15)).await;
+    send(CappedErc20::new(address, client.clone()).mint(client.signer().address(), 15.into()))
+        .await;
+    let token = Erc20::new(address, client.clone());
+    let faucet = Faucet::new(deploy(&client, "FAUCET_TX_DATA").await, client.clone());
+    send(faucet.init(address, U256::from(10), U256::from(3600))).await;
+    send(token.approve(faucet.address(), U256::from(15))).await;
+    send(faucet.refill(U256::from(15))).await;
+    assert_eq!(
+        token.balance_of(faucet.address()).call().await.unwrap(),
+        U256::from(15)
+    );
+    let reverted = |err: ethers::contract::ContractError<Client>| {
+        err.decode_contract_revert::<FaucetErrors>().unwrap()
+    };
+
+    let tester = funded_account(&client).await;
+    let me = tester.signer().address();
+    let as_tester = Faucet::new(faucet.address(), tester.clone());
+    assert_eq!(
+        as_tester.next_claim_at(me).call().await.unwrap(),
+        U256::zero()
+    );
+    let receipt = send(as_tester.claim()).await;
+    assert_eq!(token.balance_of(me).call().await.unwrap(), U256::from(10));
+    let claimed_at = client
+        .get_block(receipt.block_number.unwrap())
+        .await
+        .unwrap()
+        .unwrap()
+        .timestamp;
+    assert_eq!(
+        as_tester.next_claim_at(me).call().await.unwrap(),
+        claimed_at + 3600
+    );
+
+    // A second claim within the hour is too soon
+    let err = as_tester.claim().call().await.unwrap_err();
+    assert!(matches!(reverted(err), FaucetErrors::ClaimTooSoon(_)));
+
+    // Another tester finds only 5 of the 10 left
+    let other = Faucet::new(faucet.address(), funded_account(&client).await);
+    let err = other.claim().call().await.unwrap_err();
+    match reverted(err) {
+        FaucetErrors::FaucetEmpty(empty) => {
+            assert_eq!(empty.balance, U256::from(5));
+            assert_eq!(empty.drip_amount, U256::from(10));
+        }
+        err => panic!("unexpected revert {err:?}"),
+    }
+    send(faucet.set_drip_amount(U256::from(5))).await;
+    send(other.claim()).await;
+
+    // Only the owner changes the settings
+    let err = as_tester
+        .set_drip_amount(U256::from(1))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        reverted(err),
+        FaucetErrors::UnauthorizedAccount(_)
+    ));
+}
+
+// A wrapper over a fresh underlying token, of which the caller holds `supply`
+// and has approved the wrapper for all of it
+async fn deploy_wrapper(client: &Arc<Client>, supply: U256) -> (Erc20<Client>, Wrapper<Client>) {
+    let underlying = deploy_token(client, 6, supply).await;
+    send(CappedErc20::new(underlying, client.clone()).mint(client.signer().address(), supply))
+        .await;
+    let wrapper = Wrapper::new(deploy(client, "WRAPPER_TX_DATA").await, client.clone());
+    send(wrapper.init("Wrapped Capped".into(), "WCAP".into(), underlying)).await;
+    let underlying = Erc20::new(underlying, client.clone());
+    send(underlying.approve(wrapper.address(), U256::MAX)).await;
+    (underlying, wrapper)
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn wrapper_deposits_and_withdraws_one_to_one() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let (underlying, wrapper) = deploy_wrapper(&client, U256::from(1000)).await;
+    let wrapped = Erc20::new(wrapper.address(), client.clone());
+    assert_eq!(wrapped.decimals().call().await.unwrap(), 6);
+    assert_eq!(
+        wrapper.underlying().call().await.unwrap(),
+        underlying.address()
+    );
+
+    send(wrapper.deposit(U256::from(300))).await;
+    assert_eq!(
+        wrapped.balance_of(me).call().await.unwrap(),
+        U256::from(300)
+    );
+    assert_eq!(
+        underlying.balance_of(me).call().await.unwrap(),
+        U256::from(700)
+    );
+    assert_eq!(
+        underlying
+            .balance_of(wrapper.address())
+            .call()
+            .await
+            .unwrap(),
+        U256::from(300)
+    );
+
+    let receipt = send(wrapper.withdraw(U256::from(120))).await;
+    let withdrawn = receipt
+        .logs
+        .iter()
+        .find_map(|log| parse_log::<WithdrawnFilter>(log.clone()).ok())
+        .unwrap();
+    assert_eq!((withdrawn.account, withdrawn.amount), (me, U256::from(120)));
+    assert_eq!(
+        wrapped.balance_of(me).call().await.unwrap(),
+        U256::from(180)
+    );
+    assert_eq!(
+        underlying.balance_of(me).call().await.unwrap(),
+        U256::from(820)
+    );
+    assert!(wrapper.is_fully_collateralized().call().await.unwrap());
+
+    // Wrapper tokens are only minted against deposits, and only burned against withdrawals
+    let err = wrapper.withdraw(U256::from(181)).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::InsufficientBalance(_))
+    ));
+    let err = wrapped.mint(me, U256::from(1)).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WrapperErrors>(),
+        Some(WrapperErrors::MintDisabled(_))
+    ));
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn wrapper_recovers_only_the_surplus_underlying() {
+    let client = devnode().await;
+    let (underlying, wrapper) = deploy_wrapper(&client, U256::from(1000)).await;
+    let collector = Address::random();
+    send(wrapper.deposit(U256::from(400))).await;
+
+    // Nothing to recover while the underlying exactly backs the supply
+    let err = wrapper.recover(collector).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WrapperErrors>(),
+        Some(WrapperErrors::NothingToRecover(_))
+    ));
+
+    // Tokens sent straight to the wrapper mint nothing, and can be recovered
+    send(underlying.transfer(wrapper.address(), U256::from(25))).await;
+    let other = Wrapper::new(wrapper.address(), funded_account(&client).await);
+    let err = other.recover(collector).call().await.unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<Erc20Errors>(),
+        Some(Erc20Errors::UnauthorizedAccount(_))
+    ));
+    assert_eq!(
+        wrapper.recover(collector).call().await.unwrap(),
+        U256::from(25)
+    );
+    send(wrapper.recover(collector)).await;
+    assert_eq!(
+        underlying.balance_of(collector).call().await.unwrap(),
+        U256::from(25)
+    );
+    assert_eq!(
+        underlying
+            .balance_of(wrapper.address())
+            .call()
+            .await
+            .unwrap(),
+        U256::from(400)
+    );
+    assert!(wrapper.is_fully_collateralized().call().await.unwrap());
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn wrapper_refuses_to_rescue_its_underlying() {
+    let client = devnode().await;
+    let me = client.signer().address();
+    let (underlying, wrapper) = deploy_wrapper(&client, U256::from(1000)).await;
+    send(wrapper.deposit(U256::from(400))).await;
+
+    // The deposits back the supply, so only `recover` may move the underlying
+    let err = wrapper
+        .rescue_tokens(underlying.address(), me, U256::from(400))
+        .call()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.decode_contract_revert::<WrapperErrors>(),
+        Some(WrapperErrors::CannotRescueUnderlying(_))
+    ));
+    assert!(wrapper.is_fully_collateralized().call().await.unwrap());
+
+    // Other tokens sent to the wrapper by mistake can still be rescued
+    let stray = deploy_token(&client, 18, U256::from(10)).await;
+    send(CappedErc20::new(stray, client.clone()).mint(wrapper.address(), U256::from(10))).await;
+    send(wrapper.rescue_tokens(stray, me, U256::from(10))).await;
+    let stray = Erc20::new(stray, client.clone());
+    assert_eq!(stray.balance_of(me).call().await.unwrap(), U256::from(10));
+}
+
+/// How far above its baseline a method's gas may go before it counts as a regression.
+const GAS_TOLERANCE_PERCENT: u64 = 5;
+
+fn gas_baseline_path() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/gas_baseline.json")
+}
+
+#[tokio::test]
+#[ignore = "needs a devnode, see the module docs"]
+async fn gas_used_stays_within_the_baseline() {
+    let client = devnode().await;
+    let weth = Weth::new(deploy(&client, "WETH_TX_DATA").await, client.clone());
+    send(weth.init("Wrapped Ether".into(), "WETH".into(), 18)).await;
+    let gas_used = |receipt: TransactionReceipt| receipt.gas_used.unwrap().as_u64();
+
+    // A fixed sequence on a fresh program, so every run measures the same storage writes
+    let wad = parse_ether("1").unwrap();
+    let mut measured = BTreeMap::new();
+    measured.insert("deposit", gas_used(send(weth.deposit().value(wad)).await));
+    let transfer = weth.transfer(Address::repeat_byte(0x22), wad / 4);
+    measured.insert("transfer", gas_used(send(transfer).await));
+    measured.insert("withdraw", gas_used(send(weth.withdraw(wad / 4)).await));
+    let sum = weth
+        .sum(vec![U256::one(); 64])
+        .estimate_gas()
+        .await
+        .unwrap();
+    measured.insert("sum", sum.as_u64());
+
+    let path = gas_baseline_path();
+    if std::env::var_os("UPDATE_GAS_BASELINE").is_some() {
+        let json = serde_json::to_string_pretty(&measured).unwrap();
+        std::fs::write(&path, json + "\n").unwrap();
+        return;
+    }
+    let baseline: BTreeMap<String, u64> =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let mut regressions = Vec::new();
+    for (method, gas) in measured {
+        let Some(&expected) = baseline.get(method) else {
+            regressions.push(format!("{method}: {gas} gas, but no baseline"));
+            continue;
+        };
+        if gas * 100 > expected * (100 + GAS_TOLERANCE_PERCENT) {
+            regressions.push(format!("{method}: {gas} gas, baseline {expected}"));
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "gas above the baseline by more than {GAS_TOLERANCE_PERCENT}%, \
+         rerun with UPDATE_GAS_BASELINE=1 if intended:\n{}",
+        regressions.join("\n")
+    );
+}
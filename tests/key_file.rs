@@ -0,0 +1,56 @@
+//! Reading private keys with `read_secret_from_file`, from the key files in
+//! `tests/fixtures`.
+
+use ethers::signers::Signer;
+use stylus_hello_world::client::{read_secret_from_file, read_wallet_from_file, ClientError};
+
+const KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+fn invalid_reason(path: &str) -> &'static str {
+    match read_secret_from_file(path) {
+        Err(ClientError::InvalidKeyFile(reason)) => reason,
+        other => panic!("expected InvalidKeyFile, got {other:?}"),
+    }
+}
+
+#[test]
+fn a_bare_key_is_read_without_its_newline() {
+    assert_eq!(
+        read_secret_from_file("tests/fixtures/key.txt").unwrap(),
+        KEY
+    );
+}
+
+#[test]
+fn the_0x_prefix_is_stripped() {
+    assert_eq!(
+        read_secret_from_file("tests/fixtures/key_0x.txt").unwrap(),
+        KEY
+    );
+    // Both spellings load the same wallet
+    let bare = read_wallet_from_file("tests/fixtures/key.txt").unwrap();
+    let prefixed = read_wallet_from_file("tests/fixtures/key_0x.txt").unwrap();
+    assert_eq!(bare.address(), prefixed.address());
+}
+
+#[test]
+fn a_short_key_is_refused() {
+    assert_eq!(
+        invalid_reason("tests/fixtures/key_short.txt"),
+        "private key must be 32 bytes of hex"
+    );
+}
+
+#[test]
+fn a_second_key_line_is_refused() {
+    assert_eq!(
+        invalid_reason("tests/fixtures/key_two_lines.txt"),
+        "key file must contain exactly one non-empty line"
+    );
+}
+
+#[test]
+fn a_missing_file_is_a_read_error() {
+    let err = read_secret_from_file("tests/fixtures/no_such_key.txt").unwrap_err();
+    assert!(matches!(err, ClientError::KeyFileRead(_)));
+}
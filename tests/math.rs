@@ -0,0 +1,84 @@
+//! Property tests of `stylus_hello_world::math` against 512-bit reference
+//! arithmetic, in which none of the operations can overflow.
+
+use alloy_primitives::{U256, U512};
+use proptest::prelude::*;
+use stylus_hello_world::math::{self, MathError};
+
+// Cover small values as well, which random 256-bit ones almost never are
+fn amount() -> impl Strategy<Value = U256> {
+    prop_oneof![
+        any::<[u64; 4]>().prop_map(U256::from_limbs),
+        any::<u128>().prop_map(U256::from),
+        (0u64..=10_000).prop_map(U256::from),
+        Just(U256::ZERO),
+        Just(U256::MAX),
+    ]
+}
+
+fn wide(value: U256) -> U512 {
+    U512::from(value)
+}
+
+// The reference result, if it fits in 256 bits
+fn narrow(value: U512) -> Option<U256> {
+    (value <= wide(U256::MAX)).then(|| U256::from_limbs_slice(&value.as_limbs()[..4]))
+}
+
+proptest! {
+    #[test]
+    fn add_matches_wide_math(a in amount(), b in amount()) {
+        match (math::add(a, b), narrow(wide(a) + wide(b))) {
+            (Ok(sum), Some(expected)) => prop_assert_eq!(sum, expected),
+            (Err(MathError::Overflow(_)), None) => {}
+            _ => prop_assert!(false, "add({}, {}) disagrees", a, b),
+        }
+    }
+
+    #[test]
+    fn sub_matches_wide_math(a in amount(), b in amount()) {
+        match math::sub(a, b) {
+            Ok(difference) => {
+                prop_assert!(a >= b);
+                prop_assert_eq!(wide(difference) + wide(b), wide(a));
+            }
+            Err(MathError::Underflow(_)) => prop_assert!(a < b),
+            Err(_) => prop_assert!(false, "sub({}, {}) failed with the wrong error", a, b),
+        }
+    }
+
+    #[test]
+    fn mul_div_matches_wide_math(a in amount(), b in amount(), denominator in amount()) {
+        let result = math::mul_div(a, b, denominator);
+        if denominator == U256::ZERO {
+            prop_assert!(matches!(result, Err(MathError::DivisionByZero(_))));
+            return Ok(());
+        }
+        match (result, narrow(wide(a) * wide(b) / wide(denominator))) {
+            (Ok(quotient), Some(expected)) => prop_assert_eq!(quotient, expected),
+            (Err(MathError::Overflow(_)), None) => {}
+            _ => prop_assert!(false, "mul_div({}, {}, {}) disagrees", a, b, denominator),
+        }
+    }
+
+    #[test]
+    fn mul_div_up_matches_wide_math(a in amount(), b in amount(), denominator in amount()) {
+        prop_assume!(denominator != U256::ZERO);
+        let denominator_wide = wide(denominator);
+        let rounded_up = (wide(a) * wide(b) + denominator_wide - U512::from(1)) / denominator_wide;
+        match (math::mul_div_up(a, b, denominator), narrow(rounded_up)) {
+            (Ok(quotient), Some(expected)) => prop_assert_eq!(quotient, expected),
+            (Err(MathError::Overflow(_)), None) => {}
+            _ => prop_assert!(false, "mul_div_up({}, {}, {}) disagrees", a, b, denominator),
+        }
+    }
+}
+
+#[test]
+fn mul_div_keeps_the_full_product() {
+    // U256::MAX * 3 overflows 256 bits, but the quotient fits
+    let quotient = math::mul_div(U256::MAX, U256::from(3), U256::from(4));
+    assert!(
+        matches!(quotient, Ok(q) if q == U256::MAX / U256::from(4) * U256::from(3) + U256::from(2))
+    );
+}
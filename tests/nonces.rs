@@ -0,0 +1,85 @@
+//! `SharedNonceManager` handing out nonces to concurrent senders.
+
+use ethers::{
+    providers::{MockProvider, Provider},
+    types::{Address, H256, U256},
+};
+use std::sync::Arc;
+use stylus_hello_world::bindings::Erc20;
+use stylus_hello_world::client::{NonceManager, SharedNonceManager};
+use tokio::task::JoinSet;
+
+// Without gas or fees to fill in, each send is a single eth_sendTransaction
+fn token(count: u64) -> (Erc20<Provider<MockProvider>>, MockProvider) {
+    let (provider, mock) = Provider::mocked();
+    for i in 0..count {
+        mock.push(H256::from_low_u64_be(i)).unwrap();
+    }
+    let provider = provider.with_sender(Address::repeat_byte(0x01));
+    (
+        Erc20::new(Address::repeat_byte(0x70), Arc::new(provider)),
+        mock,
+    )
+}
+
+// A manager whose account has `pending` transactions so far
+async fn shared_from(pending: u64) -> SharedNonceManager {
+    let (provider, mock) = Provider::mocked();
+    mock.push(U256::from(pending)).unwrap();
+    let nonces = NonceManager::fetch(&provider, Address::zero())
+        .await
+        .unwrap();
+    SharedNonceManager::new(nonces)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_sends_get_consecutive_nonces() {
+    let count = 50;
+    let (token, _mock) = token(count);
+    // The account's pending transaction count
+    let nonces = shared_from(7).await;
+
+    let mut tasks = JoinSet::new();
+    for _ in 0..count {
+        let (token, nonces) = (token.clone(), nonces.clone());
+        tasks.spawn(async move {
+            let call = token
+                .transfer(Address::repeat_byte(0x02), U256::one())
+                .gas(50_000)
+                .gas_price(1);
+            nonces.send(call).await.unwrap()
+        });
+    }
+    let mut sent = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        sent.push(result.unwrap());
+    }
+
+    // No nonce is given out twice or skipped, and the node saw them in order:
+    // the mock answers the last pushed hash first, so the nth send gets hash
+    // count - 1 - n
+    sent.sort();
+    for (i, (nonce, hash)) in sent.into_iter().enumerate() {
+        assert_eq!(nonce, U256::from(7 + i));
+        assert_eq!(hash, H256::from_low_u64_be(count - 1 - i as u64));
+    }
+    assert_eq!(nonces.next().await, U256::from(7 + count));
+}
+
+#[tokio::test]
+async fn a_failed_send_gives_its_nonce_back() {
+    // Nothing queued, so the first send fails
+    let (token, mock) = token(0);
+    let nonces = shared_from(5).await;
+    let transfer = || {
+        token
+            .transfer(Address::repeat_byte(0x02), U256::one())
+            .gas(50_000)
+            .gas_price(1)
+    };
+    assert!(nonces.send(transfer()).await.is_err());
+    assert_eq!(nonces.next().await, U256::from(5));
+    mock.push(H256::repeat_byte(0xaa)).unwrap();
+    let (nonce, _) = nonces.send(transfer()).await.unwrap();
+    assert_eq!(nonce, U256::from(5));
+}
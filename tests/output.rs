@@ -0,0 +1,52 @@
+//! The JSON object the erc20 example prints under `--output json`.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::{json_output, Erc20Report};
+use ethers::types::{TransactionReceipt, H256, U256};
+use serde_json::json;
+
+#[test]
+fn a_run_serializes_its_results() {
+    let mut report = Erc20Report {
+        decimals: Some(18),
+        sum: Some("16".into()),
+        sum_tag: Some("sum".into()),
+        ..Default::default()
+    };
+    let receipt = TransactionReceipt {
+        transaction_hash: H256::repeat_byte(0xab),
+        gas_used: Some(U256::from(52_000)),
+        ..Default::default()
+    };
+    report.record_tx(&receipt);
+
+    let hash = format!("0x{}", "ab".repeat(32));
+    assert_eq!(
+        json_output(&Ok(report)),
+        json!({
+            "decimals": 18,
+            "sum": "16",
+            "sum_tag": "sum",
+            "tx_hash": hash,
+            "gas_used": "52000",
+        })
+    );
+
+    // Whatever didn't run is null rather than missing
+    let empty = json_output(&Ok(Erc20Report::default()));
+    let keys: Vec<_> = empty.as_object().unwrap().keys().cloned().collect();
+    assert_eq!(keys, ["decimals", "gas_used", "sum", "sum_tag", "tx_hash"]);
+    assert!(empty.as_object().unwrap().values().all(|v| v.is_null()));
+}
+
+#[test]
+fn a_failed_run_serializes_its_error_chain() {
+    let err = eyre::eyre!("connection refused").wrap_err("could not reach the node");
+    let output = json_output::<Erc20Report>(&Err(err));
+    assert_eq!(
+        output,
+        json!({ "error": "could not reach the node: connection refused" })
+    );
+}
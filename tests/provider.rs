@@ -0,0 +1,37 @@
+//! `check_provider` against mocked nodes.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::check_provider;
+use ethers::{providers::Provider, types::U64};
+
+#[tokio::test]
+async fn an_unreachable_endpoint_is_reported() {
+    // With nothing queued, every request fails as a down endpoint would
+    let (provider, _mock) = Provider::mocked();
+
+    let err = check_provider(&provider).await.unwrap_err();
+    assert!(
+        err.to_string().starts_with("RPC endpoint is unreachable"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn a_node_at_genesis_is_refused() {
+    let (provider, mock) = Provider::mocked();
+    // Responses pop from the back: net_version first, then eth_blockNumber
+    mock.push::<U64, _>(U64::zero()).unwrap();
+    mock.push::<String, _>("412346".to_string()).unwrap();
+
+    let err = check_provider(&provider).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "RPC endpoint (network 412346) is at block 0; is the node synced?"
+    );
+
+    mock.push::<U64, _>(U64::from(7)).unwrap();
+    mock.push::<String, _>("412346".to_string()).unwrap();
+    check_provider(&provider).await.unwrap();
+}
@@ -0,0 +1,78 @@
+//! `decode_revert` on revert payloads as a node would return them.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::decode_revert;
+use ethers::{
+    abi::{encode, Token},
+    contract::ContractError,
+    providers::{MockProvider, Provider},
+    types::{Address, Bytes, U256},
+    utils::keccak256,
+};
+
+type Error = ContractError<Provider<MockProvider>>;
+
+// The revert data of the error `signature` with `params`
+fn revert(signature: &str, params: &[Token]) -> Error {
+    let mut data = keccak256(signature)[..4].to_vec();
+    data.extend(encode(params));
+    ContractError::Revert(Bytes::from(data))
+}
+
+#[test]
+fn custom_errors_are_named_with_their_fields() {
+    let from = Address::repeat_byte(0x11);
+    let err = revert(
+        "InsufficientBalance(address,uint256,uint256)",
+        &[
+            Token::Address(from),
+            Token::Uint(5.into()),
+            Token::Uint(U256::exp10(18)),
+        ],
+    );
+    assert_eq!(
+        decode_revert(&err).unwrap(),
+        format!("InsufficientBalance(from: {from:?}, have: 5, want: 1000000000000000000)")
+    );
+    assert_eq!(
+        decode_revert(&revert("Overflow()", &[])).unwrap(),
+        "Overflow()"
+    );
+
+    // Errors of the other programs are known too
+    let err = revert(
+        "ExceededCap(uint256,uint256)",
+        &[Token::Uint(11.into()), Token::Uint(10.into())],
+    );
+    assert_eq!(
+        decode_revert(&err).unwrap(),
+        "ExceededCap(increasedSupply: 11, cap: 10)"
+    );
+    let err = revert(
+        "AuthorizationAlreadyUsed(address,bytes32)",
+        &[Token::Address(from), Token::FixedBytes(vec![0xab; 32])],
+    );
+    assert!(decode_revert(&err)
+        .unwrap()
+        .ends_with(&format!("nonce: 0x{})", "ab".repeat(32))));
+}
+
+#[test]
+fn other_reverts_fall_back_to_the_message_or_hex() {
+    let err = revert("Error(string)", &[Token::String("not allowed".into())]);
+    assert_eq!(decode_revert(&err).unwrap(), "not allowed");
+
+    let err: Error = ContractError::Revert(Bytes::from(vec![0xde, 0xad, 0xbe, 0xef, 0x01]));
+    assert_eq!(decode_revert(&err).unwrap(), "unknown error 0xdeadbeef01");
+
+    // A known selector whose data doesn't decode isn't passed off as that error
+    let err: Error = ContractError::Revert(Bytes::from(
+        keccak256("InvalidReceiver(address)")[..4].to_vec(),
+    ));
+    assert!(decode_revert(&err).unwrap().starts_with("unknown error 0x"));
+
+    let err: Error = ContractError::ContractNotDeployed;
+    assert!(decode_revert(&err).is_none());
+}
@@ -0,0 +1,57 @@
+//! The `sign_tx` command's raw transactions.
+
+use ethers::{
+    abi::AbiDecode,
+    types::{transaction::eip2718::TypedTransaction, Address, Bytes, U256},
+    utils::{parse_ether, rlp::Rlp},
+};
+use std::process::Command;
+use stylus_hello_world::bindings::erc_20::TransferCall;
+
+// A well-known development key and its account
+const KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const SENDER: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+const TOKEN: &str = "0xC4CA13280b8EafD7A033670E620B1AF74950E147";
+const TO: &str = "0x3f1Eae7D46d88F08fc2F8ed27FCb2AB183EB2d0E";
+
+#[test]
+fn a_signed_transfer_recovers_to_the_key_holder() {
+    let key_path = std::env::temp_dir().join(format!("sign_tx_{}.pri", std::process::id()));
+    std::fs::write(&key_path, KEY).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_sign_tx"))
+        .env_remove("STYLUS_PROGRAM_ADDRESS")
+        .arg("--priv-key-path")
+        .arg(&key_path)
+        .args(["--program-address", TOKEN, "--to", TO, "--amount", "12.5"])
+        .args([
+            "--nonce",
+            "7",
+            "--gas-limit",
+            "200000",
+            "--max-fee-per-gas",
+            "0.2",
+        ])
+        .args(["--chain-id", "421614"])
+        .output()
+        .unwrap();
+    std::fs::remove_file(&key_path).unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let raw: Bytes = String::from_utf8(output.stdout)
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap();
+    let (tx, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+    let sender: Address = SENDER.parse().unwrap();
+    assert_eq!(signature.recover(tx.sighash()).unwrap(), sender);
+
+    assert!(matches!(tx, TypedTransaction::Eip1559(_)));
+    assert_eq!(tx.chain_id(), Some(421614.into()));
+    assert_eq!(tx.nonce(), Some(&U256::from(7)));
+    assert_eq!(tx.gas(), Some(&U256::from(200_000)));
+    assert_eq!(tx.to_addr(), Some(&TOKEN.parse().unwrap()));
+    let call = TransferCall::decode(tx.data().unwrap()).unwrap();
+    assert_eq!(call.to, TO.parse::<Address>().unwrap());
+    assert_eq!(call.value, parse_ether("12.5").unwrap());
+}
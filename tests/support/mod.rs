@@ -0,0 +1,38 @@
+//! Helpers shared by the test files that include this module.
+
+// Each test file uses only some of these helpers
+#![allow(dead_code)]
+
+use ethers::{
+    providers::Middleware,
+    types::{Address, I256},
+};
+use std::future::Future;
+use stylus_hello_world::bindings::Erc20;
+
+/// Runs `op` and asserts that it moved `account`'s `token` balance by
+/// `expected`, negative for a decrease. Returns what `op` returned.
+pub async fn assert_balance_delta<M, T, F>(
+    token: &Erc20<M>,
+    account: Address,
+    expected: impl Into<I256>,
+    op: impl FnOnce() -> F,
+) -> T
+where
+    M: Middleware + 'static,
+    F: Future<Output = T>,
+{
+    let balance = || async {
+        let balance = token.balance_of(account).call().await.unwrap();
+        I256::try_from(balance).expect("balance exceeds I256::MAX")
+    };
+    let before = balance().await;
+    let output = op().await;
+    let delta = balance().await - before;
+    assert_eq!(
+        delta,
+        expected.into(),
+        "balance of {account:?} moved by {delta}"
+    );
+    output
+}
@@ -0,0 +1,24 @@
+//! `transfers_in_range` against a mocked node.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::transfers_in_range;
+use ethers::{
+    providers::Provider,
+    types::{Address, Log},
+};
+use std::sync::Arc;
+use stylus_hello_world::bindings::Erc20;
+
+#[tokio::test]
+async fn an_empty_range_has_no_transfers() {
+    let (provider, mock) = Provider::mocked();
+    let token = Erc20::new(Address::repeat_byte(0x70), Arc::new(provider));
+    mock.push::<Vec<Log>, _>(Vec::new()).unwrap();
+
+    let transfers = transfers_in_range(&token, 100, 200).await.unwrap();
+    assert!(transfers.is_empty());
+    let filter = token.transfer_filter().from_block(100).to_block(200).filter;
+    mock.assert_request("eth_getLogs", [filter]).unwrap();
+}
@@ -0,0 +1,46 @@
+//! `format_units` and `parse_units`, the examples' conversions between
+//! decimal strings and base units.
+
+#[path = "../examples/common.rs"]
+mod common;
+
+use common::{format_units, parse_units};
+use ethers::types::U256;
+
+#[test]
+fn decimals_round_trip_through_base_units() {
+    for (input, decimals) in [("1.5", 18), ("0.000001", 6), ("123", 6), ("0", 18)] {
+        let value = parse_units(input, decimals).unwrap();
+        assert_eq!(format_units(value, decimals), input);
+    }
+    assert_eq!(
+        parse_units("1.5", 18).unwrap(),
+        U256::from(1_500_000_000_000_000_000u64)
+    );
+    let max = format_units(U256::MAX, 18);
+    assert_eq!(parse_units(&max, 18).unwrap(), U256::MAX);
+
+    // Trailing zeros are dropped, so formatting gives the shortest spelling
+    let value = parse_units("2.50", 6).unwrap();
+    assert_eq!(value, U256::from(2_500_000));
+    assert_eq!(format_units(value, 6), "2.5");
+}
+
+#[test]
+fn more_fractional_digits_than_decimals_are_refused() {
+    let err = parse_units("1.0000001", 6).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "\"1.0000001\" has more than 6 fractional digits"
+    );
+    assert!(parse_units("1.000001", 6).is_ok());
+}
+
+#[test]
+fn zero_decimals_take_whole_numbers_only() {
+    assert_eq!(parse_units("42", 0).unwrap(), U256::from(42));
+    assert_eq!(format_units(U256::from(42), 0), "42");
+    assert!(parse_units("4.2", 0).is_err());
+    // An empty fraction adds no digits
+    assert_eq!(parse_units("42.", 0).unwrap(), U256::from(42));
+}
@@ -0,0 +1,84 @@
+//! `verify_deployment` against a mocked node.
+
+use ethers::{
+    abi::{encode, Token},
+    providers::{MockProvider, Provider},
+    types::{Address, Bytes, U256},
+};
+use std::sync::Arc;
+use stylus_hello_world::client::{verify_deployment, Check, ExpectedToken};
+
+fn expected() -> ExpectedToken {
+    ExpectedToken {
+        decimals: 18,
+        total_supply: U256::from(1_000),
+        weth: true,
+    }
+}
+
+// Queues the node's answers to the checks, in the order they are made
+fn answer(mock: &MockProvider, replies: Vec<Bytes>) {
+    for reply in replies.into_iter().rev() {
+        mock.push::<Bytes, _>(reply).unwrap();
+    }
+}
+
+fn returns(token: Token) -> Bytes {
+    encode(&[token]).into()
+}
+
+fn token_replies(supply: u64, collateralized: bool) -> Vec<Bytes> {
+    vec![
+        Bytes::from(vec![0xef, 0xf0, 0x00]),
+        returns(Token::Uint(18.into())),
+        returns(Token::String("Wrapped Ether".into())),
+        returns(Token::String("WETH".into())),
+        returns(Token::Uint(supply.into())),
+        returns(Token::Bool(collateralized)),
+    ]
+}
+
+fn failed(checks: &[Check]) -> Vec<&'static str> {
+    checks
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| c.name)
+        .collect()
+}
+
+#[tokio::test]
+async fn a_sound_deployment_passes_every_check() {
+    let (provider, mock) = Provider::mocked();
+    answer(&mock, token_replies(1_000, true));
+
+    let checks = verify_deployment(Arc::new(provider), Address::repeat_byte(1), &expected())
+        .await
+        .unwrap();
+    assert_eq!(checks.len(), 6);
+    assert!(failed(&checks).is_empty(), "{checks:?}");
+    assert_eq!(checks[2].detail, "\"Wrapped Ether\"");
+}
+
+#[tokio::test]
+async fn each_broken_invariant_fails_its_own_check() {
+    let (provider, mock) = Provider::mocked();
+    answer(&mock, token_replies(999, false));
+
+    let checks = verify_deployment(Arc::new(provider), Address::repeat_byte(1), &expected())
+        .await
+        .unwrap();
+    assert_eq!(failed(&checks), ["total supply", "fully collateralized"]);
+    assert_eq!(checks[4].detail, "999, expected 1000");
+}
+
+#[tokio::test]
+async fn an_address_without_code_fails_only_that_check() {
+    let (provider, mock) = Provider::mocked();
+    answer(&mock, vec![Bytes::new()]);
+
+    let checks = verify_deployment(Arc::new(provider), Address::repeat_byte(1), &expected())
+        .await
+        .unwrap();
+    assert_eq!(failed(&checks), ["code present"]);
+    assert_eq!(checks.len(), 1);
+}